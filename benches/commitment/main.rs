@@ -0,0 +1,35 @@
+use std::num::NonZeroUsize;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use ff::Field;
+use halo2curves::bn256;
+use rand_core::OsRng;
+use sirius::commitment::CommitmentKey;
+
+const K: usize = 18;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let ck = CommitmentKey::<bn256::G1Affine>::setup(K, b"commitment_bench");
+    let v = (0..1 << K)
+        .map(|_| bn256::Fr::random(OsRng))
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("commit_with_window");
+    group.sample_size(10);
+
+    for window_bits in [None, Some(8), Some(12), Some(16)] {
+        let window_bits = window_bits.and_then(NonZeroUsize::new);
+        let id = window_bits.map_or("best_multiexp".to_owned(), |w| w.to_string());
+
+        group.bench_with_input(
+            BenchmarkId::new("k=18", id),
+            &window_bits,
+            |b, window_bits| b.iter(|| ck.commit_with_window(black_box(&v), *window_bits).unwrap()),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,33 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use ff::Field;
+use halo2curves::bn256;
+use rand_core::OsRng;
+use sirius::plonk::{PlonkInstance, RelaxedPlonkInstance};
+
+type Curve = bn256::G1Affine;
+type Scalar = bn256::Fr;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let r = Scalar::random(OsRng);
+
+    let mut group = c.benchmark_group("fold_instance");
+
+    // instances without rounds, so only public inputs are folded: short ones serially & long
+    // ones in parallel
+    for len in [2, 4096] {
+        let lhs_instance = RelaxedPlonkInstance::<Curve>::new(len, 0, 0);
+        let rhs_instance = PlonkInstance::<Curve>::new(len, 0, 0);
+        group.bench_with_input(BenchmarkId::new("fold", len), &len, |b, _| {
+            b.iter(|| {
+                black_box(&lhs_instance)
+                    .fold(black_box(&rhs_instance), &[], &r)
+                    .unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,122 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ff::Field;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+use halo2curves::{bn256, CurveAffine};
+use rand_core::OsRng;
+use sirius::{
+    commitment::CommitmentKey,
+    poseidon::{PoseidonHash, ROTrait, Spec},
+    table::CircuitRunner,
+};
+
+type Curve = bn256::G1Affine;
+type Scalar = bn256::Fr;
+type Base = <Curve as CurveAffine>::Base;
+
+const K: u32 = 16;
+// leave some rows unused, as real circuits do
+const ROWS: usize = (1 << K) - 16;
+
+/// `a * b = c` at each row
+#[derive(Default)]
+struct MulCircuit {
+    values: Vec<(Scalar, Scalar)>,
+}
+
+impl Circuit<Scalar> for MulCircuit {
+    type Config = (Selector, [Column<Advice>; 3]);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Scalar>) -> Self::Config {
+        let s = meta.selector();
+        let columns = [(); 3].map(|_| meta.advice_column());
+
+        meta.create_gate("a * b = c", |meta| {
+            let s = meta.query_selector(s);
+            let [a, b, c] = columns.map(|column| meta.query_advice(column, Rotation::cur()));
+            vec![s * (a * b - c)]
+        });
+
+        (s, columns)
+    }
+
+    fn synthesize(
+        &self,
+        (s, [a, b, c]): Self::Config,
+        mut layouter: impl Layouter<Scalar>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "mul",
+            |mut region| {
+                for (row, (lhs, rhs)) in self.values.iter().enumerate() {
+                    s.enable(&mut region, row)?;
+                    region.assign_advice(|| "a", a, row, || Value::known(*lhs))?;
+                    region.assign_advice(|| "b", b, row, || Value::known(*rhs))?;
+                    region.assign_advice(|| "c", c, row, || Value::known(*lhs * rhs))?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+fn new_ro() -> PoseidonHash<Base, 3, 2> {
+    PoseidonHash::new(Spec::new(4, 3))
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let circuit = MulCircuit {
+        values: (0..ROWS)
+            .map(|_| (Scalar::random(OsRng), Scalar::random(OsRng)))
+            .collect(),
+    };
+    let runner = CircuitRunner::<Scalar, _>::new(K, circuit, vec![]);
+    let structure = runner.try_collect_plonk_structure().unwrap();
+    let witness = runner.try_collect_witness().unwrap();
+
+    // three advice columns are committed at once
+    let ck = CommitmentKey::<Curve>::setup(K as usize + 2, b"is_sat_bench");
+    // the only gate isn't combined with others, so there are no challenges
+    let (u, w) = structure
+        .run_sps_protocol(&ck, &[], &witness, &mut new_ro(), 0)
+        .unwrap();
+    let advice = (0..3 << K)
+        .map(|_| Scalar::random(OsRng))
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("is_sat");
+    group.sample_size(10);
+
+    // `is_sat` used to run these two sequentially
+    group.bench_function("k=16/relation_only", |b| {
+        b.iter(|| {
+            structure
+                .is_sat_relation_only(black_box(&u), black_box(&w))
+                .unwrap()
+        })
+    });
+    group.bench_function("k=16/commit", |b| {
+        b.iter(|| ck.commit(black_box(&advice)).unwrap())
+    });
+
+    group.bench_function("k=16/concurrent", |b| {
+        b.iter(|| {
+            structure
+                .is_sat(&ck, &mut new_ro(), black_box(&u), black_box(&w))
+                .unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,198 @@
+//! Versioned encoding of proof artifacts, e.g. [`crate::plonk::PlonkInstance`],
+//! [`crate::plonk::RelaxedPlonkInstance`], their witnesses, cross term commitments & even
+//! [`crate::plonk::PlonkStructure`] itself, so it can be generated once & reused
+//!
+//! Artifacts implement [`serde`] traits, so any serde format can be used with [`Artifact`]
+//! envelope. For storage & network there is compact binary form: `u16` LE [`FORMAT_VERSION`]
+//! followed by [`bincode`] encoding of the artifact itself, see [`encode`] & [`decode`]
+//!
+//! Artifacts bound to [`PlonkStructure`] must be decoded with [`decode_for`], so malformed data
+//! is rejected right away instead of panicking later on folding
+use ff::PrimeField;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::plonk::{self, PlonkStructure, StructureBound};
+
+/// Version of artifacts encoding, bumped on each incompatible change of serialized types
+pub const FORMAT_VERSION: u16 = 2;
+
+const VERSION_LEN: usize = std::mem::size_of::<u16>();
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Bincode(#[from] bincode::Error),
+    #[error("Unsupported artifact format version {actual}, expected {expected}")]
+    UnsupportedVersion { expected: u16, actual: u16 },
+    #[error("Artifact is too short to contain format version")]
+    MissingVersion,
+    #[error("Artifact doesn't match structure: {0}")]
+    Invalid(#[from] plonk::Error),
+}
+
+/// Artifact tagged with [`FORMAT_VERSION`], for use with self-describing serde formats
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Artifact<T> {
+    version: u16,
+    payload: T,
+}
+
+impl<T> Artifact<T> {
+    pub fn new(payload: T) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            payload,
+        }
+    }
+
+    /// Returns the artifact, if it was encoded with the current [`FORMAT_VERSION`]
+    pub fn into_payload(self) -> Result<T, Error> {
+        check_version(self.version)?;
+        Ok(self.payload)
+    }
+}
+
+fn check_version(version: u16) -> Result<(), Error> {
+    if version == FORMAT_VERSION {
+        Ok(())
+    } else {
+        Err(Error::UnsupportedVersion {
+            expected: FORMAT_VERSION,
+            actual: version,
+        })
+    }
+}
+
+/// Encodes `artifact` in compact binary form, see module docs
+pub fn encode<T: Serialize>(artifact: &T) -> Result<Vec<u8>, Error> {
+    let mut bytes = FORMAT_VERSION.to_le_bytes().to_vec();
+    bincode::serialize_into(&mut bytes, artifact)?;
+    Ok(bytes)
+}
+
+/// Decodes artifact encoded with [`encode`], fails if it was encoded with another
+/// [`FORMAT_VERSION`]
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Error> {
+    let (version, payload) = bytes
+        .split_first_chunk::<VERSION_LEN>()
+        .ok_or(Error::MissingVersion)?;
+    check_version(u16::from_le_bytes(*version))?;
+    Ok(bincode::deserialize(payload)?)
+}
+
+/// Same as [`decode`], but also validates decoded artifact against `S`, see
+/// [`StructureBound::validate_for`]
+pub fn decode_for<F, T>(bytes: &[u8], S: &PlonkStructure<F>) -> Result<T, Error>
+where
+    F: PrimeField,
+    T: DeserializeOwned + StructureBound<F>,
+{
+    let artifact = decode::<T>(bytes)?;
+    artifact.validate_for(S)?;
+    Ok(artifact)
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2curves::{
+        bn256::{Fr, G1Affine},
+        group::prime::PrimeCurveAffine,
+    };
+
+    use crate::plonk::{PlonkInstance, RelaxedPlonkInstance, RelaxedPlonkWitness};
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut u = PlonkInstance::<G1Affine>::new(2, 1, 1);
+        u.W_commitments[0] = G1Affine::generator();
+        u.instance = vec![Fr::from(3), Fr::from(4)];
+        u.challenges = vec![Fr::from(5)];
+        assert_eq!(
+            decode::<PlonkInstance<G1Affine>>(&encode(&u).unwrap()).unwrap(),
+            u
+        );
+
+        let mut U = u.to_relax();
+        U.u = Fr::from(7);
+        assert_eq!(
+            decode::<RelaxedPlonkInstance<G1Affine>>(&encode(&U).unwrap()).unwrap(),
+            U
+        );
+
+        let cross_term_commits = vec![G1Affine::generator(); 3];
+        assert_eq!(
+            decode::<Vec<G1Affine>>(&encode(&cross_term_commits).unwrap()).unwrap(),
+            cross_term_commits
+        );
+
+        assert_eq!(Artifact::new(U.clone()).into_payload().unwrap(), U);
+    }
+
+    #[test]
+    fn wrong_version() {
+        let mut bytes = encode(&vec![Fr::from(1)]).unwrap();
+        bytes[..VERSION_LEN].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+
+        assert!(matches!(
+            decode::<Vec<Fr>>(&bytes),
+            Err(Error::UnsupportedVersion { actual, .. }) if actual == FORMAT_VERSION + 1
+        ));
+        assert!(matches!(
+            decode::<Vec<Fr>>(&bytes[..1]),
+            Err(Error::MissingVersion)
+        ));
+    }
+
+    #[test]
+    fn validated() {
+        let S = PlonkStructure::<Fr> {
+            k: 2,
+            num_io: 2,
+            num_challenges: 1,
+            round_sizes: vec![4],
+            ..Default::default()
+        };
+
+        let mut u = PlonkInstance::<G1Affine>::new(2, 1, 1);
+        u.W_commitments[0] = G1Affine::generator();
+        assert_eq!(
+            decode_for::<_, PlonkInstance<G1Affine>>(&encode(&u).unwrap(), &S).unwrap(),
+            u
+        );
+
+        let W = RelaxedPlonkWitness::<Fr>::new(S.k, &S.round_sizes);
+        assert_eq!(
+            decode_for::<_, RelaxedPlonkWitness<Fr>>(&encode(&W).unwrap(), &S).unwrap(),
+            W
+        );
+
+        let short = PlonkInstance::<G1Affine>::new(2, 0, 1);
+        assert!(matches!(
+            decode_for::<_, PlonkInstance<G1Affine>>(&encode(&short).unwrap(), &S),
+            Err(Error::Invalid(plonk::Error::StructureMismatch {
+                field: "challenges",
+                ..
+            }))
+        ));
+
+        let mut off_curve = u.clone();
+        off_curve.W_commitments[0].y += halo2curves::bn256::Fq::one();
+        assert!(matches!(
+            decode_for::<_, PlonkInstance<G1Affine>>(&encode(&off_curve).unwrap(), &S),
+            Err(Error::Invalid(plonk::Error::InvalidCommitment {
+                name: "W"
+            }))
+        ));
+
+        let wrong_k = RelaxedPlonkWitness::<Fr>::new(S.k + 1, &S.round_sizes);
+        assert!(matches!(
+            decode_for::<_, RelaxedPlonkWitness<Fr>>(&encode(&wrong_k).unwrap(), &S),
+            Err(Error::Invalid(plonk::Error::StructureMismatch {
+                field: "E",
+                ..
+            }))
+        ));
+    }
+}
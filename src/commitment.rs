@@ -1,14 +1,18 @@
 use std::{
     fs::{self, File},
     io::{self, Read, Write},
-    iter, ops,
+    iter,
+    num::NonZeroUsize,
+    ops,
     ops::Not,
     path::Path,
     slice,
+    sync::OnceLock,
 };
 
 use digest::{ExtendableOutput, Update};
-use group::Curve;
+use ff::{Field, PrimeField};
+use group::{Curve, Group, GroupEncoding};
 use halo2_proofs::arithmetic::{best_multiexp, CurveAffine, CurveExt};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -16,19 +20,57 @@ use sha3::Shake256;
 use some_to_err::*;
 use tracing::*;
 
-use crate::util::parallelize;
+use crate::util::{fe_to_fe_safe, parallelize};
 
 #[derive(Debug, PartialEq, Eq, thiserror::Error)]
 pub enum Error {
     #[error("Can't commit too long input: input len: {input_len}, but limit is {limit}")]
     TooLongInput { input_len: usize, limit: usize },
+    #[error("Scalar at index {index} doesn't fit into scalar field of the key")]
+    ScalarOutOfField { index: usize },
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+/// Upper bound of length of curve identifier read by [`CommitmentKey::read`]
+const MAX_CURVE_ID_LEN: usize = 64;
+
+/// Upper bound of MSM window size
+///
+/// Each window allocates `2^window_bits - 1` buckets & all windows are processed in parallel, so
+/// with 16 bits MSM keeps `num_windows * 2^16` projective points at once, that is about 100MB for
+/// 256-bit scalars
+pub const MAX_WINDOW_BITS: usize = 16;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CommitmentKey<C: CurveAffine> {
     ck: Box<[C]>,
+    /// Window size of MSM used by [`CommitmentKey::commit`], see
+    /// [`CommitmentKey::commit_with_window`]
+    ///
+    /// It's a runtime tuning parameter, so it isn't serialized
+    #[serde(skip)]
+    window_bits: Option<NonZeroUsize>,
+    /// Commitment of an all-zero vector, see [`CommitmentKey::empty_commitment`]
+    ///
+    /// It's a convention of protocol, so it isn't stored with the key & is configured after load,
+    /// same as `window_bits`
+    #[serde(skip)]
+    empty_commitment: Option<C>,
+    /// Cached [`CommitmentKey::blinding_generator`], derived on first
+    /// [`CommitmentKey::commit_hiding`], so loaded keys rebuild it on demand
+    #[serde(skip)]
+    blinding_generator: OnceLock<C>,
 }
 
+/// Keys are equal if they have the same generators & empty commitment,
+/// [`CommitmentKey::window_bits`] is a tuning parameter & doesn't affect commitments
+impl<C: CurveAffine> PartialEq for CommitmentKey<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ck == other.ck && self.empty_commitment() == other.empty_commitment()
+    }
+}
+
+impl<C: CurveAffine> Eq for CommitmentKey<C> {}
+
 impl<C: CurveAffine> ops::Deref for CommitmentKey<C> {
     type Target = [C];
 
@@ -38,10 +80,32 @@ impl<C: CurveAffine> ops::Deref for CommitmentKey<C> {
 }
 
 impl<C: CurveAffine> CommitmentKey<C> {
-    pub fn default_value() -> C {
+    /// Commitment of an empty (or all-zero) vector, used for fresh instances
+    ///
+    /// It's the identity point: commitment is linear, so it's the only "empty" value consistent
+    /// with folding, i.e. `commit(0) + r * commit(v) == commit(r * v)`
+    pub fn identity_commitment() -> C {
         C::identity()
     }
 
+    /// Overrides commitment of an empty (or all-zero) vector, e.g. with a specific generator
+    ///
+    /// It's returned by [`CommitmentKey::commit`] & its variants for all-zero input & used by
+    /// [`crate::plonk::RelaxedPlonkInstance::new_with_ck`]. Only the identity keeps commitment
+    /// linear, so with another value a fresh instance matches its zero witness, but the result of
+    /// folding doesn't match the folded witness
+    pub fn with_empty_commitment(mut self, empty_commitment: C) -> Self {
+        self.empty_commitment = Some(empty_commitment);
+        self
+    }
+
+    /// Commitment of an empty (or all-zero) vector: the one set by
+    /// [`CommitmentKey::with_empty_commitment`] or [`CommitmentKey::identity_commitment`]
+    pub fn empty_commitment(&self) -> C {
+        self.empty_commitment
+            .unwrap_or_else(Self::identity_commitment)
+    }
+
     pub fn len(&self) -> usize {
         self.ck.len()
     }
@@ -73,19 +137,202 @@ impl<C: CurveAffine> CommitmentKey<C> {
             C::Curve::batch_normalize(&ck_proj[start..start + ck.len()], ck);
         });
 
-        CommitmentKey { ck }
+        CommitmentKey {
+            ck,
+            window_bits: None,
+            empty_commitment: None,
+            blinding_generator: OnceLock::new(),
+        }
+    }
+
+    /// Sets window size of MSM used by [`CommitmentKey::commit`], `None` means heuristic of
+    /// [`best_multiexp`]
+    ///
+    /// Window is clamped to [`MAX_WINDOW_BITS`]
+    pub fn set_window_bits(&mut self, window_bits: Option<NonZeroUsize>) {
+        self.window_bits = window_bits.map(clamp_window_bits);
+    }
+
+    pub fn window_bits(&self) -> Option<NonZeroUsize> {
+        self.window_bits
     }
 
     pub fn commit(&self, v: &[C::Scalar]) -> Result<C, Error> {
-        if self.ck.len() >= v.len() {
-            Ok(best_multiexp(v, &self.ck[..v.len()]).to_affine())
-        } else {
-            Err(Error::TooLongInput {
+        self.commit_with_window(v, self.window_bits)
+    }
+
+    /// Same as [`CommitmentKey::commit`], but scalars of other field `F2` are converted into
+    /// `C::Scalar` first
+    ///
+    /// Conversion is checked: scalar, which doesn't fit into `C::Scalar` without reduction,
+    /// fails with [`Error::ScalarOutOfField`]
+    pub fn commit_converted<F2: PrimeField>(&self, v: &[F2]) -> Result<C, Error> {
+        let v = v
+            .par_iter()
+            .enumerate()
+            .map(|(index, scalar)| fe_to_fe_safe(scalar).ok_or(Error::ScalarOutOfField { index }))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.commit(&v)
+    }
+
+    /// Same as [`CommitmentKey::commit`], but MSM is done with `window_bits` window, or with
+    /// [`best_multiexp`] if it's `None`
+    ///
+    /// The result doesn't depend on the window size. Window is clamped to [`MAX_WINDOW_BITS`]
+    pub fn commit_with_window(
+        &self,
+        v: &[C::Scalar],
+        window_bits: Option<NonZeroUsize>,
+    ) -> Result<C, Error> {
+        Ok(self.msm(v, window_bits)?.to_affine())
+    }
+
+    fn msm(&self, v: &[C::Scalar], window_bits: Option<NonZeroUsize>) -> Result<C::Curve, Error> {
+        if self.ck.len() < v.len() {
+            return Err(Error::TooLongInput {
                 input_len: v.len(),
                 limit: self.ck.len(),
+            });
+        }
+
+        if let Some(empty_commitment) = self.empty_commitment {
+            if v.iter().all(|scalar| scalar.is_zero_vartime()) {
+                return Ok(empty_commitment.to_curve());
+            }
+        }
+
+        let bases = &self.ck[..v.len()];
+        Ok(match window_bits {
+            Some(window_bits) => windowed_multiexp(v, bases, window_bits),
+            None => best_multiexp(v, bases),
+        })
+    }
+
+    /// Same as [`CommitmentKey::commit`], but zero scalars & their bases are dropped before MSM
+    ///
+    /// So the time is proportional to the number of nonzero scalars, which is useful for vectors,
+    /// that are mostly zero, e.g. error vector `E` in early folds
+    pub fn commit_sparse(&self, v: &[C::Scalar]) -> Result<C, Error> {
+        self.commit_chunks(v.len(), iter::once((0, v)))
+    }
+
+    /// Same as [`CommitmentKey::commit_sparse`] for vector of `len` scalars given by chunks
+    ///
+    /// Each chunk is `(offset, scalars)` & scalars outside of chunks are zero, so missing chunks
+    /// cost nothing, see [`crate::plonk::error_vector::ErrorVector`]
+    pub fn commit_chunks<'s>(
+        &self,
+        len: usize,
+        chunks: impl Iterator<Item = (usize, &'s [C::Scalar])>,
+    ) -> Result<C, Error> {
+        if self.ck.len() < len {
+            return Err(Error::TooLongInput {
+                input_len: len,
+                limit: self.ck.len(),
+            });
+        }
+
+        let chunks = chunks.collect::<Vec<_>>();
+        debug_assert!(chunks
+            .iter()
+            .all(|(offset, scalars)| offset + scalars.len() <= len));
+
+        let (scalars, bases): (Vec<_>, Vec<_>) = chunks
+            .par_iter()
+            .flat_map_iter(|(offset, scalars)| {
+                scalars
+                    .iter()
+                    .zip(self.ck[*offset..].iter())
+                    .filter(|(scalar, _)| !bool::from(scalar.is_zero()))
+                    .map(|(scalar, base)| (*scalar, *base))
             })
+            .unzip();
+
+        if scalars.is_empty() {
+            return Ok(self.empty_commitment());
+        }
+
+        Ok(match self.window_bits {
+            Some(window_bits) => windowed_multiexp(&scalars, &bases, window_bits),
+            None => best_multiexp(&scalars, &bases),
         }
+        .to_affine())
     }
+
+    /// Generator `H` of blinding term in [`CommitmentKey::commit_hiding`]
+    ///
+    /// Derived independently of the key, so it doesn't change key size or its file format
+    pub fn blinding_generator() -> C {
+        (C::CurveExt::hash_to_curve("sirius_blinding_generator"))(b"H").to_affine()
+    }
+
+    /// Pedersen commitment with blinding term: `commit(v) + blind * H`
+    ///
+    /// With zero `blind` it's equal to [`CommitmentKey::commit`]
+    pub fn commit_hiding(&self, v: &[C::Scalar], blind: &C::Scalar) -> Result<C, Error> {
+        let H = *self
+            .blinding_generator
+            .get_or_init(Self::blinding_generator);
+        Ok((self.msm(v, self.window_bits)? + H * blind).to_affine())
+    }
+}
+
+fn clamp_window_bits(window_bits: NonZeroUsize) -> NonZeroUsize {
+    window_bits.min(NonZeroUsize::new(MAX_WINDOW_BITS).unwrap())
+}
+
+/// Pippenger's MSM with fixed window size, windows are processed in parallel
+///
+/// Window is clamped to [`MAX_WINDOW_BITS`], so memory of buckets stays bounded
+fn windowed_multiexp<C: CurveAffine>(
+    coeffs: &[C::Scalar],
+    bases: &[C],
+    window_bits: NonZeroUsize,
+) -> C::Curve {
+    let window_bits = clamp_window_bits(window_bits).get();
+    let coeffs = coeffs.iter().map(PrimeField::to_repr).collect::<Vec<_>>();
+
+    // little-endian digit of `repr` in window, which starts at `offset` bit
+    let digit = |repr: &[u8], offset: usize| {
+        (offset..offset + window_bits)
+            .take_while(|bit| bit / 8 < repr.len())
+            .enumerate()
+            .fold(0usize, |digit, (i, bit)| {
+                digit | ((((repr[bit / 8] >> (bit % 8)) & 1) as usize) << i)
+            })
+    };
+
+    let num_windows = (C::Scalar::NUM_BITS as usize).div_ceil(window_bits);
+    let windows = (0..num_windows)
+        .into_par_iter()
+        .map(|window| {
+            let mut buckets = vec![C::Curve::identity(); (1 << window_bits) - 1];
+            coeffs.iter().zip(bases).for_each(|(coeff, base)| {
+                match digit(coeff.as_ref(), window * window_bits) {
+                    0 => {}
+                    digit => buckets[digit - 1] += *base,
+                }
+            });
+
+            // sum_{j} j * bucket_j
+            let mut running_sum = C::Curve::identity();
+            buckets
+                .iter()
+                .rev()
+                .fold(C::Curve::identity(), |acc, bucket| {
+                    running_sum += bucket;
+                    acc + running_sum
+                })
+        })
+        .collect::<Vec<_>>();
+
+    windows
+        .into_iter()
+        .rev()
+        .fold(C::Curve::identity(), |acc, window| {
+            (0..window_bits).fold(acc, |acc, _| acc.double()) + window
+        })
 }
 
 impl<C: CurveAffine> CommitmentKey<C> {
@@ -121,6 +368,74 @@ impl<C: CurveAffine> CommitmentKey<C> {
 
         Ok(Self {
             ck: ck.into_boxed_slice(),
+            window_bits: None,
+            empty_commitment: None,
+            blinding_generator: OnceLock::new(),
+        })
+    }
+
+    /// Serializes `Self` in portable format, unlike [`CommitmentKey::save_to_file`]
+    ///
+    /// Format:
+    /// - `u32` LE length of curve identifier & identifier itself, see [`CurveExt::CURVE_ID`]
+    /// - `u64` LE count of generators
+    /// - generators in compressed form, see [`GroupEncoding::to_bytes`]
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        let curve_id = C::CurveExt::CURVE_ID.as_bytes();
+        writer.write_all(&(curve_id.len() as u32).to_le_bytes())?;
+        writer.write_all(curve_id)?;
+        writer.write_all(&(self.ck.len() as u64).to_le_bytes())?;
+
+        self.ck
+            .iter()
+            .try_for_each(|point| writer.write_all(point.to_bytes().as_ref()))
+    }
+
+    /// Deserializes `Self` written by [`CommitmentKey::write`]
+    ///
+    /// Refuses to load a key written for another curve or with points not on the curve. Curve
+    /// identifier longer than 64 bytes is rejected before allocation
+    pub fn read(reader: &mut impl Read) -> io::Result<Self> {
+        let invalid_data = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+        let mut len = [0u8; 4];
+        reader.read_exact(&mut len)?;
+        let len = u32::from_le_bytes(len) as usize;
+        if len > MAX_CURVE_ID_LEN {
+            return Err(invalid_data(format!(
+                "too long curve identifier: {len} bytes, but limit is {MAX_CURVE_ID_LEN}"
+            )));
+        }
+        let mut curve_id = vec![0u8; len];
+        reader.read_exact(&mut curve_id)?;
+
+        if curve_id != C::CurveExt::CURVE_ID.as_bytes() {
+            return Err(invalid_data(format!(
+                "commitment key for curve {}, but expected {}",
+                String::from_utf8_lossy(&curve_id),
+                C::CurveExt::CURVE_ID
+            )));
+        }
+
+        let mut count = [0u8; 8];
+        reader.read_exact(&mut count)?;
+        let count = usize::try_from(u64::from_le_bytes(count))
+            .map_err(|err| invalid_data(format!("too long commitment key: {err}")))?;
+
+        let ck = iter::repeat_with(|| {
+            let mut repr = <C as GroupEncoding>::Repr::default();
+            reader.read_exact(repr.as_mut())?;
+            Option::from(C::from_bytes(&repr))
+                .ok_or_else(|| invalid_data("point of commitment key out of curve".to_owned()))
+        })
+        .take(count)
+        .collect::<io::Result<Box<[C]>>>()?;
+
+        Ok(Self {
+            ck,
+            window_bits: None,
+            empty_commitment: None,
+            blinding_generator: OnceLock::new(),
         })
     }
 
@@ -169,8 +484,12 @@ impl<C: CurveAffine> CommitmentKey<C> {
 }
 
 #[cfg(test)]
-mod file_tests {
-    use halo2curves::bn256::G1Affine;
+mod tests {
+    use ff::Field;
+    use halo2curves::{
+        bn256::{Fr, G1Affine},
+        pasta::{EqAffine, Fp, Fq},
+    };
     use tempfile::tempdir;
     use tracing_test::traced_test;
 
@@ -193,4 +512,171 @@ mod file_tests {
 
         assert_eq!(key, loaded);
     }
+
+    #[test]
+    fn write_read() {
+        use halo2curves::grumpkin;
+
+        const K: usize = 4;
+
+        let key = CommitmentKey::<G1Affine>::setup(K, b"write_read");
+        let mut buf = Vec::new();
+        key.write(&mut buf).unwrap();
+
+        let loaded = CommitmentKey::<G1Affine>::read(&mut buf.as_slice()).unwrap();
+        assert_eq!(key, loaded);
+
+        let v = (0..1 << K).map(Fr::from).collect::<Vec<_>>();
+        assert_eq!(key.commit(&v), loaded.commit(&v));
+
+        assert_eq!(
+            CommitmentKey::<grumpkin::G1Affine>::read(&mut buf.as_slice())
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidData
+        );
+
+        // length of curve identifier is checked before allocation
+        let mut crafted = u32::MAX.to_le_bytes().to_vec();
+        crafted.extend_from_slice(&buf[4..]);
+        assert_eq!(
+            CommitmentKey::<G1Affine>::read(&mut crafted.as_slice())
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn commit_with_window() {
+        let mut key = CommitmentKey::<G1Affine>::setup(6, b"commit_with_window");
+        let v = (0..60u64)
+            .map(|i| Fr::from(i * 0x1234_5678_9abc) - Fr::from(i))
+            .collect::<Vec<_>>();
+
+        let expected = key.commit(&v).unwrap();
+        for window_bits in [1, 2, 3, 4, 7, 8, 13] {
+            let window_bits = NonZeroUsize::new(window_bits);
+            assert_eq!(key.commit_with_window(&v, window_bits), Ok(expected));
+
+            key.set_window_bits(window_bits);
+            assert_eq!(key.commit(&v), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn window_bits_bounds() {
+        let key = CommitmentKey::<G1Affine>::setup(2, b"window_bits_bounds");
+
+        let mut tuned = key.clone();
+        tuned.set_window_bits(NonZeroUsize::new(usize::MAX));
+        assert_eq!(tuned.window_bits(), NonZeroUsize::new(MAX_WINDOW_BITS));
+
+        // tuning doesn't make keys different
+        assert_eq!(tuned, key);
+    }
+
+    #[test]
+    fn commit_hiding() {
+        let key = CommitmentKey::<G1Affine>::setup(4, b"commit_hiding");
+        let v = (0..16).map(Fr::from).collect::<Vec<_>>();
+
+        assert_eq!(
+            key.commit_hiding(&v, &Fr::ZERO).unwrap(),
+            key.commit(&v).unwrap()
+        );
+        assert_ne!(
+            key.commit_hiding(&v, &Fr::from(1)).unwrap(),
+            key.commit_hiding(&v, &Fr::from(2)).unwrap()
+        );
+
+        let scalars = [v.as_slice(), &[Fr::from(3)]].concat();
+        let bases = [
+            &key[..v.len()],
+            &[CommitmentKey::<G1Affine>::blinding_generator()],
+        ]
+        .concat();
+        assert_eq!(
+            key.commit_hiding(&v, &Fr::from(3)).unwrap(),
+            best_multiexp(&scalars, &bases).to_affine()
+        );
+
+        // cached `H` isn't stored with the key, it's derived again after load
+        let mut buf = Vec::new();
+        key.write(&mut buf).unwrap();
+        let loaded = CommitmentKey::<G1Affine>::read(&mut buf.as_slice()).unwrap();
+        assert_eq!(
+            loaded.commit_hiding(&v, &Fr::from(3)),
+            key.commit_hiding(&v, &Fr::from(3))
+        );
+    }
+
+    #[test]
+    fn zero_commits_to_identity() {
+        let key = CommitmentKey::<G1Affine>::setup(4, b"zero_commits_to_identity");
+        let identity = CommitmentKey::<G1Affine>::identity_commitment();
+
+        assert_eq!(key.commit(&[]), Ok(identity));
+        assert_eq!(key.commit(&[Fr::ZERO; 16]), Ok(identity));
+        assert_eq!(key.commit_hiding(&[Fr::ZERO; 16], &Fr::ZERO), Ok(identity));
+
+        // folding of fresh commitment with another one doesn't depend on the fresh one
+        let v = (0..16).map(Fr::from).collect::<Vec<_>>();
+        let r = Fr::from(5);
+        let rv = v.iter().map(|vi| *vi * r).collect::<Vec<_>>();
+        assert_eq!(
+            (key.commit(&v).unwrap() * r + identity).to_affine(),
+            key.commit(&rv).unwrap()
+        );
+    }
+
+    #[test]
+    fn commit_converted() {
+        let key = CommitmentKey::<EqAffine>::setup(4, b"commit_converted");
+        let v = (0..16).map(Fq::from).collect::<Vec<_>>();
+
+        let converted = v
+            .iter()
+            .map(|fq| Fp::from_repr(fq.to_repr()).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(key.commit_converted(&v), key.commit(&converted));
+
+        // modulus of `Fq` is greater than modulus of `Fp`
+        let mut v = v;
+        v[3] = -Fq::ONE;
+        assert_eq!(
+            key.commit_converted(&v),
+            Err(Error::ScalarOutOfField { index: 3 })
+        );
+    }
+
+    #[test]
+    fn commit_sparse() {
+        let key = CommitmentKey::<G1Affine>::setup(5, b"commit_sparse");
+
+        let mut v = vec![Fr::ZERO; 32];
+        assert_eq!(key.commit_sparse(&v), key.commit(&v));
+
+        v[3] = Fr::from(7);
+        v[17] = -Fr::ONE;
+        assert_eq!(key.commit_sparse(&v), key.commit(&v));
+        assert_eq!(key.commit_sparse(&v[..20]), key.commit(&v[..20]));
+
+        assert_eq!(
+            key.commit_chunks(32, [(3, &v[3..4]), (17, &v[17..18])].into_iter()),
+            key.commit(&v)
+        );
+        assert_eq!(
+            key.commit_chunks(32, iter::empty()),
+            Ok(CommitmentKey::<G1Affine>::identity_commitment())
+        );
+
+        assert_eq!(
+            key.commit_sparse(&[Fr::ONE; 33]),
+            Err(Error::TooLongInput {
+                input_len: 33,
+                limit: 32
+            })
+        );
+    }
 }
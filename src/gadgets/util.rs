@@ -21,15 +21,15 @@ impl<F: PrimeField, const T: usize> MainGate<F, T> {
         ctx: &mut RegionCtx<'_, F>,
         a: Value<F>,
     ) -> Result<AssignedValue<F>, Error> {
-        // s0*s1 - out = 0
-        let s0 = ctx.assign_advice(|| "is_inf", self.config().state[0], a)?;
-        let s1 = ctx.assign_advice(|| "is_inf", self.config().state[0], a)?;
-        let out = ctx.assign_advice(|| "is_inf", self.config().out, a)?;
+        // s0*s1 - out = 0, with s0 = s1 = out, i.e. a^2 = a
+        let s0 = ctx.assign_advice(|| "bit", self.config().state[0], a)?;
+        let s1 = ctx.assign_advice(|| "bit", self.config().state[1], a)?;
+        let out = ctx.assign_advice(|| "bit", self.config().out, a)?;
         ctx.constrain_equal(s0.cell(), out.cell())?;
         ctx.constrain_equal(s1.cell(), out.cell())?;
 
         ctx.assign_fixed(|| "q_m", self.config().q_m[0], F::ONE)?;
-        ctx.assign_fixed(|| "q_o", self.config().q_m[0], -F::ONE)?;
+        ctx.assign_fixed(|| "q_o", self.config().q_o, -F::ONE)?;
         ctx.next();
         Ok(out)
     }
@@ -289,6 +289,8 @@ impl<C: CurveAffine> AssignedRelaxedPlonkInstance<C> {
                 .iter()
                 .flat_map(|c| to_diff_bn(c))
                 .collect::<Result<Vec<_>, _>>()?,
+            // not supported on-circuit, IVC rejects instances with commitment on entry
+            instance_commitment: None,
         }))
     }
 }
@@ -859,6 +861,8 @@ where
 
         let assigned_public_params_hash = assign_and_absorb_point!(public_params_hash)?;
 
+        // slices are prefixed with their length, same as in `AbsorbInRO` of relaxed instance
+        ro_circuit.absorb_len(self.relaxed.W_commitments.len());
         let assigned_W = self
             .relaxed
             .W_commitments
@@ -867,12 +871,14 @@ where
             .collect::<Result<Vec<_>, _>>()?;
         let assigned_E = assign_and_absorb_point!(&self.relaxed.E_commitment)?;
 
+        ro_circuit.absorb_len(self.relaxed.instance.len());
         let assigned_X0 =
             assign_and_absorb_diff_field_as_bn!(&self.relaxed.instance[0], || "X0")?.1;
         let assigned_X1 =
             assign_and_absorb_diff_field_as_bn!(&self.relaxed.instance[1], || "X1")?.1;
         assert_eq!(self.relaxed.instance.len(), 2);
 
+        ro_circuit.absorb_len(self.relaxed.challenges.len());
         let assigned_challenges = self
             .relaxed
             .challenges
@@ -894,12 +900,14 @@ where
             folded_X1: assigned_X1,
         };
 
+        ro_circuit.absorb_len(input_plonk.W_commitments.len());
         let assigned_instance_W_commitment_coordinates = input_plonk
             .W_commitments
             .iter()
             .map(|com| assign_and_absorb_point!(com))
             .collect::<Result<Vec<_>, _>>()?;
 
+        ro_circuit.absorb_len(input_plonk.instance.len());
         let assigned_input_instance = input_plonk
             .instance
             .iter()
@@ -910,6 +918,7 @@ where
             })
             .collect::<Result<Vec<_>, _>>()?;
 
+        ro_circuit.absorb_len(input_plonk.challenges.len());
         let assigned_challanges_instance = input_plonk
             .challenges
             .iter()
@@ -920,6 +929,7 @@ where
             })
             .collect::<Result<Vec<_>, _>>()?;
 
+        ro_circuit.absorb_len(cross_term_commits.len());
         let assigned_cross_term_commits = cross_term_commits
             .iter()
             .map(|cross_term_commit| assign_and_absorb_point!(cross_term_commit))
@@ -1128,6 +1138,7 @@ mod tests {
             challenges: iter::repeat_with(|| ScalarExt::random(&mut rnd))
                 .take(NUM_CHALLENGES)
                 .collect(),
+            instance_commitment: None,
         }
     }
 
@@ -1218,7 +1229,7 @@ mod tests {
             r,
         } = Fixture::default();
 
-        let mut folded_W = vec![CommitmentKey::<C1>::default_value(); NUM_WITNESS];
+        let mut folded_W = vec![CommitmentKey::<C1>::identity_commitment(); NUM_WITNESS];
 
         let mut layouter = SingleChipLayouter::new(&mut ws, vec![]).unwrap();
 
@@ -1252,15 +1263,18 @@ mod tests {
 
             assert_eq!(plonk.W_commitments, folded_W);
 
-            plonk = plonk.fold(
-                &PlonkInstance {
-                    W_commitments: input_W.clone(),
-                    instance: vec![],
-                    challenges: vec![],
-                },
-                &[],
-                &r,
-            );
+            plonk = plonk
+                .fold(
+                    &PlonkInstance {
+                        W_commitments: input_W.clone(),
+                        instance: vec![],
+                        challenges: vec![],
+                        instance_commitment: None,
+                    },
+                    &[],
+                    &r,
+                )
+                .unwrap();
 
             let off_circuit_W = plonk
                 .W_commitments
@@ -1346,15 +1360,18 @@ mod tests {
 
             assert_eq!(plonk.E_commitment, folded_E);
 
-            plonk = plonk.fold(
-                &PlonkInstance {
-                    W_commitments: vec![],
-                    instance: vec![],
-                    challenges: vec![],
-                },
-                &cross_term_commits,
-                &r,
-            );
+            plonk = plonk
+                .fold(
+                    &PlonkInstance {
+                        W_commitments: vec![],
+                        instance: vec![],
+                        challenges: vec![],
+                        instance_commitment: None,
+                    },
+                    &cross_term_commits,
+                    &r,
+                )
+                .unwrap();
 
             let off_circuit_E_coordinates = plonk.E_commitment.coordinates().unwrap();
             let off_circuit_E_x = *off_circuit_E_coordinates.x();
@@ -1473,15 +1490,18 @@ mod tests {
                 },
             );
 
-            relaxed_plonk = relaxed_plonk.fold(
-                &PlonkInstance {
-                    W_commitments: vec![],
-                    instance: input_instances.to_vec(),
-                    challenges: vec![],
-                },
-                &[],
-                &r,
-            );
+            relaxed_plonk = relaxed_plonk
+                .fold(
+                    &PlonkInstance {
+                        W_commitments: vec![],
+                        instance: input_instances.to_vec(),
+                        challenges: vec![],
+                        instance_commitment: None,
+                    },
+                    &[],
+                    &r,
+                )
+                .unwrap();
 
             let off_circuit_instances = relaxed_plonk
                 .instance
@@ -1614,15 +1634,18 @@ mod tests {
                 },
             );
 
-            relaxed_plonk = relaxed_plonk.fold(
-                &PlonkInstance {
-                    W_commitments: vec![],
-                    instance: vec![],
-                    challenges: input_challenges.to_vec(),
-                },
-                &[],
-                &r,
-            );
+            relaxed_plonk = relaxed_plonk
+                .fold(
+                    &PlonkInstance {
+                        W_commitments: vec![],
+                        instance: vec![],
+                        challenges: input_challenges.to_vec(),
+                        instance_commitment: None,
+                    },
+                    &[],
+                    &r,
+                )
+                .unwrap();
 
             let off_circuit_challenges = relaxed_plonk
                 .challenges
@@ -1713,7 +1736,9 @@ mod tests {
                 &cross_term_commits,
             );
 
-            relaxed = relaxed.fold(&input_plonk, &cross_term_commits, &off_circuit_r);
+            relaxed = relaxed
+                .fold(&input_plonk, &cross_term_commits, &off_circuit_r)
+                .unwrap();
 
             assert_eq!(on_circuit_relaxed, relaxed);
         }
@@ -1730,7 +1755,7 @@ mod tests {
 
         let mut ro = PoseidonHash::new(spec.clone());
 
-        VanillaFS::generate_challenge(&pp_hash, &mut ro, relaxed, input, cross_term_commits)
+        VanillaFS::derive_fold_challenge(&pp_hash, &mut ro, relaxed, input, cross_term_commits)
             .unwrap()
     }
 }
@@ -4,7 +4,7 @@ use ff::{Field, FromUniformBytes, PrimeField, PrimeFieldBits};
 use group::prime::PrimeCurveAffine;
 use halo2_proofs::dev::MockProver;
 use halo2curves::CurveAffine;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tracing::*;
 
 use crate::{
@@ -14,7 +14,7 @@ use crate::{
     },
     main_gate::MainGateConfig,
     nifs::{self, vanilla::VanillaFS, FoldingScheme},
-    plonk::{self, PlonkTrace, RelaxedPlonkTrace},
+    plonk::{self, PlonkStructure, PlonkTrace, RelaxedPlonkTrace, StructureBound},
     poseidon::{random_oracle::ROTrait, ROPair},
     sps,
     table::CircuitRunner,
@@ -56,6 +56,10 @@ pub enum Error {
     NIFS(#[from] nifs::Error),
     #[error("TODO")]
     VerifyFailed(Vec<VerificationError>),
+    #[error("Checkpoint doesn't match public params: {what}")]
+    CheckpointMismatch { what: &'static str },
+    #[error("Instance commitment isn't supported by on-circuit folding verifier: {what}")]
+    InstanceCommitment { what: &'static str },
 }
 
 impl Error {
@@ -95,6 +99,142 @@ pub enum VerificationError {
     },
 }
 
+/// Serializable state of [`IVC`] prover between steps, see [`IVC::checkpoint`] & [`IVC::resume`]
+///
+/// Commitment keys & structures aren't stored, they are taken from [`PublicParams`] on resume.
+/// Digests of public params are stored instead to check, that the state belongs to them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "C1: Serialize, C2: Serialize, C1::ScalarExt: Serialize, C2::ScalarExt: Serialize",
+    deserialize = "C1: Deserialize<'de>, C2: Deserialize<'de>, C1::ScalarExt: Deserialize<'de>, \
+                   C2::ScalarExt: Deserialize<'de>"
+))]
+pub struct IVCCheckpoint<C1: CurveAffine, C2: CurveAffine> {
+    step: usize,
+    digest_1: C1,
+    digest_2: C2,
+    primary: CheckpointContext<C1>,
+    secondary: CheckpointContext<C2>,
+    secondary_trace: PlonkTrace<C2>,
+}
+
+/// Proof of IVC for [`IVC::verify_claim`]
+///
+/// It's the uncompressed proof, as `RecursiveSNARK` of Nova: folded traces carry full witnesses,
+/// so it has the same content as [`IVCCheckpoint`], made by [`IVC::checkpoint`]
+pub type IVCProof<C1, C2> = IVCCheckpoint<C1, C2>;
+
+/// Borrowed state of one circuit, that is checked by [`IVC::verify`] & [`IVC::verify_claim`]
+struct StepState<'s, const ARITY: usize, C: CurveAffine> {
+    relaxed_trace: &'s RelaxedPlonkTrace<C>,
+    z_0: &'s [C::Scalar; ARITY],
+    z_i: &'s [C::Scalar; ARITY],
+}
+
+impl<'s, const ARITY: usize, C: CurveAffine, SC> From<&'s StepCircuitContext<ARITY, C, SC>>
+    for StepState<'s, ARITY, C>
+{
+    fn from(ctx: &'s StepCircuitContext<ARITY, C, SC>) -> Self {
+        Self {
+            relaxed_trace: &ctx.relaxed_trace,
+            z_0: &ctx.z_0,
+            z_i: &ctx.z_i,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "C: Serialize, C::ScalarExt: Serialize",
+    deserialize = "C: Deserialize<'de>, C::ScalarExt: Deserialize<'de>"
+))]
+struct CheckpointContext<C: CurveAffine> {
+    relaxed_trace: RelaxedPlonkTrace<C>,
+    z_0: Vec<C::ScalarExt>,
+    z_i: Vec<C::ScalarExt>,
+}
+
+impl<C: CurveAffine> CheckpointContext<C> {
+    fn new<const ARITY: usize, SC>(ctx: &StepCircuitContext<ARITY, C, SC>) -> Self {
+        Self {
+            relaxed_trace: ctx.relaxed_trace.clone(),
+            z_0: ctx.z_0.to_vec(),
+            z_i: ctx.z_i.to_vec(),
+        }
+    }
+
+    /// Validates stored trace against `S`, so malformed checkpoint fails here instead of
+    /// panicking on the next fold
+    fn into_context<const ARITY: usize, SC>(
+        self,
+        S: &PlonkStructure<C::ScalarExt>,
+        what: &'static str,
+    ) -> Result<StepCircuitContext<ARITY, C, SC>, Error> {
+        self.relaxed_trace.validate_for(S)?;
+        check_no_instance_commitment(self.relaxed_trace.U.instance_commitment(), "relaxed trace")?;
+
+        let to_array = |z: Vec<C::ScalarExt>| {
+            <[C::ScalarExt; ARITY]>::try_from(z).map_err(|_| Error::CheckpointMismatch { what })
+        };
+
+        Ok(StepCircuitContext {
+            relaxed_trace: self.relaxed_trace,
+            z_0: to_array(self.z_0)?,
+            z_i: to_array(self.z_i)?,
+            _p: PhantomData,
+        })
+    }
+
+    /// Same checks as [`CheckpointContext::into_context`], but state is borrowed
+    fn as_state<const ARITY: usize>(
+        &self,
+        S: &PlonkStructure<C::ScalarExt>,
+        what: &'static str,
+    ) -> Result<StepState<'_, ARITY, C>, Error> {
+        self.relaxed_trace.validate_for(S)?;
+        check_no_instance_commitment(self.relaxed_trace.U.instance_commitment(), "relaxed trace")?;
+
+        let to_array = |z: &[C::ScalarExt]| {
+            <&[C::ScalarExt; ARITY]>::try_from(z).map_err(|_| Error::CheckpointMismatch { what })
+        };
+
+        Ok(StepState {
+            relaxed_trace: &self.relaxed_trace,
+            z_0: to_array(&self.z_0)?,
+            z_i: to_array(&self.z_i)?,
+        })
+    }
+}
+
+/// [`crate::ivc::fold_relaxed_plonk_instance_chip`] doesn't absorb & fold instance commitments
+/// (see [`crate::plonk::PlonkInstance::commit_instance`]), so instance with it would get another
+/// folding challenge on-circuit than off-circuit
+fn check_no_instance_commitment<C>(
+    commitment: Option<&C>,
+    what: &'static str,
+) -> Result<(), Error> {
+    match commitment {
+        Some(_) => Err(Error::InstanceCommitment { what }),
+        None => Ok(()),
+    }
+}
+
+impl<C1: CurveAffine, C2: CurveAffine> IVCCheckpoint<C1, C2> {
+    fn check_digests(&self, digest_1: C1, digest_2: C2) -> Result<(), Error> {
+        if self.digest_1 != digest_1 {
+            return Err(Error::CheckpointMismatch {
+                what: "primary public params digest",
+            });
+        }
+        if self.digest_2 != digest_2 {
+            return Err(Error::CheckpointMismatch {
+                what: "secondary public params digest",
+            });
+        }
+        Ok(())
+    }
+}
+
 // TODO #31 docs
 #[allow(clippy::upper_case_acronyms)]
 /// RecursiveSNARK from Nova codebase
@@ -200,9 +340,10 @@ where
         let primary_z_output = primary.process_step(&primary_z_0, pp.primary.k_table_size())?;
         debug!("primary z output calculated off-circuit");
 
-        // Will be used as input & output `U` of zero-step of IVC
-        let secondary_relaxed_trace =
-            secondary_pre_round_plonk_trace.to_relax(pp.secondary.k_table_size() as usize);
+        // Will be used as input & output `U` of zero-step of IVC, initial trace stays in `pp`
+        let secondary_relaxed_trace = secondary_pre_round_plonk_trace
+            .clone()
+            .into_relax(pp.secondary.k_table_size() as usize);
 
         // Prepare primary constraint system for folding
         let primary_instance = {
@@ -259,7 +400,7 @@ where
         .try_collect_witness()?;
 
         let (primary_nifs_pp, _primary_off_circuit_vp) =
-            VanillaFS::setup_params(pp.digest_1(), pp.primary.S().clone())?;
+            VanillaFS::setup_params_with_digest(pp.digest_1(), pp.primary.S().clone());
 
         let primary_plonk_trace = VanillaFS::generate_plonk_trace(
             pp.primary.ck(),
@@ -269,8 +410,13 @@ where
             &mut RP2::OffCircuit::new(pp.secondary.params().ro_constant().clone()),
         )?;
 
-        let primary_relaxed_trace =
-            primary_plonk_trace.to_relax(pp.primary.k_table_size() as usize);
+        // only instance of `primary_plonk_trace` is needed below, so witness is moved
+        let primary_relaxed_trace = RelaxedPlonkTrace {
+            U: primary_plonk_trace.u.to_relax(),
+            W: primary_plonk_trace
+                .w
+                .into_relax(pp.primary.k_table_size() as usize),
+        };
 
         primary_span.exit();
         let _secondary_span = info_span!("secondary").entered();
@@ -336,7 +482,7 @@ where
         .try_collect_witness()?;
 
         let (secondary_nifs_pp, _nifs_vp) =
-            VanillaFS::setup_params(pp.digest_2(), pp.secondary.S().clone())?;
+            VanillaFS::setup_params_with_digest(pp.digest_2(), pp.secondary.S().clone());
 
         let secondary_plonk_trace = VanillaFS::generate_plonk_trace(
             pp.secondary.ck(),
@@ -351,7 +497,7 @@ where
             debug_mode: false,
             secondary_nifs_pp,
             primary_nifs_pp,
-            secondary_trace: secondary_plonk_trace.clone(),
+            secondary_trace: secondary_plonk_trace,
             primary: StepCircuitContext {
                 z_0: primary_z_0,
                 z_i: primary_z_output,
@@ -537,11 +683,135 @@ where
         Ok(())
     }
 
+    /// Saves state of prover, so folding can be continued with [`IVC::resume`], e.g. after
+    /// restart of the process
+    pub fn checkpoint<const T: usize, RP1, RP2>(
+        &self,
+        pp: &PublicParams<'_, A1, A2, T, C1, C2, SC1, SC2, RP1, RP2>,
+    ) -> IVCCheckpoint<C1, C2>
+    where
+        RP1: ROPair<C1::Scalar, Config = MainGateConfig<T>>,
+        RP2: ROPair<C2::Scalar, Config = MainGateConfig<T>>,
+    {
+        IVCCheckpoint {
+            step: self.step,
+            digest_1: pp.digest_1(),
+            digest_2: pp.digest_2(),
+            primary: CheckpointContext::new(&self.primary),
+            secondary: CheckpointContext::new(&self.secondary),
+            secondary_trace: self.secondary_trace.clone(),
+        }
+    }
+
+    /// Restores prover from state saved by [`IVC::checkpoint`]
+    ///
+    /// Fails with [`Error::CheckpointMismatch`] if `checkpoint` was made with other public params,
+    /// i.e. for other circuits or structures, and with [`Error::Plonk`] if stored traces don't
+    /// match structures of `pp`
+    pub fn resume<const T: usize, RP1, RP2>(
+        pp: &PublicParams<'_, A1, A2, T, C1, C2, SC1, SC2, RP1, RP2>,
+        checkpoint: IVCCheckpoint<C1, C2>,
+    ) -> Result<Self, Error>
+    where
+        RP1: ROPair<C1::Scalar, Config = MainGateConfig<T>>,
+        RP2: ROPair<C2::Scalar, Config = MainGateConfig<T>>,
+    {
+        checkpoint.check_digests(pp.digest_1(), pp.digest_2())?;
+
+        let (primary_nifs_pp, _) =
+            VanillaFS::setup_params_with_digest(pp.digest_1(), pp.primary.S().clone());
+        let (secondary_nifs_pp, _) =
+            VanillaFS::setup_params_with_digest(pp.digest_2(), pp.secondary.S().clone());
+
+        checkpoint.secondary_trace.validate_for(pp.secondary.S())?;
+        check_no_instance_commitment(
+            checkpoint.secondary_trace.u.instance_commitment(),
+            "secondary trace",
+        )?;
+
+        Ok(Self {
+            primary: checkpoint
+                .primary
+                .into_context(pp.primary.S(), "primary arity")?,
+            secondary: checkpoint
+                .secondary
+                .into_context(pp.secondary.S(), "secondary arity")?,
+            step: checkpoint.step,
+            secondary_nifs_pp,
+            primary_nifs_pp,
+            secondary_trace: checkpoint.secondary_trace,
+            debug_mode: false,
+        })
+    }
+
+    /// Verifies, that `proof` proves `num_steps` steps of primary circuit from `z_0` to `z_n`
+    ///
+    /// `proof` may come from elsewhere, e.g. made by [`IVC::checkpoint`] of other process, so its
+    /// digests & shape are checked against `pp` first. Step & inputs of both circuits are bound to
+    /// public inputs of folded instances, so after the claim is compared with them, the rest is
+    /// checked as in [`IVC::verify`]
+    #[instrument(name = "ivc_verify_claim", skip_all)]
+    pub fn verify_claim<const T: usize, RP1, RP2>(
+        pp: &PublicParams<'_, A1, A2, T, C1, C2, SC1, SC2, RP1, RP2>,
+        num_steps: NonZeroUsize,
+        z_0: &[C1::Scalar; A1],
+        z_n: &[C1::Scalar; A1],
+        proof: &IVCProof<C1, C2>,
+    ) -> Result<(), Error>
+    where
+        RP1: ROPair<C1::Scalar, Config = MainGateConfig<T>>,
+        RP2: ROPair<C2::Scalar, Config = MainGateConfig<T>>,
+    {
+        proof.check_digests(pp.digest_1(), pp.digest_2())?;
+
+        if num_steps.get() != proof.step {
+            return Err(Error::NumStepNotMatch);
+        }
+
+        let primary = proof
+            .primary
+            .as_state::<A1>(pp.primary.S(), "primary arity")?;
+        let secondary = proof
+            .secondary
+            .as_state::<A2>(pp.secondary.S(), "secondary arity")?;
+        proof.secondary_trace.validate_for(pp.secondary.S())?;
+        check_no_instance_commitment(
+            proof.secondary_trace.u.instance_commitment(),
+            "secondary trace",
+        )?;
+
+        if z_0 != primary.z_0 || z_n != primary.z_i {
+            return Err(Error::SCInputNotMatch);
+        }
+
+        Self::verify_state(pp, proof.step, primary, secondary, &proof.secondary_trace)
+    }
+
     #[instrument(name = "ivc_vefify", skip_all)]
     pub fn verify<const T: usize, RP1, RP2>(
         &mut self,
         pp: &PublicParams<'_, A1, A2, T, C1, C2, SC1, SC2, RP1, RP2>,
     ) -> Result<(), Error>
+    where
+        RP1: ROPair<C1::Scalar, Config = MainGateConfig<T>>,
+        RP2: ROPair<C2::Scalar, Config = MainGateConfig<T>>,
+    {
+        Self::verify_state(
+            pp,
+            self.step,
+            StepState::from(&self.primary),
+            StepState::from(&self.secondary),
+            &self.secondary_trace,
+        )
+    }
+
+    fn verify_state<const T: usize, RP1, RP2>(
+        pp: &PublicParams<'_, A1, A2, T, C1, C2, SC1, SC2, RP1, RP2>,
+        step: usize,
+        primary: StepState<'_, A1, C1>,
+        secondary: StepState<'_, A2, C2>,
+        secondary_trace: &PlonkTrace<C2>,
+    ) -> Result<(), Error>
     where
         RP1: ROPair<C1::Scalar, Config = MainGateConfig<T>>,
         RP2: ROPair<C2::Scalar, Config = MainGateConfig<T>>,
@@ -551,17 +821,17 @@ where
         RandomOracleComputationInstance::<'_, A1, C2, RP1::OffCircuit> {
             random_oracle_constant: pp.primary.params().ro_constant().clone(),
             public_params_hash: &pp.digest_2(),
-            step: self.step,
-            z_0: &self.primary.z_0,
-            z_i: &self.primary.z_i,
-            relaxed: &self.secondary.relaxed_trace.U,
+            step,
+            z_0: primary.z_0,
+            z_i: primary.z_i,
+            relaxed: &secondary.relaxed_trace.U,
             limb_width: pp.secondary.params().limb_width(),
             limbs_count: pp.secondary.params().limbs_count(),
         }
         .generate_with_inspect::<C2::Scalar>(|buf| {
-            debug!("primary X0 verify at {}-step: {buf:?}", self.step)
+            debug!("primary X0 verify at {step}-step: {buf:?}")
         })
-        .ne(&self.secondary_trace.u.instance[0])
+        .ne(&secondary_trace.u.instance[0])
         .then(|| {
             errors.push(VerificationError::InstanceNotMatch {
                 index: 0,
@@ -572,17 +842,17 @@ where
         RandomOracleComputationInstance::<'_, A2, C1, RP2::OffCircuit> {
             random_oracle_constant: pp.secondary.params().ro_constant().clone(),
             public_params_hash: &pp.digest_1(),
-            step: self.step,
-            z_0: &self.secondary.z_0,
-            z_i: &self.secondary.z_i,
-            relaxed: &self.primary.relaxed_trace.U,
+            step,
+            z_0: secondary.z_0,
+            z_i: secondary.z_i,
+            relaxed: &primary.relaxed_trace.U,
             limb_width: pp.secondary.params().limb_width(),
             limbs_count: pp.secondary.params().limbs_count(),
         }
         .generate_with_inspect::<C1::Scalar>(|buf| {
-            debug!("primary X1 verify at {}-step: {buf:?}", self.step)
+            debug!("primary X1 verify at {step}-step: {buf:?}")
         })
-        .ne(&util::fe_to_fe(&self.secondary_trace.u.instance[1]).unwrap())
+        .ne(&util::fe_to_fe(&secondary_trace.u.instance[1]).unwrap())
         .then(|| {
             errors.push(VerificationError::InstanceNotMatch {
                 index: 1,
@@ -592,8 +862,8 @@ where
 
         if let Err(err) = pp.primary.S().is_sat_relaxed(
             pp.primary.ck(),
-            &self.primary.relaxed_trace.U,
-            &self.primary.relaxed_trace.W,
+            &primary.relaxed_trace.U,
+            &primary.relaxed_trace.W,
         ) {
             errors.push(VerificationError::NotSat {
                 err,
@@ -604,8 +874,8 @@ where
 
         if let Err(err) = pp.secondary.S().is_sat_relaxed(
             pp.secondary.ck(),
-            &self.secondary.relaxed_trace.U,
-            &self.secondary.relaxed_trace.W,
+            &secondary.relaxed_trace.U,
+            &secondary.relaxed_trace.W,
         ) {
             errors.push(VerificationError::NotSat {
                 err,
@@ -617,8 +887,8 @@ where
         if let Err(err) = pp.secondary.S().is_sat(
             pp.secondary.ck(),
             &mut RP1::OffCircuit::new(pp.primary.params().ro_constant().clone()),
-            &self.secondary_trace.u,
-            &self.secondary_trace.w,
+            &secondary_trace.u,
+            &secondary_trace.w,
         ) {
             errors.push(VerificationError::NotSat {
                 err,
@@ -630,7 +900,7 @@ where
         if let Err(err) = pp
             .primary
             .S()
-            .is_sat_perm(&self.primary.relaxed_trace.U, &self.primary.relaxed_trace.W)
+            .is_sat_perm(&primary.relaxed_trace.U, &primary.relaxed_trace.W)
         {
             errors.push(VerificationError::NotSat {
                 err,
@@ -639,10 +909,11 @@ where
             })
         }
 
-        if let Err(err) = pp.secondary.S().is_sat_perm(
-            &self.secondary.relaxed_trace.U,
-            &self.secondary.relaxed_trace.W,
-        ) {
+        if let Err(err) = pp
+            .secondary
+            .S()
+            .is_sat_perm(&secondary.relaxed_trace.U, &secondary.relaxed_trace.W)
+        {
             errors.push(VerificationError::NotSat {
                 err,
                 is_primary: false,
@@ -657,3 +928,146 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::array;
+
+    use halo2curves::{bn256, grumpkin};
+
+    use bn256::G1 as C1;
+    use grumpkin::G1 as C2;
+
+    use crate::{
+        ivc::{
+            public_params::pp_test::get_or_create_commitment_key, step_circuit::trivial,
+            CircuitPublicParamsInput,
+        },
+        plonk::error_vector::ErrorVector,
+        poseidon::PoseidonRO,
+    };
+
+    use super::*;
+
+    type C1Affine = <C1 as halo2curves::group::prime::PrimeCurve>::Affine;
+    type C2Affine = <C2 as halo2curves::group::prime::PrimeCurve>::Affine;
+
+    type Scalar1 = <C1 as group::Group>::Scalar;
+    type Scalar2 = <C2 as group::Group>::Scalar;
+
+    type RandomOracle = PoseidonRO<5, 4>;
+    type RandomOracleConstant<F> = <RandomOracle as ROPair<F>>::Args;
+
+    const K: usize = 17;
+    const LIMB_WIDTH: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(32) };
+    const LIMBS_COUNT_LIMIT: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(10) };
+
+    #[test]
+    fn verify_claim() {
+        let primary_ck = get_or_create_commitment_key::<C1Affine>(K + 3, "bn256").unwrap();
+        let secondary_ck = get_or_create_commitment_key::<C2Affine>(K + 3, "grumpkin").unwrap();
+
+        let sc1 = trivial::Circuit::<1, Scalar1>::default();
+        let sc2 = trivial::Circuit::<1, Scalar2>::default();
+
+        let pp = PublicParams::<
+            '_,
+            1,
+            1,
+            5,
+            C1Affine,
+            C2Affine,
+            trivial::Circuit<1, Scalar1>,
+            trivial::Circuit<1, Scalar2>,
+            RandomOracle,
+            RandomOracle,
+        >::new(
+            CircuitPublicParamsInput::new(
+                K as u32,
+                &primary_ck,
+                RandomOracleConstant::<Scalar1>::new(10, 10),
+                &sc1,
+            ),
+            CircuitPublicParamsInput::new(
+                K as u32,
+                &secondary_ck,
+                RandomOracleConstant::<Scalar2>::new(10, 10),
+                &sc2,
+            ),
+            LIMB_WIDTH,
+            LIMBS_COUNT_LIMIT,
+        )
+        .unwrap();
+
+        let z_0 = array::from_fn(|i| Scalar1::from(i as u64 + 1));
+        let mut ivc = IVC::new(&pp, &sc1, z_0, &sc2, [Scalar2::ONE], false).unwrap();
+        ivc.fold_step(&pp, &sc1, &sc2).unwrap();
+
+        // proof is received by verifier as bytes
+        let bytes = bincode::serialize(&ivc.checkpoint(&pp)).unwrap();
+        drop(ivc);
+        let proof: IVCProof<C1Affine, C2Affine> = bincode::deserialize(&bytes).unwrap();
+
+        let num_steps = NonZeroUsize::new(2).unwrap();
+        // trivial circuit returns its input as is
+        let z_n = z_0;
+        IVC::verify_claim(&pp, num_steps, &z_0, &z_n, &proof).unwrap();
+
+        assert!(matches!(
+            IVC::verify_claim(&pp, num_steps, &z_0, &[Scalar1::from(42)], &proof),
+            Err(Error::SCInputNotMatch)
+        ));
+        assert!(matches!(
+            IVC::verify_claim(&pp, NonZeroUsize::new(3).unwrap(), &z_0, &z_n, &proof),
+            Err(Error::NumStepNotMatch)
+        ));
+
+        let mut tampered = proof.clone();
+        tampered.primary.relaxed_trace.U.u += Scalar1::ONE;
+        assert!(matches!(
+            IVC::verify_claim(&pp, num_steps, &z_0, &z_n, &tampered),
+            Err(Error::VerifyFailed(_))
+        ));
+
+        // restore prover from the same bytes, then continue folding
+        let checkpoint: IVCCheckpoint<C1Affine, C2Affine> = bincode::deserialize(&bytes).unwrap();
+
+        let mut tampered = checkpoint.clone();
+        tampered.digest_1 = C1Affine::generator();
+        assert!(matches!(
+            IVC::resume(&pp, tampered),
+            Err(Error::CheckpointMismatch { .. })
+        ));
+
+        let mut tampered = checkpoint.clone();
+        tampered.primary.relaxed_trace.W.E = ErrorVector::zero(0);
+        assert!(matches!(
+            IVC::resume(&pp, tampered),
+            Err(Error::Plonk(plonk::Error::StructureMismatch {
+                field: "E",
+                ..
+            }))
+        ));
+
+        let mut tampered = checkpoint.clone();
+        tampered.secondary_trace.u.instance.pop();
+        assert!(matches!(
+            IVC::resume(&pp, tampered),
+            Err(Error::Plonk(plonk::Error::NumIoMismatch { .. }))
+        ));
+
+        let mut tampered = checkpoint.clone();
+        tampered.primary.relaxed_trace.U.instance_commitment = Some(C1Affine::generator());
+        assert!(matches!(
+            IVC::resume(&pp, tampered),
+            Err(Error::InstanceCommitment {
+                what: "relaxed trace"
+            })
+        ));
+
+        let mut ivc: IVC<1, 1, _, _, _, _> = IVC::resume(&pp, checkpoint).unwrap();
+        ivc.fold_step(&pp, &sc1, &sc2).unwrap();
+        let num_steps = NonZeroUsize::new(3).unwrap();
+        IVC::verify_claim(&pp, num_steps, &z_0, &z_n, &ivc.checkpoint(&pp)).unwrap();
+    }
+}
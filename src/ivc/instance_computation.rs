@@ -209,11 +209,12 @@ mod tests {
         let z_0 = [Base::from_u128(0x1024); 10];
         let z_i = [Base::from_u128(0x2048); 10];
         let relaxed = RelaxedPlonkInstance {
-            W_commitments: vec![CommitmentKey::<C1>::default_value(); 10],
-            E_commitment: CommitmentKey::<C1>::default_value(),
+            W_commitments: vec![CommitmentKey::<C1>::identity_commitment(); 10],
+            E_commitment: CommitmentKey::<C1>::identity_commitment(),
             instance: vec![Scalar::from_u128(0x67899); 2],
             challenges: vec![Scalar::from_u128(0x123456); 10],
             u: Scalar::from_u128(u128::MAX),
+            instance_commitment: None,
         };
 
         let off_circuit_hash: Base = RandomOracleComputationInstance::<
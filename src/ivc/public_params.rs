@@ -1,4 +1,4 @@
-use std::{fmt, io, marker::PhantomData, num::NonZeroUsize, ops::Deref};
+use std::{fmt, io, marker::PhantomData, num::NonZeroUsize, ops::Deref, sync::Arc};
 
 use ff::{Field, FromUniformBytes, PrimeFieldBits};
 use group::prime::PrimeCurveAffine;
@@ -46,7 +46,7 @@ where
     C::Scalar: PrimeFieldBits + FromUniformBytes<64> + Serialize,
     RP: ROPair<C::Scalar>,
 {
-    S: PlonkStructure<C::Scalar>,
+    S: Arc<PlonkStructure<C::Scalar>>,
     #[serde(skip_serializing)]
     ck: &'key CommitmentKey<C>,
     params: StepParams<C::Scalar, RP::OnCircuit>,
@@ -68,7 +68,8 @@ where
     pub fn params(&self) -> &StepParams<C::Scalar, RP::OnCircuit> {
         &self.params
     }
-    pub fn S(&self) -> &PlonkStructure<C::Scalar> {
+    /// Structure is shared, so it can be cloned cheaply, e.g. into NIFS params
+    pub fn S(&self) -> &Arc<PlonkStructure<C::Scalar>> {
         &self.S
     }
 }
@@ -98,7 +99,7 @@ where
     RP: ROPair<C::Scalar, Config = MainGateConfig<MAIN_GATE_T>>,
 {
     fn new(
-        S: PlonkStructure<C::Scalar>,
+        S: Arc<PlonkStructure<C::Scalar>>,
         commitment_key: &'key CommitmentKey<C>,
         ro_constant: RP::Args,
         limb_width: NonZeroUsize,
@@ -265,11 +266,12 @@ where
                         primary.k_table_size,
                         NUM_IO,
                         &StepParams::new(limb_width, limbs_count, primary.ro_constant.clone()),
-                    ),
+                    )?,
                 },
                 vec![C1::Scalar::ZERO; NUM_IO],
             )
             .try_collect_plonk_structure()
+            .map(Arc::new)
         }?;
 
         let (secondary_S, secondary_initial_plonk_trace) = {
@@ -284,7 +286,7 @@ where
                 secondary.k_table_size,
                 NUM_IO,
                 &secondary_initial_step_params,
-            );
+            )?;
 
             let secondary_initial_instance: [C2::Scalar; 2] = [
                 util::fe_to_fe(&secondary_initial_step_input.u.instance[0]).unwrap(),
@@ -314,12 +316,13 @@ where
                 vec![C2::Scalar::ZERO; NUM_IO],
             );
 
-            let secondary_S = secondary_cr.try_collect_plonk_structure()?;
+            let secondary_S = Arc::new(secondary_cr.try_collect_plonk_structure()?);
             let secondary_initial_plonk_trace = VanillaFS::generate_plonk_trace(
                 secondary.commitment_key,
                 &secondary_initial_instance,
                 &secondary_cr.try_collect_witness()?,
-                &VanillaFS::setup_params(C2::identity(), secondary_S.clone())?.0,
+                // digest isn't used while generating trace
+                &VanillaFS::setup_params_with_digest(C2::identity(), secondary_S.clone()).0,
                 &mut RP1::OffCircuit::new(primary.ro_constant.clone()),
             )?;
 
@@ -415,7 +418,7 @@ where
 }
 
 #[cfg(test)]
-mod pp_test {
+pub(crate) mod pp_test {
     use std::{fs, path::Path};
 
     use group::Group;
@@ -440,7 +443,7 @@ mod pp_test {
     type RandomOracleConstant<const T: usize, const RATE: usize, F> =
         <RandomOracle<T, RATE> as ROPair<F>>::Args;
 
-    fn get_or_create_commitment_key<C: CurveAffine>(
+    pub(crate) fn get_or_create_commitment_key<C: CurveAffine>(
         k: usize,
         label: &'static str,
     ) -> io::Result<CommitmentKey<C>> {
@@ -15,6 +15,8 @@ pub enum SynthesisError {
     Halo2(halo2_proofs::plonk::Error),
     #[error(transparent)]
     FoldError(#[from] fold_relaxed_plonk_instance_chip::Error),
+    #[error("VM state layout needs at least {expected} cells, but arity is {actual}")]
+    VmStateLayout { expected: usize, actual: usize },
 }
 
 /// The `StepCircuit` trait represents a step in incremental computation in
@@ -96,10 +98,12 @@ pub trait StepCircuit<const ARITY: usize, F: PrimeField> {
             advice: vec![vec![F::ZERO.into(); 1 << k_table_size as usize]; cs.num_advice_columns()],
         };
         let mut layouter =
-            SingleChipLayouter::<'_, F, _>::new(&mut witness, vec![]).map_err(|err| {
-                error!("while creation of layouter in `process_step`: {err:?}");
-                SynthesisError::Halo2(err)
-            })?;
+            SingleChipLayouter::<'_, F, _>::new(&mut witness, cs.constants().clone()).map_err(
+                |err| {
+                    error!("while creation of layouter in `process_step`: {err:?}");
+                    SynthesisError::Halo2(err)
+                },
+            )?;
 
         let assigned_z_i = layouter
             .assign_region(
@@ -177,3 +181,218 @@ pub mod trivial {
         }
     }
 }
+
+/// Conventions for zkVM-like step circuits, where each step is one transition of machine state
+///
+/// Machine state is packed into `z` of [`StepCircuit`] as `[pc, memory_root, registers...]`, so
+/// `ARITY = 2 + number of registers`. Memory itself isn't part of `z`, only its commitment (e.g.
+/// merkle root), so memory access proofs are provided to step as non-deterministic input.
+pub mod vm {
+    use ff::PrimeField;
+    use halo2_proofs::{
+        circuit::{AssignedCell, Layouter},
+        plonk::ConstraintSystem,
+    };
+
+    use super::{StepCircuit, SynthesisError};
+
+    /// Index of program counter in `z`
+    pub const PC_INDEX: usize = 0;
+    /// Index of memory root in `z`
+    pub const MEMORY_ROOT_INDEX: usize = 1;
+    /// Index of first register in `z`
+    pub const REGISTERS_OFFSET: usize = 2;
+
+    /// Machine state, passed between steps of [`VmStepCircuit`]
+    ///
+    /// `T` is [`AssignedCell`] on-circuit and field element off-circuit
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct VmState<T> {
+        pub pc: T,
+        pub memory_root: T,
+        pub registers: Vec<T>,
+    }
+
+    impl<T: Clone> VmState<T> {
+        /// Unpack state from `z` by [layout](self)
+        pub fn from_z<const ARITY: usize>(z: &[T; ARITY]) -> Result<Self, SynthesisError> {
+            if ARITY < REGISTERS_OFFSET {
+                return Err(SynthesisError::VmStateLayout {
+                    expected: REGISTERS_OFFSET,
+                    actual: ARITY,
+                });
+            }
+
+            Ok(Self {
+                pc: z[PC_INDEX].clone(),
+                memory_root: z[MEMORY_ROOT_INDEX].clone(),
+                registers: z[REGISTERS_OFFSET..].to_vec(),
+            })
+        }
+
+        /// Pack state into `z` by [layout](self)
+        pub fn into_z<const ARITY: usize>(self) -> Result<[T; ARITY], SynthesisError> {
+            let expected = REGISTERS_OFFSET + self.registers.len();
+            if expected != ARITY {
+                return Err(SynthesisError::VmStateLayout {
+                    expected,
+                    actual: ARITY,
+                });
+            }
+
+            let mut z = Vec::with_capacity(ARITY);
+            z.push(self.pc);
+            z.push(self.memory_root);
+            z.extend(self.registers);
+
+            Ok(z.try_into()
+                .unwrap_or_else(|_| unreachable!("length checked above")))
+        }
+    }
+
+    /// Step of zkVM: takes current machine state & returns the next one
+    ///
+    /// Use [`VmStep`] wrapper to pass it into IVC as a [`StepCircuit`]
+    pub trait VmStepCircuit<const ARITY: usize, F: PrimeField> {
+        /// Same as [`StepCircuit::Config`]
+        type Config: Clone;
+
+        /// Same as [`StepCircuit::configure`]
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config;
+
+        /// Synthesize transition from `state` to the next state
+        ///
+        /// The number of registers in returned state must be the same as in input
+        fn synthesize_transition(
+            &self,
+            config: Self::Config,
+            layouter: &mut impl Layouter<F>,
+            state: VmState<AssignedCell<F, F>>,
+        ) -> Result<VmState<AssignedCell<F, F>>, SynthesisError>;
+    }
+
+    /// Adapter of [`VmStepCircuit`] to [`StepCircuit`], packs & unpacks [`VmState`] to `z`
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    pub struct VmStep<VM>(pub VM);
+
+    impl<const ARITY: usize, F, VM> StepCircuit<ARITY, F> for VmStep<VM>
+    where
+        F: PrimeField,
+        VM: VmStepCircuit<ARITY, F>,
+    {
+        type Config = VM::Config;
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            VM::configure(cs)
+        }
+
+        fn synthesize_step(
+            &self,
+            config: Self::Config,
+            layouter: &mut impl Layouter<F>,
+            z_i: &[AssignedCell<F, F>; ARITY],
+        ) -> Result<[AssignedCell<F, F>; ARITY], SynthesisError> {
+            let state = VmState::from_z(z_i)?;
+            self.0
+                .synthesize_transition(config, layouter, state)?
+                .into_z()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use ff::Field;
+        use halo2curves::pasta::Fp;
+
+        use super::*;
+        use crate::main_gate::{MainGate, MainGateConfig, RegionCtx, WrapValue};
+
+        /// Machine with two registers, which on each step does `pc += 1; r0 += r1`
+        struct AddVm;
+
+        impl VmStepCircuit<4, Fp> for AddVm {
+            type Config = MainGateConfig<2>;
+
+            fn configure(cs: &mut ConstraintSystem<Fp>) -> Self::Config {
+                MainGate::configure(cs)
+            }
+
+            fn synthesize_transition(
+                &self,
+                config: Self::Config,
+                layouter: &mut impl Layouter<Fp>,
+                state: VmState<AssignedCell<Fp, Fp>>,
+            ) -> Result<VmState<AssignedCell<Fp, Fp>>, SynthesisError> {
+                let gate = MainGate::new(config);
+                layouter
+                    .assign_region(
+                        || "add vm",
+                        |region| {
+                            let ctx = &mut RegionCtx::new(region, 0);
+
+                            let pc = gate.linear_combination(
+                                ctx,
+                                &[(Fp::ONE, WrapValue::Assigned(state.pc.clone()))],
+                                Fp::ONE,
+                            )?;
+                            let r0 = gate.linear_combination(
+                                ctx,
+                                &[
+                                    (Fp::ONE, WrapValue::Assigned(state.registers[0].clone())),
+                                    (Fp::ONE, WrapValue::Assigned(state.registers[1].clone())),
+                                ],
+                                Fp::ZERO,
+                            )?;
+
+                            Ok(VmState {
+                                pc,
+                                memory_root: state.memory_root.clone(),
+                                registers: vec![r0, state.registers[1].clone()],
+                            })
+                        },
+                    )
+                    .map_err(SynthesisError::Halo2)
+            }
+        }
+
+        #[test]
+        fn layout() {
+            let state = VmState::from_z(&[1, 2, 3, 4]).unwrap();
+            assert_eq!(
+                state,
+                VmState {
+                    pc: 1,
+                    memory_root: 2,
+                    registers: vec![3, 4]
+                }
+            );
+            assert_eq!(state.clone().into_z::<4>().unwrap(), [1, 2, 3, 4]);
+
+            assert!(matches!(
+                state.into_z::<3>(),
+                Err(SynthesisError::VmStateLayout {
+                    expected: 4,
+                    actual: 3
+                })
+            ));
+            assert!(matches!(
+                VmState::from_z(&[1]),
+                Err(SynthesisError::VmStateLayout {
+                    expected: 2,
+                    actual: 1
+                })
+            ));
+        }
+
+        #[test]
+        fn process_step() {
+            let z_i = [Fp::from(7), Fp::from(42), Fp::from(10), Fp::from(5)];
+            let z_out = VmStep(AddVm).process_step(&z_i, 10).unwrap();
+
+            assert_eq!(
+                z_out,
+                [Fp::from(8), Fp::from(42), Fp::from(15), Fp::from(5)]
+            );
+        }
+    }
+}
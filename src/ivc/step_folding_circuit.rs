@@ -21,7 +21,7 @@ use crate::{
     main_gate::{AdviceCyclicAssignor, MainGate, MainGateConfig, RegionCtx},
     plonk::{PlonkInstance, RelaxedPlonkInstance},
     poseidon::ROCircuitTrait,
-    table::ConstraintSystemMetainfo,
+    table::{ConstantsConfig, ConstraintSystemMetainfo},
 };
 
 use super::instance_computation::AssignedRandomOracleComputationInstance;
@@ -107,23 +107,29 @@ where
     C: CurveAffine,
     RO: ROCircuitTrait<C::Base>,
 {
+    /// Fails with [`halo2_proofs::plonk::Error::Synthesis`] if gates of `PairedCircuit` can't
+    /// be converted, see [`ConstraintSystemMetainfo::build`]
     pub fn without_witness<PairedCircuit: Circuit<C::Scalar>>(
         k_table_size: u32,
         num_io: usize,
         step_pp: &'link StepParams<C::Base, RO>,
-    ) -> Self {
+    ) -> Result<Self, halo2_proofs::plonk::Error> {
         let mut cs = ConstraintSystem::<C::Scalar>::default();
 
         PairedCircuit::configure(&mut cs);
+        ConstantsConfig::configure(&mut cs);
 
         let ConstraintSystemMetainfo {
             num_challenges,
             round_sizes,
             folding_degree,
             ..
-        } = ConstraintSystemMetainfo::build(k_table_size as usize, &cs);
+        } = ConstraintSystemMetainfo::build(k_table_size as usize, &cs).map_err(|err| {
+            error!("{err}");
+            halo2_proofs::plonk::Error::Synthesis
+        })?;
 
-        Self {
+        Ok(Self {
             step: C::Base::ZERO,
             step_pp,
             public_params_hash: C::identity(),
@@ -132,7 +138,7 @@ where
             U: RelaxedPlonkInstance::new(num_io, num_challenges, round_sizes.len()),
             u: PlonkInstance::new(num_io, num_challenges, round_sizes.len()),
             cross_term_commits: vec![C::identity(); folding_degree.saturating_sub(1)],
-        }
+        })
     }
 }
 
@@ -3,6 +3,7 @@
 #![allow(dead_code)] // TODO: remove it later
 #![allow(non_snake_case)] // UPPER_CASE is used for ease of compatibility with Nova documentation
 
+pub mod artifact;
 pub mod commitment;
 pub mod constants;
 pub mod digest;
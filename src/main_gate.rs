@@ -195,6 +195,10 @@ pub enum WrapValue<F: PrimeField> {
     Assigned(AssignedValue<F>),
     Unassigned(Value<F>),
     Zero,
+    /// Value defined by the circuit itself, it's pinned by fixed column instead of advice cell
+    ///
+    /// Only [`crate::poseidon::poseidon_circuit::PoseidonChip`] input supports it
+    Constant(F),
 }
 
 impl<F: PrimeField> WrapValue<F> {
@@ -224,6 +228,7 @@ impl<F: PrimeField> WrapValue<F> {
             WrapValue::Assigned(v) => v.value().copied(),
             WrapValue::Unassigned(v) => *v,
             WrapValue::Zero => Value::known(F::ZERO),
+            WrapValue::Constant(v) => Value::known(*v),
         }
     }
 }
@@ -595,7 +600,10 @@ impl<F: PrimeField, const T: usize> MainGate<F, T> {
                         )?;
                         ctx.constrain_equal(si.cell(), avv.cell())?;
                     }
-                    _ => {}
+                    WrapValue::Zero => {}
+                    WrapValue::Constant(_) => {
+                        unimplemented!() // this is not allowed
+                    }
                 }
             }
         }
@@ -613,7 +621,7 @@ impl<F: PrimeField, const T: usize> MainGate<F, T> {
                 ctx.constrain_equal(out.cell(), avv.cell())?;
                 out
             }
-            WrapValue::Zero => {
+            WrapValue::Zero | WrapValue::Constant(_) => {
                 unimplemented!() // this is not allowed
             }
         };
@@ -650,7 +658,10 @@ impl<F: PrimeField, const T: usize> MainGate<F, T> {
                         )?;
                         ctx.constrain_equal(si.cell(), avv.cell())?;
                     }
-                    _ => {}
+                    WrapValue::Zero => {}
+                    WrapValue::Constant(_) => {
+                        unimplemented!() // this is not allowed
+                    }
                 }
             }
         }
@@ -668,7 +679,10 @@ impl<F: PrimeField, const T: usize> MainGate<F, T> {
                         ctx.assign_advice(|| "input", self.config.input, avv.value().copied())?;
                     ctx.constrain_equal(si.cell(), avv.cell())?;
                 }
-                _ => {}
+                WrapValue::Zero => {}
+                WrapValue::Constant(_) => {
+                    unimplemented!() // this is not allowed
+                }
             }
         }
 
@@ -681,7 +695,7 @@ impl<F: PrimeField, const T: usize> MainGate<F, T> {
                 ctx.constrain_equal(out.cell(), avv.cell())?;
                 out
             }
-            WrapValue::Zero => {
+            WrapValue::Zero | WrapValue::Constant(_) => {
                 unimplemented!() // this is not allowed
             }
         };
@@ -689,41 +703,87 @@ impl<F: PrimeField, const T: usize> MainGate<F, T> {
         Ok(res)
     }
 
-    // calculate sum_{i=0}^d r^i terms[i]
-    pub fn random_linear_combination(
+    /// Calculates `constant + sum_i coeff_i * term_i` for `terms` given as `(coeff_i, term_i)`
+    ///
+    /// Each row takes up to `T` terms into `state` with coefficients in `q_1` and the result of
+    /// the previous row as `input`, so it takes `ceil(terms.len() / T)` rows, but at least one
+    pub fn linear_combination(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        terms: Vec<F>,
-        r: F,
+        terms: &[(F, WrapValue<F>)],
+        constant: F,
     ) -> Result<AssignedValue<F>, Error> {
-        let d = terms.len();
+        if terms.is_empty() {
+            return self.apply(
+                ctx,
+                (None, None, None),
+                Some(constant),
+                (-F::ONE, WrapValue::Unassigned(Value::known(constant))),
+            );
+        }
+
         let mut out: Option<AssignedValue<F>> = None;
-        for i in 1..d {
-            let lhs_val = Value::known(terms[d - 1 - i]);
-            let rhs_val = if i == 1 {
-                Value::known(terms[d - i])
-            } else {
-                out.as_ref().unwrap().value().copied()
+        for (row, chunk) in terms.chunks(T).enumerate() {
+            let mut out_val = match &out {
+                Some(prev) => {
+                    ctx.assign_fixed(|| "q_i", self.config.q_i, F::ONE)?;
+                    let input =
+                        ctx.assign_advice(|| "input", self.config.input, prev.value().copied())?;
+                    ctx.constrain_equal(input.cell(), prev.cell())?;
+                    input.value().copied()
+                }
+                None => Value::known(F::ZERO),
             };
-            let r_val = Value::known(r);
-            ctx.assign_advice(|| "input", self.config.input, lhs_val)?;
-            let rhs = ctx.assign_advice(|| "s[1]", self.config.state[1], rhs_val)?;
-            if out.is_some() {
-                ctx.constrain_equal(rhs.cell(), out.unwrap().cell())?;
+
+            if row == 0 {
+                ctx.assign_fixed(|| "rc", self.config.rc, constant)?;
+                out_val = out_val + Value::known(constant);
+            }
+
+            for (i, (coeff, term)) in chunk.iter().enumerate() {
+                ctx.assign_fixed(|| "q_1", self.config.q_1[i], *coeff)?;
+                match term {
+                    WrapValue::Unassigned(val) => {
+                        ctx.assign_advice(|| "state", self.config.state[i], *val)?;
+                    }
+                    WrapValue::Assigned(cell) => {
+                        let si = ctx.assign_advice(
+                            || "state",
+                            self.config.state[i],
+                            cell.value().copied(),
+                        )?;
+                        ctx.constrain_equal(si.cell(), cell.cell())?;
+                    }
+                    WrapValue::Zero => {}
+                    WrapValue::Constant(_) => {
+                        unimplemented!() // this is not allowed
+                    }
+                }
+                out_val = out_val + term.value() * Value::known(*coeff);
             }
-            ctx.assign_advice(|| "s[0]", self.config.state[0], r_val)?;
-            out = Some(ctx.assign_advice(
-                || "out=s[0]*s[1]+input",
-                self.config.out,
-                lhs_val + r_val * rhs_val,
-            )?);
-
-            ctx.assign_fixed(|| "q_i", self.config.q_i, F::ONE)?;
-            ctx.assign_fixed(|| "q_m", self.config.q_m[0], F::ONE)?;
+
             ctx.assign_fixed(|| "q_o", self.config.q_o, -F::ONE)?;
+            out = Some(ctx.assign_advice(|| "out", self.config.out, out_val)?);
             ctx.next();
         }
-        Ok(out.unwrap())
+
+        Ok(out.expect("terms are not empty"))
+    }
+
+    // calculate sum_{i=0}^d r^i terms[i]
+    pub fn random_linear_combination(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        terms: Vec<F>,
+        r: F,
+    ) -> Result<AssignedValue<F>, Error> {
+        let terms = terms
+            .into_iter()
+            .zip(iter::successors(Some(F::ONE), |power| Some(*power * r)))
+            .map(|(term, coeff)| (coeff, WrapValue::Unassigned(Value::known(term))))
+            .collect::<Vec<_>>();
+
+        self.linear_combination(ctx, &terms, F::ZERO)
     }
 }
 
@@ -794,6 +854,69 @@ impl<F: PrimeFieldBits, const T: usize> MainGate<F, T> {
 
         Ok(bits)
     }
+
+    /// Decomposes `value` into exactly `num_bits` little-endian bits
+    ///
+    /// Each bit is constrained to be boolean by [`MainGate::assign_bit`] and the weighted sum
+    /// `sum_i 2^i * bit_i`, composed with [`MainGate::le_bits_to_num`], is constrained to be
+    /// equal to `value`. Unlike [`MainGate::le_num_to_bits`] a value, that doesn't fit into
+    /// `num_bits`, does not panic during synthesis, it leaves the circuit unsatisfied.
+    ///
+    /// With `num_bits >= F::NUM_BITS` both `x` & `x + p` would be valid decompositions, so such
+    /// `num_bits` is rejected with [`Error::Synthesis`]
+    pub fn to_bits(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: &AssignedValue<F>,
+        num_bits: NonZeroUsize,
+    ) -> Result<Vec<AssignedValue<F>>, Error> {
+        if num_bits.get() >= F::NUM_BITS as usize {
+            return Err(Error::Synthesis);
+        }
+
+        let mut bits: Vec<bool> = value
+            .value()
+            .unwrap()
+            .map(|v| v.to_le_bits().into_iter().take(num_bits.get()).collect())
+            .unwrap_or_default();
+        bits.resize(num_bits.get(), false);
+
+        self.assign_decomposition(
+            ctx,
+            value,
+            bits.into_iter()
+                .map(|bit| Value::known(if bit { F::ONE } else { F::ZERO })),
+        )
+    }
+
+    /// Constrains `value` to fit into `num_bits` bits, i.e. `value < 2^num_bits`
+    ///
+    /// Same as [`MainGate::to_bits`], but drops the bits
+    pub fn range_check(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: &AssignedValue<F>,
+        num_bits: NonZeroUsize,
+    ) -> Result<(), Error> {
+        self.to_bits(ctx, value, num_bits).map(|_| ())
+    }
+
+    fn assign_decomposition(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: &AssignedValue<F>,
+        bits: impl IntoIterator<Item = Value<F>>,
+    ) -> Result<Vec<AssignedValue<F>>, Error> {
+        let bits = bits
+            .into_iter()
+            .map(|bit| self.assign_bit(ctx, bit))
+            .collect::<Result<Vec<_>, _>>()?;
+        let num = self.le_bits_to_num(ctx, &bits)?;
+
+        ctx.constrain_equal(value.cell(), num.cell())?;
+
+        Ok(bits)
+    }
 }
 
 #[cfg(test)]
@@ -803,9 +926,72 @@ mod tests {
         plonk::CompressedGates,
         polynomial::{expression::QueryIndexContext, Expression},
     };
+    use ff::Field;
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+    };
     use halo2curves::pasta::Fp;
     use tracing_test::traced_test;
 
+    /// Assigns `value` with [`MainGate::assign_bit`]
+    struct AssignBitCircuit {
+        value: Fp,
+    }
+
+    impl Circuit<Fp> for AssignBitCircuit {
+        type Config = MainGateConfig<2>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self { value: Fp::ZERO }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            MainGate::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let gate = MainGate::new(config);
+
+            layouter.assign_region(
+                || "bit",
+                |region| {
+                    let ctx = &mut RegionCtx::new(region, 0);
+                    gate.assign_bit(ctx, Value::known(self.value))?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn assign_bit() {
+        use halo2_proofs::dev::MockProver;
+
+        const K: u32 = 4;
+        let is_sat = |value: u64| {
+            MockProver::run(
+                K,
+                &AssignBitCircuit {
+                    value: Fp::from(value),
+                },
+                vec![],
+            )
+            .unwrap()
+            .verify()
+            .is_ok()
+        };
+
+        assert!(is_sat(0));
+        assert!(is_sat(1));
+        assert!(!is_sat(2));
+    }
+
     #[traced_test]
     #[test]
     fn main_gate_size_change() {
@@ -823,31 +1009,27 @@ mod tests {
         const RATE: usize = 2;
         let mut cs = ConstraintSystem::<Fp>::default();
         let _: MainGateConfig<T> = MainGate::configure(&mut cs);
-        let num_selector = cs.num_selectors(); // is zero for current main_gate design
-        let num_fixed = cs.num_fixed_columns();
         let num_instance = cs.num_instance_columns();
-        let num_advice = cs.num_advice_columns();
+        let ctx = QueryIndexContext {
+            num_fixed: cs.num_fixed_columns(),
+            num_advice: cs.num_advice_columns(),
+            num_selectors: cs.num_selectors(), // is zero for current main_gate design
+            num_challenges: cs.num_challenges(),
+            num_lookups: 0,
+            num_instance,
+        };
         let gates: Vec<Vec<Expression<Fp>>> = cs
             .gates()
             .iter()
             .map(|gate| {
                 gate.polynomials()
                     .iter()
-                    .map(|expr| Expression::from_halo2_expr(expr, num_selector, num_fixed))
-                    .collect()
+                    .map(|expr| Expression::from_halo2_expr(expr, &ctx))
+                    .collect::<Result<_, _>>()
+                    .unwrap()
             })
             .collect();
-        (
-            gates,
-            num_instance,
-            QueryIndexContext {
-                num_fixed,
-                num_advice,
-                num_selectors: cs.num_selectors(),
-                num_challenges: cs.num_challenges(),
-                num_lookups: 0,
-            },
-        )
+        (gates, num_instance, ctx)
     }
 
     #[test]
@@ -886,4 +1068,317 @@ mod tests {
             "r_1 * r_1 * r_1 * (Z_14 * Z_13 * Z_4 + r_1 * Z_15 * Z_6 + r_1 * r_1 * Z_8 + r_1 * Z_16 * Z_7) + r_1 * r_1 * r_1 * r_1 * Z_13 * Z_0 + Z_13 * Z_13 * Z_13 * Z_13 * Z_13 * Z_2 + r_1 * r_1 * r_1 * r_1 * Z_14 * Z_1 + Z_14 * Z_14 * Z_14 * Z_14 * Z_14 * Z_3"
         );
     }
+
+    struct TwoGadgetsCircuit;
+
+    impl Circuit<Fp> for TwoGadgetsCircuit {
+        type Config = (MainGateConfig<2>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            (MainGate::configure(meta), instance)
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let gate = MainGate::new(config);
+
+            let (first, second) = layouter.assign_region(
+                || "two gadgets",
+                |region| {
+                    let ctx = &mut RegionCtx::new(region, 0);
+
+                    // 1 + 2 * 2 + 3 * 2^2, takes two rows
+                    let first = gate.random_linear_combination(
+                        ctx,
+                        vec![Fp::from(1), Fp::from(2), Fp::from(3)],
+                        Fp::from(2),
+                    )?;
+                    assert_eq!(ctx.offset(), 2);
+
+                    // 4 + 5 * 3, takes one row right after the first gadget
+                    let second = gate.random_linear_combination(
+                        ctx,
+                        vec![Fp::from(4), Fp::from(5)],
+                        Fp::from(3),
+                    )?;
+                    assert_eq!(ctx.offset(), 3);
+
+                    Ok((first, second))
+                },
+            )?;
+
+            layouter.constrain_instance(first.cell(), instance, 0)?;
+            layouter.constrain_instance(second.cell(), instance, 1)?;
+
+            Ok(())
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn consecutive_gadgets_layout() {
+        crate::run_mock_prover_test!(4, TwoGadgetsCircuit, vec![vec![Fp::from(17), Fp::from(19)]]);
+    }
+
+    #[derive(Default)]
+    struct LinearCombinationCircuit {
+        terms: Vec<Fp>,
+        r: Fp,
+        constant: Fp,
+    }
+
+    impl Circuit<Fp> for LinearCombinationCircuit {
+        type Config = (MainGateConfig<2>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            (MainGate::configure(meta), instance)
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let gate = MainGate::new(config.clone());
+
+            let (rlc, lc, lc_with_constant) = layouter.assign_region(
+                || "linear combination",
+                |region| {
+                    let ctx = &mut RegionCtx::new(region, 0);
+
+                    let rlc = gate.random_linear_combination(ctx, self.terms.clone(), self.r)?;
+
+                    let terms = self
+                        .terms
+                        .iter()
+                        .zip(iter::successors(Some(Fp::ONE), |power| {
+                            Some(power * self.r)
+                        }))
+                        .map(|(term, coeff)| {
+                            let cell =
+                                ctx.assign_advice(|| "term", config.input, Value::known(*term))?;
+                            ctx.next();
+                            Ok((coeff, WrapValue::Assigned(cell)))
+                        })
+                        .collect::<Result<Vec<_>, Error>>()?;
+
+                    Ok((
+                        rlc,
+                        gate.linear_combination(ctx, &terms, Fp::ZERO)?,
+                        gate.linear_combination(ctx, &terms, self.constant)?,
+                    ))
+                },
+            )?;
+
+            layouter.constrain_instance(rlc.cell(), instance, 0)?;
+            layouter.constrain_instance(lc.cell(), instance, 0)?;
+            layouter.constrain_instance(lc_with_constant.cell(), instance, 1)?;
+
+            Ok(())
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn linear_combination_as_rlc() {
+        let terms = (1..8).map(Fp::from).collect::<Vec<_>>();
+        let r = Fp::from(3);
+        let constant = Fp::from(5);
+
+        let rlc = terms
+            .iter()
+            .rev()
+            .fold(Fp::ZERO, |acc, term| acc * r + term);
+
+        crate::run_mock_prover_test!(
+            5,
+            LinearCombinationCircuit { terms, r, constant },
+            vec![vec![rlc, rlc + constant]]
+        );
+    }
+
+    struct RangeCheckCircuit {
+        value: Fp,
+        num_bits: NonZeroUsize,
+    }
+
+    impl Circuit<Fp> for RangeCheckCircuit {
+        type Config = MainGateConfig<2>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Fp::ZERO,
+                num_bits: self.num_bits,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            MainGate::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let gate = MainGate::new(config);
+
+            layouter.assign_region(
+                || "range check",
+                |region| {
+                    let ctx = &mut RegionCtx::new(region, 0);
+                    let value = gate.assign_value(ctx, Value::known(self.value))?;
+                    gate.range_check(ctx, &value, self.num_bits)
+                },
+            )
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn range_check() {
+        use halo2_proofs::dev::MockProver;
+
+        const K: u32 = 5;
+        let num_bits = NonZeroUsize::new(8).unwrap();
+
+        for value in [0, 1, 200, 255] {
+            crate::run_mock_prover_test!(
+                K,
+                RangeCheckCircuit {
+                    value: Fp::from(value),
+                    num_bits
+                },
+                vec![]
+            );
+        }
+
+        for value in [Fp::from(256), Fp::from(1000), -Fp::ONE] {
+            let prover =
+                MockProver::run(K, &RangeCheckCircuit { value, num_bits }, vec![]).unwrap();
+            assert!(
+                prover.verify().is_err(),
+                "{value:?} must not fit into {num_bits} bits"
+            );
+        }
+
+        // `x` & `x + p` are both decompositions into `F::NUM_BITS` bits
+        let num_bits = NonZeroUsize::new(Fp::NUM_BITS as usize).unwrap();
+        assert!(matches!(
+            MockProver::run(
+                K,
+                &RangeCheckCircuit {
+                    value: Fp::ONE,
+                    num_bits
+                },
+                vec![]
+            ),
+            Err(Error::Synthesis)
+        ));
+    }
+
+    struct ToBitsCircuit {
+        value: Fp,
+        /// If present, assigned instead of honest decomposition of `value`
+        tampered_bits: Option<Vec<Fp>>,
+    }
+
+    impl Circuit<Fp> for ToBitsCircuit {
+        type Config = MainGateConfig<2>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Fp::ZERO,
+                tampered_bits: None,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            MainGate::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let gate = MainGate::new(config);
+
+            layouter.assign_region(
+                || "to bits",
+                |region| {
+                    let ctx = &mut RegionCtx::new(region, 0);
+                    let value = gate.assign_value(ctx, Value::known(self.value))?;
+
+                    let bits = match &self.tampered_bits {
+                        Some(bits) => gate.assign_decomposition(
+                            ctx,
+                            &value,
+                            bits.iter().copied().map(Value::known),
+                        )?,
+                        None => gate.to_bits(ctx, &value, NonZeroUsize::new(8).unwrap())?,
+                    };
+                    assert_eq!(bits.len(), 8);
+
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn to_bits() {
+        use halo2curves::{pasta::EqAffine, CurveAffine};
+
+        use crate::{commitment::CommitmentKey, table::CircuitRunner, util::create_ro};
+
+        const K: u32 = 5;
+        let ck = CommitmentKey::<EqAffine>::setup(K as usize + 2, b"to_bits");
+        let new_ro = create_ro::<<EqAffine as CurveAffine>::Base, 3, 2, 4, 3>;
+
+        let is_sat = |circuit: ToBitsCircuit| {
+            let runner = CircuitRunner::new(K, circuit, vec![]);
+            let S = runner.try_collect_plonk_structure().unwrap();
+            let witness = runner.try_collect_witness().unwrap();
+
+            let (u, w) = S
+                .run_sps_protocol(&ck, &[], &witness, &mut new_ro(), S.num_challenges)
+                .unwrap();
+            S.is_sat(&ck, &mut new_ro(), &u, &w).is_ok()
+                && S.is_sat_perm(&u.to_relax(), &w.into_relax(S.k)).is_ok()
+        };
+
+        assert!(is_sat(ToBitsCircuit {
+            value: Fp::from(6),
+            tampered_bits: None,
+        }));
+
+        // 3 * 2^1 = 6, so weighted sum is kept & only boolean constraint of bits is broken
+        let mut tampered_bits = vec![Fp::ZERO; 8];
+        tampered_bits[1] = Fp::from(3);
+        assert!(!is_sat(ToBitsCircuit {
+            value: Fp::from(6),
+            tampered_bits: Some(tampered_bits),
+        }));
+    }
 }
@@ -8,14 +8,17 @@
 //! For more details look at:
 //! - Paragraph '3. Folding scheme' at [Nova whitepaper](https://eprint.iacr.org/2021/370)
 //! - [nifs module](https://github.com/microsoft/Nova/blob/main/src/nifs.rs) at [Nova codebase](https://github.com/microsoft/Nova)
+use std::{io, sync::Arc};
+
 use rayon::prelude::*;
+use serde::Serialize;
 
 use halo2_proofs::arithmetic::CurveAffine;
 use halo2_proofs::plonk::Error as Halo2Error;
 
 use crate::commitment::{self, CommitmentKey};
 use crate::plonk::eval::Error as EvalError;
-use crate::plonk::{PlonkInstance, PlonkStructure, PlonkTrace};
+use crate::plonk::{Error as PlonkError, PlonkInstance, PlonkStructure, PlonkTrace};
 use crate::poseidon::ROTrait;
 use crate::sps::Error as SpsError;
 
@@ -39,10 +42,15 @@ pub trait FoldingScheme<C: CurveAffine> {
     /// The proof send from prover to verifier
     type Proof;
 
+    /// `S` is shared with prover params instead of being copied, so `Arc` can be passed to
+    /// avoid copy of fixed columns & other big data of structure
     fn setup_params(
         pp_digest: C,
-        S: PlonkStructure<C::ScalarExt>,
-    ) -> Result<(Self::ProverParam, Self::VerifierParam), Error>;
+        S: impl Into<Arc<PlonkStructure<C::ScalarExt>>>,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), Error>
+    where
+        C: Serialize,
+        C::ScalarExt: Serialize;
 
     fn generate_plonk_trace(
         ck: &CommitmentKey<C>,
@@ -105,6 +113,26 @@ pub enum Error {
     Plonk(#[from] Halo2Error),
     #[error(transparent)]
     Commitment(#[from] commitment::Error),
+    #[error(transparent)]
+    Fold(#[from] PlonkError),
+    #[error("Wrong count of cross term commits: expected {expected}, but actual {actual}")]
+    WrongCrossTermCommitsCount { expected: usize, actual: usize },
+    #[error("Wrong count of merge proofs: expected {expected}, but actual {actual}")]
+    WrongTreeProofsCount { expected: usize, actual: usize },
+    #[error("Nothing to fold: tree has no leaves")]
+    EmptyTree,
+    #[error("Wrong length of error vector: expected {expected}, but actual {actual}")]
+    WrongErrorVectorLength { expected: usize, actual: usize },
+    #[error(
+        "Folded traces don't match structure: {field} expected {expected}, but actual {actual}"
+    )]
+    StructureMismatch {
+        field: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Error while calculate digest of structure")]
+    WhileDigest(#[from] io::Error),
 }
 
 #[cfg(test)]
@@ -1,4 +1,4 @@
-use std::marker::PhantomData;
+use std::{marker::PhantomData, sync::Arc};
 
 use ff::PrimeField;
 use halo2_proofs::arithmetic::CurveAffine;
@@ -20,7 +20,7 @@ pub struct ProtoGalaxy<C: CurveAffine> {
 }
 
 pub struct ProtoGalaxyProverParam<C: CurveAffine> {
-    pub(crate) S: PlonkStructure<C::ScalarExt>,
+    pub(crate) S: Arc<PlonkStructure<C::ScalarExt>>,
     /// digest of public parameter of IVC circuit
     pp_digest: C,
 }
@@ -40,9 +40,15 @@ impl<C: CurveAffine> FoldingScheme<C> for ProtoGalaxy<C> {
 
     fn setup_params(
         pp_digest: C,
-        S: PlonkStructure<C::ScalarExt>,
+        S: impl Into<Arc<PlonkStructure<C::ScalarExt>>>,
     ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
-        Ok((ProtoGalaxyProverParam { S, pp_digest }, pp_digest))
+        Ok((
+            ProtoGalaxyProverParam {
+                S: S.into(),
+                pp_digest,
+            },
+            pp_digest,
+        ))
     }
 
     // TODO: if this function turned out to be the same, consider move to trait
@@ -10,7 +10,7 @@ use tracing::*;
 
 use crate::{
     fft,
-    plonk::{self, eval, GetChallenges, GetWitness, PlonkStructure},
+    plonk::{self, eval, GetChallenges, GetInstance, GetWitness, PlonkStructure},
     polynomial::{lagrange, univariate::UnivariatePoly},
 };
 
@@ -55,7 +55,10 @@ pub(crate) fn compute_F<C: CurveAffine>(
     beta: C::ScalarExt,
     delta: C::ScalarExt,
     S: &PlonkStructure<C::ScalarExt>,
-    trace: &(impl Sync + GetChallenges<C::ScalarExt> + GetWitness<C::ScalarExt>),
+    trace: &(impl Sync
+          + GetChallenges<C::ScalarExt>
+          + GetWitness<C::ScalarExt>
+          + GetInstance<C::ScalarExt>),
 ) -> Result<UnivariatePoly<C::ScalarExt>, Error> {
     let count_of_rows = 2usize.pow(S.k as u32);
     let count_of_gates = S.gates.len();
@@ -1,6 +1,6 @@
 use std::marker::PhantomData;
 
-use ff::{PrimeField, PrimeFieldBits};
+use ff::{Field, PrimeField, PrimeFieldBits};
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
     plonk::{self, Advice, Circuit, Column, ConstraintSystem, Instance, Selector},
@@ -8,11 +8,13 @@ use halo2_proofs::{
 };
 use halo2curves::bn256::{Fr, G1Affine};
 use halo2curves::group::ff::FromUniformBytes;
+use serde::Serialize;
 use some_to_err::*;
 
 use crate::nifs::{self, vanilla::VanillaFS};
 use crate::plonk::{
-    PlonkStructure, PlonkTrace, RelaxedPlonkInstance, RelaxedPlonkTrace, RelaxedPlonkWitness,
+    CompressedWitness, PlonkInstance, PlonkStructure, PlonkTrace, PlonkWitness,
+    RelaxedPlonkInstance, RelaxedPlonkTrace, RelaxedPlonkWitness,
 };
 use crate::table::CircuitRunner;
 use crate::util::create_ro;
@@ -25,6 +27,8 @@ enum Error<C: CurveAffine> {
     Nifs(#[from] nifs::Error),
     #[error(transparent)]
     Plonk(#[from] plonk::Error),
+    #[error(transparent)]
+    Relation(#[from] crate::plonk::Error),
     #[error("while verify: {errors:?}")]
     Verify {
         errors: Vec<(&'static str, crate::plonk::Error)>,
@@ -67,8 +71,8 @@ fn prepare_trace<C, F1, F2, CT>(
     Error<C>,
 >
 where
-    C: CurveAffine<ScalarExt = F1, Base = F2>,
-    F1: PrimeField,
+    C: CurveAffine<ScalarExt = F1, Base = F2> + Serialize,
+    F1: PrimeField + Serialize,
     F2: PrimeFieldBits + FromUniformBytes<64>,
     CT: Circuit<F1>,
 {
@@ -96,11 +100,11 @@ where
 
     let pair1 =
         VanillaFS::generate_plonk_trace(&ck, &public_inputs1, &W1, &pp, &mut ro_nark_prepare)?;
-    let pair1_relaxed = pair1.to_relax(S.k);
+    let pair1_relaxed = pair1.clone().into_relax(S.k);
 
     let pair2 =
         VanillaFS::generate_plonk_trace(&ck, &public_inputs2, &W2, &pp, &mut ro_nark_prepare)?;
-    let pair2_relaxed = pair2.to_relax(S.k);
+    let pair2_relaxed = pair2.clone().into_relax(S.k);
 
     let mut errors = Vec::new();
 
@@ -145,8 +149,8 @@ fn fold_instances<C, F1, F2>(
     pp_digest: C,
 ) -> Result<(), Error<C>>
 where
-    C: CurveAffine<ScalarExt = F1, Base = F2>,
-    F1: PrimeField,
+    C: CurveAffine<ScalarExt = F1, Base = F2> + Serialize,
+    F1: PrimeField + Serialize,
     F2: PrimeFieldBits + FromUniformBytes<64>,
 {
     const T: usize = 3;
@@ -173,6 +177,16 @@ where
         },
         pair1,
     )?;
+    assert_eq!(cross_term_commits.len(), S.num_cross_terms());
+
+    let r = VanillaFS::derive_fold_challenge(
+        &pp.pp_digest,
+        &mut create_ro::<C::Base, T, RATE, R_F, R_P>(),
+        &f_U,
+        &pair1.u,
+        &cross_term_commits,
+    )?;
+    assert_eq!(f_U.fold(&pair1.u, &cross_term_commits, &r)?, U_from_prove);
 
     let U_from_verify = VanillaFS::verify(
         &vp,
@@ -191,6 +205,14 @@ where
     if let Err(err) = S.is_sat_relaxed(ck, &f_U, &f_W) {
         errors.push(("is_sat_relaxed 1", err));
     }
+    match S.compute_E(&f_U, &f_W) {
+        Ok(E) => assert_eq!(E.to_vec(), f_W.E.to_vec()),
+        Err(err) => errors.push(("compute_E 1", err)),
+    }
+    match S.compute_E_columnwise(&f_U, &f_W) {
+        Ok(E) => assert_eq!(E.to_vec(), f_W.E.to_vec()),
+        Err(err) => errors.push(("compute_E_columnwise 1", err)),
+    }
     if let Err(err) = S.is_sat_perm(&f_U, &f_W) {
         errors.push(("is_sat_perm 1", err));
     }
@@ -231,6 +253,7 @@ where
     if let Err(err) = S.is_sat_perm(&f_U, &f_W) {
         errors.push(("is_sat_perm 2", err));
     }
+
     if errors.is_empty() {
         Ok(())
     } else {
@@ -250,11 +273,77 @@ fn smallest_power(n: usize, K: u32) -> usize {
 mod zero_round_test {
     use tracing_test::traced_test;
 
-    use crate::main_gate::{MainGate, MainGateConfig, RegionCtx};
+    use crate::{
+        main_gate::{MainGate, MainGateConfig, RegionCtx},
+        poseidon::PoseidonHash,
+    };
 
     use super::*;
 
     const T: usize = 3;
+    const K: u32 = 4;
+
+    fn new_ro() -> PoseidonHash<<G1Affine as CurveAffine>::Base, T, 2> {
+        create_ro::<<G1Affine as CurveAffine>::Base, T, 2, 4, 3>()
+    }
+
+    /// Structure & two traces of [`TestCircuit`] with outputs `4097` & `93494`, both traces are
+    /// checked by [`prepare_trace`]
+    fn prepare_test_trace() -> Result<
+        (
+            CommitmentKey<G1Affine>,
+            PlonkStructure<Fr>,
+            PlonkTrace<G1Affine>,
+            PlonkTrace<G1Affine>,
+        ),
+        Error<G1Affine>,
+    > {
+        prepare_trace(
+            K,
+            TestCircuit::new((1..10).map(Fr::from).collect(), Fr::from_u128(2)),
+            TestCircuit::new((2..11).map(Fr::from).collect(), Fr::from_u128(3)),
+            vec![Fr::from_u128(4097)],
+            vec![Fr::from_u128(93494)],
+            G1Affine::default(),
+        )
+    }
+
+    /// Runner of the first circuit of [`prepare_test_trace`] & its public inputs, for tests
+    /// that produce the trace on their own
+    fn test_runner() -> (CircuitRunner<Fr, TestCircuit<Fr>>, Vec<Fr>) {
+        let circuit = TestCircuit::new((1..10).map(Fr::from).collect(), Fr::from_u128(2));
+        let public_inputs = vec![Fr::from_u128(4097)];
+        (
+            CircuitRunner::new(K, circuit, public_inputs.clone()),
+            public_inputs,
+        )
+    }
+
+    /// Accumulator, that any trace of `S` is folded into
+    fn zero_accumulator(S: &PlonkStructure<Fr>) -> RelaxedPlonkTrace<G1Affine> {
+        RelaxedPlonkTrace {
+            U: RelaxedPlonkInstance::new(S.num_io, S.num_challenges, S.round_sizes.len()),
+            W: RelaxedPlonkWitness::new(S.k, &S.round_sizes),
+        }
+    }
+
+    /// `pair2` folded into relaxed `pair1`
+    fn fold_test_trace(
+        ck: &CommitmentKey<G1Affine>,
+        S: &PlonkStructure<Fr>,
+        pair1: &PlonkTrace<G1Affine>,
+        pair2: &PlonkTrace<G1Affine>,
+    ) -> Result<RelaxedPlonkTrace<G1Affine>, Error<G1Affine>> {
+        let (pp, _vp) = VanillaFS::setup_params(G1Affine::default(), S.clone())?;
+        let (folded, _) = VanillaFS::prove(
+            ck,
+            &pp,
+            &mut new_ro(),
+            &pair1.clone().into_relax(S.k),
+            pair2,
+        )?;
+        Ok(folded)
+    }
 
     #[derive(Clone, Debug)]
     struct TestCircuitConfig {
@@ -314,27 +403,434 @@ mod zero_round_test {
     #[traced_test]
     #[test]
     fn test_nifs() -> Result<(), Error<G1Affine>> {
-        const K: u32 = 4;
-        let inputs1 = (1..10).map(Fr::from).collect();
-        let inputs2 = (2..11).map(Fr::from).collect();
-        let circuit1 = TestCircuit::new(inputs1, Fr::from_u128(2));
-        let output1 = Fr::from_u128(4097);
-        let public_inputs1 = vec![output1];
+        let (ck, S, pair1, pair2) = prepare_test_trace()?;
+        // single gate with `q_5 * s^5` term
+        assert_eq!(S.num_cross_terms(), 5);
+        assert_eq!(S.cross_term_len(), 1 << K);
+        fold_instances(&ck, &S, &pair1, &pair2, G1Affine::default())
+    }
 
-        let circuit2 = TestCircuit::new(inputs2, Fr::from_u128(3));
-        let output2 = Fr::from_u128(93494);
-        let public_inputs2 = vec![output2];
+    #[traced_test]
+    #[test]
+    fn cross_term_commits_order() -> Result<(), Error<G1Affine>> {
+        let (ck, S, pair1, pair2) = prepare_test_trace()?;
+        let acc = pair1.clone().into_relax(S.k);
 
-        let (ck, S, pair1, pair2) = prepare_trace(
-            K,
-            circuit1,
-            circuit2,
-            public_inputs1,
-            public_inputs2,
-            G1Affine::default(),
+        let (cross_terms, cross_term_commits) =
+            VanillaFS::commit_cross_terms(&ck, &S, &acc.U, &acc.W, &pair2.u, &pair2.w)?;
+        assert_eq!(cross_terms.len(), S.num_cross_terms());
+
+        let mut serial = Vec::new();
+        for T in cross_terms.iter() {
+            serial.push(ck.commit(T).unwrap());
+        }
+        assert_eq!(cross_term_commits, serial);
+
+        assert_eq!(
+            VanillaFS::compute_cross_terms(&S, &acc.U, &acc.W, &pair2.u, &pair2.w)?,
+            cross_terms
+        );
+
+        Ok(())
+    }
+
+    #[traced_test]
+    #[test]
+    fn verify_any_trace() -> Result<(), Error<G1Affine>> {
+        let (ck, S, pair1, pair2) = prepare_test_trace()?;
+        let folded = fold_test_trace(&ck, &S, &pair1, &pair2)?;
+
+        let mut ro_nark = new_ro();
+        S.verify(&ck, &mut ro_nark, &pair1)?;
+        S.verify(&ck, &mut ro_nark, &folded)?;
+
+        let (verdict, profile) = S.is_sat_relaxed_profiled(&ck, &folded.U, &folded.W);
+        assert_eq!(verdict, Ok(()));
+        assert!(profile.failing_rows.is_empty());
+
+        let mut broken = folded.clone();
+        *broken.W.E.get_mut(0).unwrap() += Fr::ONE;
+        assert!(S.verify(&ck, &mut ro_nark, &broken).is_err());
+
+        // profiled & plain versions share the verdict
+        let (verdict, profile) = S.is_sat_relaxed_profiled(&ck, &folded.U, &broken.W);
+        assert!(matches!(
+            verdict,
+            Err(crate::plonk::Error::EvaluationMismatch { .. })
+        ));
+        assert_eq!(verdict, S.is_sat_relaxed(&ck, &folded.U, &broken.W));
+        assert_eq!(profile.failing_rows, vec![0]);
+
+        let mut broken_U = folded.U.clone();
+        broken_U.E_commitment = broken_U.W_commitments[0];
+        let (verdict, _profile) = S.is_sat_relaxed_profiled(&ck, &broken_U, &folded.W);
+        assert_eq!(verdict, Err(crate::plonk::Error::ECommitmentMismatch));
+        assert_eq!(verdict, S.is_sat_relaxed(&ck, &broken_U, &folded.W));
+
+        Ok(())
+    }
+
+    #[traced_test]
+    #[test]
+    fn residuals() -> Result<(), Error<G1Affine>> {
+        use rayon::prelude::*;
+
+        let (_ck, S, pair1, _pair2) = prepare_test_trace()?;
+
+        let mut residuals = S
+            .residuals(&pair1.u, &pair1.w)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        residuals.sort_by_key(|(row, _)| *row);
+        assert_eq!(
+            residuals,
+            (0..1 << K).map(|row| (row, Fr::ZERO)).collect::<Vec<_>>()
+        );
+
+        let mut broken = pair1.w.clone();
+        broken.W[0][0] += Fr::ONE;
+        let failing_rows = S
+            .residuals(&pair1.u, &broken)
+            .map(Result::unwrap)
+            .filter(|(_, residual)| !bool::from(residual.is_zero()))
+            .count();
+        assert_ne!(failing_rows, 0);
+
+        Ok(())
+    }
+
+    #[traced_test]
+    #[test]
+    fn is_sat_in_thread_pool() -> Result<(), Error<G1Affine>> {
+        use crate::util::in_thread_pool;
+
+        let (ck, S, pair1, pair2) = prepare_test_trace()?;
+
+        let mut broken = pair2.clone();
+        broken.w.W[0][1] += Fr::ONE;
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+
+        for trace in [&pair1, &broken] {
+            let default = in_thread_pool(None, || S.is_sat(&ck, &mut new_ro(), &trace.u, &trace.w));
+            let single_threaded = in_thread_pool(Some(&pool), || {
+                assert_eq!(rayon::current_num_threads(), 1);
+                S.is_sat(&ck, &mut new_ro(), &trace.u, &trace.w)
+            });
+            assert_eq!(default, single_threaded);
+        }
+        assert!(S.is_sat(&ck, &mut new_ro(), &broken.u, &broken.w).is_err());
+
+        Ok(())
+    }
+
+    #[traced_test]
+    #[test]
+    fn base_proof() -> Result<(), Error<G1Affine>> {
+        let (ck, S, pair1, _pair2) = prepare_test_trace()?;
+
+        let proof = S.prove_base(&ck, &mut new_ro(), &pair1.u, &pair1.w)?;
+        let U = S.verify_base(&ck, &mut new_ro(), &pair1.u, &proof)?;
+        assert_eq!(U, pair1.u.to_relax());
+
+        let mut corrupted = pair1.w.clone();
+        corrupted.W[0][0] += Fr::ONE;
+        assert!(S
+            .prove_base(&ck, &mut new_ro(), &pair1.u, &corrupted)
+            .is_err());
+
+        let mut corrupted_proof = proof;
+        corrupted_proof.W.W[0][0] += Fr::ONE;
+        assert!(S
+            .verify_base(&ck, &mut new_ro(), &pair1.u, &corrupted_proof)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[traced_test]
+    #[test]
+    fn compress_witness() -> Result<(), Error<G1Affine>> {
+        let (ck, S, pair1, pair2) = prepare_test_trace()?;
+        let (pp, _vp) = VanillaFS::setup_params(G1Affine::default(), S.clone())?;
+        let base = zero_accumulator(&S);
+        let (step1, _) = VanillaFS::prove(&ck, &pp, &mut new_ro(), &base, &pair1)?;
+        let (step2, _) = VanillaFS::prove(&ck, &pp, &mut new_ro(), &step1, &pair2)?;
+
+        let round_trip = |compressed: &CompressedWitness<Fr>| {
+            bincode::deserialize::<CompressedWitness<Fr>>(&bincode::serialize(compressed).unwrap())
+                .unwrap()
+        };
+
+        // trivial `E` is dropped
+        let compressed = base.W.compress(None);
+        assert_eq!(round_trip(&compressed).decompress(&S, None)?, base.W);
+
+        let compressed = step2.W.compress(None);
+        assert_eq!(round_trip(&compressed).decompress(&S, None)?, step2.W);
+
+        let compressed = step2.W.compress(Some(&step1.W));
+        assert_eq!(
+            round_trip(&compressed).decompress(&S, Some(&step1.W))?,
+            step2.W
+        );
+        assert!(matches!(
+            compressed.decompress(&S, None),
+            Err(crate::plonk::Error::Decompress { .. })
+        ));
+
+        // `E_len` from stored data must match the structure
+        let mut other_S = S.clone();
+        other_S.k += 1;
+        assert!(matches!(
+            round_trip(&base.W.compress(None)).decompress(&other_S, None),
+            Err(crate::plonk::Error::Decompress { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[traced_test]
+    #[test]
+    fn make_instance() -> Result<(), Error<G1Affine>> {
+        let (_ck, S, pair1, _pair2) = prepare_test_trace()?;
+        let PlonkInstance {
+            W_commitments,
+            instance,
+            challenges,
+            ..
+        } = pair1.u.clone();
+
+        assert_eq!(
+            S.make_instance(W_commitments.clone(), instance, challenges.clone())?,
+            pair1.u
+        );
+        assert_eq!(
+            S.make_instance(W_commitments, vec![Fr::ONE; 2], challenges),
+            Err(crate::plonk::Error::NumIoMismatch {
+                expected: 1,
+                actual: 2
+            })
+        );
+
+        Ok(())
+    }
+
+    #[traced_test]
+    #[test]
+    fn fold_relaxed() -> Result<(), Error<G1Affine>> {
+        let (ck, S, pair1, pair2) = prepare_test_trace()?;
+        let (pp, _vp) = VanillaFS::setup_params(G1Affine::default(), S.clone())?;
+        let base = zero_accumulator(&S);
+
+        // two independent accumulators, e.g. two subtrees of tree-based accumulation
+        let (lhs, _) = VanillaFS::prove(&ck, &pp, &mut new_ro(), &base, &pair1)?;
+        let (lhs, _) = VanillaFS::prove(&ck, &pp, &mut new_ro(), &lhs, &pair2)?;
+        let (rhs, _) = VanillaFS::prove(&ck, &pp, &mut new_ro(), &base, &pair2)?;
+        S.is_sat_relaxed(&ck, &lhs.U, &lhs.W)?;
+        S.is_sat_relaxed(&ck, &rhs.U, &rhs.W)?;
+
+        let (cross_terms, cross_term_commits) =
+            VanillaFS::commit_cross_terms_relaxed(&ck, &S, &lhs.U, &lhs.W, &rhs.U, &rhs.W)?;
+        assert_eq!(cross_terms.len(), S.num_cross_terms() - 1);
+
+        let r = Fr::from(13);
+        let U = lhs.U.fold_relaxed(&rhs.U, &cross_term_commits, &r)?;
+        let W = lhs.W.fold_relaxed(&rhs.W, &cross_terms, &r)?;
+        assert_eq!(U.u, lhs.U.u + r * rhs.U.u);
+        S.is_sat_relaxed(&ck, &U, &W)?;
+
+        Ok(())
+    }
+
+    #[traced_test]
+    #[test]
+    fn fold_tree() -> Result<(), Error<G1Affine>> {
+        let (ck, S, pair1, pair2) = prepare_test_trace()?;
+        let (pp, vp) = VanillaFS::setup_params(G1Affine::default(), S.clone())?;
+
+        let leaves = [&pair1, &pair2, &pair1].map(|pair| pair.clone().into_relax(S.k));
+
+        let (merged, proofs) = VanillaFS::prove_tree(&ck, &pp, &new_ro, &leaves)?;
+        assert_eq!(proofs.len(), leaves.len() - 1);
+        S.is_sat_relaxed(&ck, &merged.U, &merged.W)?;
+        S.is_sat_perm(&merged.U, &merged.W)?;
+
+        let instances = leaves.iter().map(|leaf| leaf.U.clone()).collect::<Vec<_>>();
+        let U = VanillaFS::verify_tree(&vp, &new_ro, &instances, &proofs)?;
+        Error::check_equality(&U, &merged.U)?;
+
+        assert!(matches!(
+            VanillaFS::verify_tree(&vp, &new_ro, &instances, &proofs[1..]),
+            Err(nifs::Error::WrongTreeProofsCount {
+                expected: 2,
+                actual: 1
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[traced_test]
+    #[test]
+    fn instance_commitments() -> Result<(), Error<G1Affine>> {
+        let (ck, S, mut pair1, mut pair2) = prepare_test_trace()?;
+
+        let (pp, vp) = VanillaFS::setup_params(G1Affine::default(), S.clone())?;
+        let accumulator = pair1.clone().into_relax(S.k);
+
+        // only one side of folding has commitment
+        pair2.u.commit_instance(&ck).unwrap();
+        assert!(matches!(
+            VanillaFS::prove(&ck, &pp, &mut new_ro(), &accumulator, &pair2,),
+            Err(nifs::Error::StructureMismatch {
+                field: "instance commitments",
+                ..
+            })
+        ));
+
+        pair1.u.commit_instance(&ck).unwrap();
+        let accumulator = pair1.clone().into_relax(S.k);
+        S.is_sat(&ck, &mut new_ro(), &pair1.u, &pair1.w)?;
+
+        let (folded, cross_term_commits) =
+            VanillaFS::prove(&ck, &pp, &mut new_ro(), &accumulator, &pair2)?;
+        assert_eq!(
+            folded.U.instance_commitment(),
+            Some(&ck.commit(&folded.U.instance).unwrap())
+        );
+        S.is_sat_relaxed(&ck, &folded.U, &folded.W)?;
+
+        let U_from_verify = VanillaFS::verify(
+            &vp,
+            &mut new_ro(),
+            &mut new_ro(),
+            &accumulator.U,
+            &pair2.u,
+            &cross_term_commits,
         )?;
+        assert_eq!(U_from_verify, folded.U);
+
+        // instance is bound by transcript, not only by its commitment
+        let mut tampered = pair2.u.clone();
+        tampered.instance[0] += Fr::ONE;
+        assert_ne!(
+            VanillaFS::verify(
+                &vp,
+                &mut new_ro(),
+                &mut new_ro(),
+                &accumulator.U,
+                &tampered,
+                &cross_term_commits,
+            )?,
+            folded.U
+        );
+
+        let mut broken = folded.U;
+        broken.instance[0] += Fr::ONE;
+        assert_eq!(
+            S.is_sat_relaxed(&ck, &broken, &folded.W),
+            Err(crate::plonk::Error::InstanceCommitmentMismatch)
+        );
+
+        Ok(())
+    }
+
+    #[traced_test]
+    #[test]
+    fn hiding_commitments() -> Result<(), Error<G1Affine>> {
+        let (runner, public_inputs) = test_runner();
+        let S = runner.try_collect_plonk_structure()?;
+        let witness = runner.try_collect_witness()?;
+        let ck = CommitmentKey::<G1Affine>::setup(
+            smallest_power(S.num_advice_columns, K),
+            b"hiding_commitments",
+        );
+
+        let [pair1, pair2] = [Fr::from(1), Fr::from(2)].map(|blind| {
+            let (u, w) = S
+                .run_sps_protocol_hiding(
+                    &ck,
+                    &public_inputs,
+                    &witness,
+                    &mut new_ro(),
+                    S.num_challenges,
+                    &[blind],
+                )
+                .unwrap();
+            PlonkTrace { u, w }
+        });
+
+        // same witness, but different commitments
+        assert_eq!(pair1.w.W, pair2.w.W);
+        assert_ne!(pair1.u.W_commitments, pair2.u.W_commitments);
+
+        for pair in [&pair1, &pair2] {
+            S.is_sat(&ck, &mut new_ro(), &pair.u, &pair.w).unwrap();
+        }
+
         fold_instances(&ck, &S, &pair1, &pair2, G1Affine::default())
     }
+
+    #[traced_test]
+    #[test]
+    fn other_structure() -> Result<(), Error<G1Affine>> {
+        let (ck, S, pair1, _pair2) = prepare_test_trace()?;
+        let accumulator = zero_accumulator(&S);
+        let (pp, vp) = VanillaFS::setup_params(G1Affine::default(), S.clone())?;
+        assert_eq!(pp.pp_digest, vp.pp_digest);
+        assert_ne!(pp.pp_digest, G1Affine::default());
+
+        let mut other_io = S.clone();
+        other_io.num_io += 1;
+
+        let mut other_advice = S.clone();
+        other_advice.round_sizes[0] += 1 << K;
+
+        for other_S in [other_io, other_advice] {
+            let (other_pp, _) = VanillaFS::setup_params(G1Affine::default(), other_S)?;
+            // digest binds structure
+            assert_ne!(pp.pp_digest, other_pp.pp_digest);
+
+            let result = VanillaFS::prove(&ck, &other_pp, &mut new_ro(), &accumulator, &pair1);
+            assert!(matches!(result, Err(nifs::Error::StructureMismatch { .. })));
+        }
+
+        // trusted digest is used as is
+        let (pp, _) = VanillaFS::setup_params_with_digest(G1Affine::default(), S);
+        assert_eq!(pp.pp_digest, G1Affine::default());
+
+        Ok(())
+    }
+
+    #[traced_test]
+    #[test]
+    fn relation_only() {
+        let (runner, public_inputs) = test_runner();
+        let S = runner.try_collect_plonk_structure().unwrap();
+        let witness = runner.try_collect_witness().unwrap();
+
+        // nothing committed
+        let mut u = PlonkInstance::<G1Affine>::new(S.num_io, S.num_challenges, 1);
+        u.instance = public_inputs;
+        let w = PlonkWitness {
+            W: vec![crate::util::concatenate_with_padding(&witness, 1 << K)],
+            blinds: vec![Fr::ZERO],
+        };
+        assert_eq!(S.is_sat_relation_only(&u, &w), Ok(()));
+
+        let ck = CommitmentKey::<G1Affine>::setup(
+            smallest_power(S.num_advice_columns, K),
+            b"relation_only",
+        );
+        assert!(matches!(
+            S.is_sat(&ck, &mut new_ro(), &u, &w),
+            Err(crate::plonk::Error::CommitmentMismatch { .. })
+        ));
+    }
 }
 
 // test multiple gates without lookup
@@ -524,6 +1020,8 @@ mod one_round_test {
             public_inputs2,
             G1Affine::default(),
         )?;
+        // two linear gates compressed by challenge `y`
+        assert_eq!(S.num_cross_terms(), 2);
         fold_instances(&ck, &S, &pair1, &pair2, G1Affine::default())
     }
 }
@@ -831,3 +1329,75 @@ mod three_rounds_test {
         fold_instances(&ck, &S, &pair1, &pair2, G1Affine::default())
     }
 }
+
+// test with gate, that queries instance column directly
+mod instance_query_test {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    /// Gate `a * a - instance = 0`, advice holds square roots of public inputs
+    struct SquareRootCircuit<F> {
+        roots: Vec<F>,
+    }
+
+    impl<F: PrimeField> Circuit<F> for SquareRootCircuit<F> {
+        type Config = Column<Advice>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self { roots: vec![] }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.create_gate("square", |meta| {
+                let a = meta.query_advice(a, Rotation::cur());
+                vec![a.clone() * a - meta.query_instance(instance, Rotation::cur())]
+            });
+            a
+        }
+
+        fn synthesize(
+            &self,
+            a: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), plonk::Error> {
+            layouter.assign_region(
+                || "roots",
+                |mut region| {
+                    for (offset, root) in self.roots.iter().enumerate() {
+                        region.assign_advice(|| "root", a, offset, || Value::known(*root))?;
+                    }
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_nifs() -> Result<(), Error<G1Affine>> {
+        const K: u32 = 4;
+        let roots1 = vec![Fr::from(3), Fr::from(5)];
+        let roots2 = vec![Fr::from(7), Fr::from(11)];
+        let squares = |roots: &[Fr]| roots.iter().map(Field::square).collect::<Vec<_>>();
+
+        let (ck, S, pair1, pair2) = prepare_trace(
+            K,
+            SquareRootCircuit {
+                roots: roots1.clone(),
+            },
+            SquareRootCircuit {
+                roots: roots2.clone(),
+            },
+            squares(&roots1),
+            squares(&roots2),
+            G1Affine::default(),
+        )?;
+        // instance is folded together with advice, so it's a part of cross terms
+        assert_eq!(S.num_fold_vars(), 2);
+        fold_instances(&ck, &S, &pair1, &pair2, G1Affine::default())
+    }
+}
@@ -1,19 +1,21 @@
-use std::marker::PhantomData;
+use std::{marker::PhantomData, sync::Arc};
 
 use ff::Field;
+use serde::Serialize;
 use tracing::*;
 
 use super::*;
 use crate::commitment::CommitmentKey;
 use crate::concat_vec;
 use crate::constants::NUM_CHALLENGE_BITS;
-use crate::plonk::eval::{GetDataForEval, PlonkEvalDomain};
+use crate::digest::{self, DigestToCurve};
+use crate::plonk::eval::{GetDataForEval, PlonkEvalDomain, RowView};
 use crate::plonk::{
     PlonkInstance, PlonkStructure, PlonkWitness, RelaxedPlonkInstance, RelaxedPlonkWitness,
 };
 use crate::plonk::{PlonkTrace, RelaxedPlonkTrace};
 use crate::polynomial::graph_evaluator::GraphEvaluator;
-use crate::poseidon::ROTrait;
+use crate::poseidon::{AbsorbInRO, ROTrait};
 use crate::sps::SpecialSoundnessVerifier;
 use halo2_proofs::arithmetic::CurveAffine;
 
@@ -44,13 +46,43 @@ pub struct VanillaFS<C: CurveAffine> {
     _marker: PhantomData<C>,
 }
 
+/// Data, that prover of [`VanillaFS`] needs, see [`VanillaFS::setup_params`]
 pub struct VanillaFSProverParam<C: CurveAffine> {
-    pub(crate) S: PlonkStructure<C::ScalarExt>,
-    /// digest of public parameter of IVC circuit
-    pp_digest: C,
+    pub(crate) S: Arc<PlonkStructure<C::ScalarExt>>,
+    /// digest of public params, which covers `S`, see [`VanillaFS::setup_params`]
+    pub(crate) pp_digest: C,
+}
+
+/// Data, that verifier of [`VanillaFS`] needs besides instances & proof, made by
+/// [`VanillaFS::setup_params`] together with [`VanillaFSProverParam`]
+///
+/// Structure itself isn't needed by verifier: it's bound by `pp_digest` & only the count of
+/// cross terms is checked against the proof
+pub struct VanillaFSVerifierParam<C: CurveAffine> {
+    /// digest of public params, which covers the folded structure, see
+    /// [`VanillaFS::setup_params`]
+    pub(crate) pp_digest: C,
+    /// expected count of cross term commits, see [`PlonkStructure::num_cross_terms`]
+    num_cross_terms: usize,
 }
 
 impl<C: CurveAffine> VanillaFS<C> {
+    /// Same as [`VanillaFS::setup_params`], but `pp_digest` is used as is
+    ///
+    /// Only for digest, that already covers `S`, e.g. digest of IVC public params, which hashes
+    /// both step circuit structures & is also used on-circuit
+    pub(crate) fn setup_params_with_digest(
+        pp_digest: C,
+        S: impl Into<Arc<PlonkStructure<C::ScalarExt>>>,
+    ) -> (VanillaFSProverParam<C>, VanillaFSVerifierParam<C>) {
+        let S = S.into();
+        let vp = VanillaFSVerifierParam {
+            pp_digest,
+            num_cross_terms: S.num_cross_terms(),
+        };
+        (VanillaFSProverParam { S, pp_digest }, vp)
+    }
+
     /// Commits to the cross terms between two Plonk instance-witness pairs.
     ///
     /// This method calculates the cross terms and their commitments, which
@@ -81,22 +113,91 @@ impl<C: CurveAffine> VanillaFS<C> {
         U2: &PlonkInstance<C>,
         W2: &PlonkWitness<C::ScalarExt>,
     ) -> Result<(CrossTerms<C>, CrossTermCommits<C>), Error> {
-        let data = PlonkEvalDomain {
+        let cross_terms = Self::compute_cross_terms(S, U1, W1, U2, W2)?;
+        let cross_term_commits = Self::commit_terms(ck, &cross_terms)?;
+
+        Ok((cross_terms, cross_term_commits))
+    }
+
+    /// Evaluates cross terms `[T_1, ..., T_{d-1}]` of homogenized gates over all rows, same as
+    /// [`VanillaFS::commit_cross_terms`] but without commitments
+    ///
+    /// Each `T_k` is the coefficient of `r^k` in `G(Z1 + r * Z2)`, where `G` is the compressed
+    /// gate of `S` & `Z1`, `Z2` are witnesses & challenges (with `u`) of both pairs
+    #[instrument(skip_all)]
+    pub fn compute_cross_terms(
+        S: &PlonkStructure<C::ScalarExt>,
+        U1: &RelaxedPlonkInstance<C>,
+        W1: &RelaxedPlonkWitness<C::ScalarExt>,
+        U2: &PlonkInstance<C>,
+        W2: &PlonkWitness<C::ScalarExt>,
+    ) -> Result<CrossTerms<C>, Error> {
+        let challenges = concat_vec!(&U1.challenges, &[U1.u], &U2.challenges, &[U2.to_relax().u]);
+        Self::compute_cross_terms_with(
+            S,
+            &challenges,
+            (&W1.W, &U1.instance),
+            (&W2.W, &U2.instance),
+            S.num_cross_terms(),
+        )
+    }
+
+    /// Same as [`VanillaFS::commit_cross_terms`], but the second pair is relaxed too, so its
+    /// `u` is used as is
+    ///
+    /// The last cross term `T_d` of relaxed pair is its error term `E2`, so it's not computed &
+    /// only `[T_1, ..., T_{d-1}]` are returned. Used with
+    /// [`RelaxedPlonkInstance::fold_relaxed`]
+    #[instrument(skip_all)]
+    pub fn commit_cross_terms_relaxed(
+        ck: &CommitmentKey<C>,
+        S: &PlonkStructure<C::ScalarExt>,
+        U1: &RelaxedPlonkInstance<C>,
+        W1: &RelaxedPlonkWitness<C::ScalarExt>,
+        U2: &RelaxedPlonkInstance<C>,
+        W2: &RelaxedPlonkWitness<C::ScalarExt>,
+    ) -> Result<(CrossTerms<C>, CrossTermCommits<C>), Error> {
+        let challenges = concat_vec!(&U1.challenges, &[U1.u], &U2.challenges, &[U2.u]);
+        let cross_terms = Self::compute_cross_terms_with(
+            S,
+            &challenges,
+            (&W1.W, &U1.instance),
+            (&W2.W, &U2.instance),
+            S.num_cross_terms().saturating_sub(1),
+        )?;
+        let cross_term_commits = Self::commit_terms(ck, &cross_terms)?;
+
+        Ok((cross_terms, cross_term_commits))
+    }
+
+    /// Computes first `count` cross terms, `challenges` are concatenation of challenges & `u` of
+    /// both pairs, `pair1` & `pair2` are witness & instance values of each pair
+    fn compute_cross_terms_with(
+        S: &PlonkStructure<C::ScalarExt>,
+        challenges: &[C::ScalarExt],
+        (W1s, instance1): (&[Vec<C::ScalarExt>], &[C::ScalarExt]),
+        (W2s, instance2): (&[Vec<C::ScalarExt>], &[C::ScalarExt]),
+        count: usize,
+    ) -> Result<CrossTerms<C>, Error> {
+        let data = RowView::new(PlonkEvalDomain {
             num_advice: S.num_advice_columns,
             num_lookup: S.num_lookups(),
-            challenges: &concat_vec!(&U1.challenges, &[U1.u], &U2.challenges, &[U2.to_relax().u]),
+            num_instance: S.num_instance_columns,
+            challenges,
             selectors: &S.selectors,
             fixed: &S.fixed_columns,
-            W1s: &W1.W,
-            W2s: &W2.W,
-        };
+            W1s,
+            W2s,
+            instance1,
+            instance2,
+        });
 
         let row_size = data.row_size();
 
-        let cross_terms = S
-            .custom_gates_lookup_compressed
+        S.custom_gates_lookup_compressed
             .grouped()
             .iter_from_first()
+            .take(count)
             .map(|optional_expr| match optional_expr {
                 Some(expr) => {
                     let evaluator = GraphEvaluator::new(expr);
@@ -112,46 +213,295 @@ impl<C: CurveAffine> VanillaFS<C> {
                 }
                 None => Ok(vec![C::ScalarExt::ZERO; row_size].into_boxed_slice()),
             })
-            .collect::<Result<CrossTerms<C>, _>>()?;
+            .collect::<Result<CrossTerms<C>, _>>()
+    }
 
-        let cross_term_commits: Vec<C> = cross_terms
-            .iter()
+    fn commit_terms(
+        ck: &CommitmentKey<C>,
+        cross_terms: &CrossTerms<C>,
+    ) -> Result<CrossTermCommits<C>, Error> {
+        Ok(cross_terms
+            .par_iter()
             .map(|v| ck.commit(v))
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect::<Result<Vec<_>, _>>()?)
+    }
 
-        Ok((cross_terms, cross_term_commits))
+    /// Checks that both accumulator & incoming trace are shaped by `S`, i.e. that they were
+    /// produced for the same [`PlonkStructure`]
+    fn check_structure(
+        S: &PlonkStructure<C::ScalarExt>,
+        U1: &RelaxedPlonkInstance<C>,
+        W1: &RelaxedPlonkWitness<C::ScalarExt>,
+        U2: &PlonkInstance<C>,
+        W2: &PlonkWitness<C::ScalarExt>,
+    ) -> Result<(), Error> {
+        let check = |field: &'static str, expected: usize, actual: usize| {
+            if expected == actual {
+                Ok(())
+            } else {
+                Err(Error::StructureMismatch {
+                    field,
+                    expected,
+                    actual,
+                })
+            }
+        };
+
+        let num_rounds = S.round_sizes.len();
+        check("accumulator instance", S.num_io, U1.instance.len())?;
+        check(
+            "accumulator challenges",
+            S.num_challenges,
+            U1.challenges.len(),
+        )?;
+        check(
+            "accumulator W commitments",
+            num_rounds,
+            U1.W_commitments.len(),
+        )?;
+        check("accumulator W", num_rounds, W1.W.len())?;
+        check("incoming instance", S.num_io, U2.instance.len())?;
+        check("incoming challenges", S.num_challenges, U2.challenges.len())?;
+        check("incoming W commitments", num_rounds, U2.W_commitments.len())?;
+        check("incoming W", num_rounds, W2.W.len())?;
+        check(
+            "instance commitments",
+            U1.instance_commitment.iter().count(),
+            U2.instance_commitment.iter().count(),
+        )?;
+
+        S.round_sizes
+            .iter()
+            .zip(W1.W.iter().zip(W2.W.iter()))
+            .try_for_each(|(round_size, (W1i, W2i))| {
+                check("accumulator W round", *round_size, W1i.len())?;
+                check("incoming W round", *round_size, W2i.len())
+            })
     }
 
-    /// Absorb all fields into RandomOracle `RO` & generate challenge based on that
+    /// Derive folding challenge `r`, the canonical definition used by both
+    /// [`FoldingScheme::prove`] & [`FoldingScheme::verify`]
+    ///
+    /// Absorption order into `ro_acc`:
+    /// 1. `pp_digest` - digest of public params, it binds the [`PlonkStructure`], see
+    ///    [`VanillaFS::setup_params`]
+    /// 2. `U1` - accumulator instance, see [`RelaxedPlonkInstance`] absorb impl
+    /// 3. `U2` - incoming instance, see [`PlonkInstance`] absorb impl
+    /// 4. `cross_term_commits` - commitments to `[T_1, ..., T_{d-1}]` in order
+    ///
+    /// Each slice (commitments of rounds, instance, challenges & cross terms) is prefixed with
+    /// its length, see [`ROTrait::absorb_point_slice`]. After that `r` squeezed with
+    /// [`NUM_CHALLENGE_BITS`] bits
     #[instrument(skip_all)]
-    pub(crate) fn generate_challenge(
+    pub fn derive_fold_challenge(
         pp_digest: &C,
         ro_acc: &mut impl ROTrait<C::Base>,
         U1: &RelaxedPlonkInstance<C>,
         U2: &PlonkInstance<C>,
         cross_term_commits: &[C],
     ) -> Result<<C as CurveAffine>::ScalarExt, Error> {
+        Self::derive_challenge(pp_digest, ro_acc, None, U1, U2, cross_term_commits)
+    }
+
+    /// Shared transcript of [`VanillaFS::derive_fold_challenge`] &
+    /// [`VanillaFS::derive_merge_challenge`], `label` is absorbed right after `pp_digest`
+    fn derive_challenge<RO: ROTrait<C::Base>, U2: AbsorbInRO<C::Base, RO>>(
+        pp_digest: &C,
+        ro_acc: &mut RO,
+        label: Option<C::Base>,
+        U1: &RelaxedPlonkInstance<C>,
+        U2: &U2,
+        cross_term_commits: &[C],
+    ) -> Result<<C as CurveAffine>::ScalarExt, Error> {
+        ro_acc.absorb_point(pp_digest);
+        if let Some(label) = label {
+            ro_acc.absorb_field(label);
+        }
         Ok(ro_acc
-            .absorb_point(pp_digest)
             .absorb(U1)
             .absorb(U2)
-            .absorb_point_iter(cross_term_commits.iter())
+            .absorb_point_slice(cross_term_commits)
             .squeeze::<C>(NUM_CHALLENGE_BITS))
     }
 }
 
+/// Tree-based folding: independent accumulators are merged pairwise, so subtrees can be folded
+/// in parallel, as in parallel Nova / PCD
+impl<C: CurveAffine> VanillaFS<C> {
+    /// Same as [`VanillaFS::derive_fold_challenge`], but `U2` is relaxed too, used by
+    /// [`VanillaFS::prove_merge`] & [`VanillaFS::verify_merge`]
+    ///
+    /// Label `-1` is absorbed after `pp_digest`, so transcript of merge never matches the one of
+    /// fold: at this position fold absorbs the number of rounds of `U1`
+    #[instrument(skip_all)]
+    pub fn derive_merge_challenge(
+        pp_digest: &C,
+        ro_acc: &mut impl ROTrait<C::Base>,
+        U1: &RelaxedPlonkInstance<C>,
+        U2: &RelaxedPlonkInstance<C>,
+        cross_term_commits: &[C],
+    ) -> Result<<C as CurveAffine>::ScalarExt, Error> {
+        Self::derive_challenge(
+            pp_digest,
+            ro_acc,
+            Some(-C::Base::ONE),
+            U1,
+            U2,
+            cross_term_commits,
+        )
+    }
+
+    /// Folds two accumulators into one, returns it together with commitments of cross terms
+    /// `[T_1, ..., T_{d-1}]` as a proof for [`VanillaFS::verify_merge`]
+    #[instrument(skip_all)]
+    pub fn prove_merge(
+        ck: &CommitmentKey<C>,
+        pp: &VanillaFSProverParam<C>,
+        ro_acc: &mut impl ROTrait<C::Base>,
+        lhs: &RelaxedPlonkTrace<C>,
+        rhs: &RelaxedPlonkTrace<C>,
+    ) -> Result<(RelaxedPlonkTrace<C>, CrossTermCommits<C>), Error> {
+        for E in [&lhs.W.E, &rhs.W.E] {
+            if E.len() != pp.S.cross_term_len() {
+                return Err(Error::WrongErrorVectorLength {
+                    expected: pp.S.cross_term_len(),
+                    actual: E.len(),
+                });
+            }
+        }
+
+        let (cross_terms, cross_term_commits) =
+            Self::commit_cross_terms_relaxed(ck, &pp.S, &lhs.U, &lhs.W, &rhs.U, &rhs.W)?;
+
+        let r = Self::derive_merge_challenge(
+            &pp.pp_digest,
+            ro_acc,
+            &lhs.U,
+            &rhs.U,
+            &cross_term_commits,
+        )?;
+
+        let U = lhs.U.fold_relaxed(&rhs.U, &cross_term_commits, &r)?;
+        let W = lhs.W.fold_relaxed(&rhs.W, &cross_terms, &r)?;
+
+        Ok((RelaxedPlonkTrace { U, W }, cross_term_commits))
+    }
+
+    /// Verifier counterpart of [`VanillaFS::prove_merge`], returns merged accumulator instance
+    #[instrument(skip_all)]
+    pub fn verify_merge(
+        vp: &VanillaFSVerifierParam<C>,
+        ro_acc: &mut impl ROTrait<C::Base>,
+        U1: &RelaxedPlonkInstance<C>,
+        U2: &RelaxedPlonkInstance<C>,
+        cross_term_commits: &CrossTermCommits<C>,
+    ) -> Result<RelaxedPlonkInstance<C>, Error> {
+        let expected = vp.num_cross_terms.saturating_sub(1);
+        if cross_term_commits.len() != expected {
+            return Err(Error::WrongCrossTermCommitsCount {
+                expected,
+                actual: cross_term_commits.len(),
+            });
+        }
+
+        let r = Self::derive_merge_challenge(&vp.pp_digest, ro_acc, U1, U2, cross_term_commits)?;
+
+        Ok(U1.fold_relaxed(U2, cross_term_commits, &r)?)
+    }
+
+    /// Merges all `leaves` into one accumulator with a balanced binary tree, folding both
+    /// subtrees of each node in parallel
+    ///
+    /// Each merge uses a fresh `new_ro()`. Proofs of merges are returned in post-order: proofs of
+    /// the left subtree, of the right one & of the node itself. The left subtree of a node with
+    /// `n` leaves contains `n / 2` of them
+    #[instrument(skip_all)]
+    pub fn prove_tree<RO>(
+        ck: &CommitmentKey<C>,
+        pp: &VanillaFSProverParam<C>,
+        new_ro: &(impl Fn() -> RO + Sync),
+        leaves: &[RelaxedPlonkTrace<C>],
+    ) -> Result<(RelaxedPlonkTrace<C>, Vec<CrossTermCommits<C>>), Error>
+    where
+        RO: ROTrait<C::Base>,
+    {
+        match leaves {
+            [] => Err(Error::EmptyTree),
+            [leaf] => Ok((leaf.clone(), vec![])),
+            _ => {
+                let (left, right) = leaves.split_at(leaves.len() / 2);
+                let (left, right) = rayon::join(
+                    || Self::prove_tree(ck, pp, new_ro, left),
+                    || Self::prove_tree(ck, pp, new_ro, right),
+                );
+                let ((left, mut proofs), (right, right_proofs)) = (left?, right?);
+
+                let (merged, proof) = Self::prove_merge(ck, pp, &mut new_ro(), &left, &right)?;
+                proofs.extend(right_proofs);
+                proofs.push(proof);
+
+                Ok((merged, proofs))
+            }
+        }
+    }
+
+    /// Verifier counterpart of [`VanillaFS::prove_tree`], replays merges of the same tree over
+    /// instances of `leaves`
+    #[instrument(skip_all)]
+    pub fn verify_tree<RO>(
+        vp: &VanillaFSVerifierParam<C>,
+        new_ro: &impl Fn() -> RO,
+        leaves: &[RelaxedPlonkInstance<C>],
+        proofs: &[CrossTermCommits<C>],
+    ) -> Result<RelaxedPlonkInstance<C>, Error>
+    where
+        RO: ROTrait<C::Base>,
+    {
+        let expected = leaves.len().saturating_sub(1);
+        if proofs.len() != expected {
+            return Err(Error::WrongTreeProofsCount {
+                expected,
+                actual: proofs.len(),
+            });
+        }
+
+        match leaves {
+            [] => Err(Error::EmptyTree),
+            [leaf] => Ok(leaf.clone()),
+            _ => {
+                let (left, right) = leaves.split_at(leaves.len() / 2);
+                let (left_proofs, proofs) = proofs.split_at(left.len() - 1);
+                let (right_proofs, proof) = proofs.split_at(right.len() - 1);
+
+                let left = Self::verify_tree(vp, new_ro, left, left_proofs)?;
+                let right = Self::verify_tree(vp, new_ro, right, right_proofs)?;
+
+                Self::verify_merge(vp, &mut new_ro(), &left, &right, &proof[0])
+            }
+        }
+    }
+}
+
 impl<C: CurveAffine> FoldingScheme<C> for VanillaFS<C> {
     type ProverParam = VanillaFSProverParam<C>;
-    type VerifierParam = C;
+    type VerifierParam = VanillaFSVerifierParam<C>;
     type Accumulator = RelaxedPlonkTrace<C>;
     type AccumulatorInstance = RelaxedPlonkInstance<C>;
     type Proof = CrossTermCommits<C>;
 
+    /// `pp_digest` isn't trusted to cover `S`: the digest absorbed into transcript is derived
+    /// from both `pp_digest` & [`PlonkStructure::digest`]
     fn setup_params(
         pp_digest: C,
-        S: PlonkStructure<C::ScalarExt>,
-    ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
-        Ok((VanillaFSProverParam { S, pp_digest }, pp_digest))
+        S: impl Into<Arc<PlonkStructure<C::ScalarExt>>>,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), Error>
+    where
+        C: Serialize,
+        C::ScalarExt: Serialize,
+    {
+        let S = S.into();
+        let pp_digest = digest::DefaultHasher::digest_to_curve(&(pp_digest, S.digest::<C>()?))?;
+        Ok(Self::setup_params_with_digest(pp_digest, S))
     }
 
     #[instrument(skip_all)]
@@ -195,13 +545,28 @@ impl<C: CurveAffine> FoldingScheme<C> for VanillaFS<C> {
         let U2 = &incoming.u;
         let W2 = &incoming.w;
 
+        if W1.E.len() != pp.S.cross_term_len() {
+            return Err(Error::WrongErrorVectorLength {
+                expected: pp.S.cross_term_len(),
+                actual: W1.E.len(),
+            });
+        }
+        Self::check_structure(&pp.S, U1, W1, U2, W2)?;
+
         let (cross_terms, cross_term_commits) =
             Self::commit_cross_terms(ck, &pp.S, U1, W1, U2, W2)?;
+        // verifier checks this count too, see `VanillaFSVerifierParam::num_cross_terms`
+        if cross_term_commits.len() != pp.S.num_cross_terms() {
+            return Err(Error::WrongCrossTermCommitsCount {
+                expected: pp.S.num_cross_terms(),
+                actual: cross_term_commits.len(),
+            });
+        }
 
-        let r = VanillaFS::generate_challenge(&pp.pp_digest, ro_acc, U1, U2, &cross_term_commits)?;
+        let r = Self::derive_fold_challenge(&pp.pp_digest, ro_acc, U1, U2, &cross_term_commits)?;
 
-        let U = U1.fold(U2, &cross_term_commits, &r);
-        let W = W1.fold(W2, &cross_terms, &r);
+        let U = U1.fold(U2, &cross_term_commits, &r)?;
+        let W = W1.fold(W2, &cross_terms, &r)?;
 
         Ok((RelaxedPlonkTrace { U, W }, cross_term_commits))
     }
@@ -231,10 +596,17 @@ impl<C: CurveAffine> FoldingScheme<C> for VanillaFS<C> {
         U2: &PlonkInstance<C>,
         cross_term_commits: &CrossTermCommits<C>,
     ) -> Result<Self::AccumulatorInstance, Error> {
+        if cross_term_commits.len() != vp.num_cross_terms {
+            return Err(Error::WrongCrossTermCommitsCount {
+                expected: vp.num_cross_terms,
+                actual: cross_term_commits.len(),
+            });
+        }
+
         U2.sps_verify(ro_nark)?;
 
-        let r = VanillaFS::generate_challenge(vp, ro_acc, U1, U2, cross_term_commits)?;
+        let r = Self::derive_fold_challenge(&vp.pp_digest, ro_acc, U1, U2, cross_term_commits)?;
 
-        Ok(U1.fold(U2, cross_term_commits, &r))
+        Ok(U1.fold(U2, cross_term_commits, &r)?)
     }
 }
@@ -0,0 +1,278 @@
+//! Error vector `E` of [`RelaxedPlonkWitness`](super::RelaxedPlonkWitness)
+//!
+//! `E` is mostly zero in early folds, e.g. it's all zeros right after the base step. So it's
+//! stored by chunks of [`CHUNK_SIZE`] elements, and a chunk with all zero elements isn't
+//! allocated. Memory, [`ErrorVector::fold`] & [`ErrorVector::commit`] scale with the number of
+//! nonzero chunks instead of the table size.
+use std::ops::Range;
+
+use ff::PrimeField;
+use halo2_proofs::arithmetic::CurveAffine;
+use rayon::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::commitment::{self, CommitmentKey};
+
+/// Count of elements in one chunk of [`ErrorVector`]
+pub const CHUNK_SIZE: usize = 1 << 10;
+
+fn chunk_range(len: usize, index: usize) -> Range<usize> {
+    index * CHUNK_SIZE..len.min((index + 1) * CHUNK_SIZE)
+}
+
+fn is_nonzero<F: PrimeField>(value: &F) -> bool {
+    !bool::from(value.is_zero())
+}
+
+#[derive(Clone, Debug)]
+pub struct ErrorVector<F: PrimeField> {
+    len: usize,
+    /// `None` for a chunk with all zero elements, the last chunk may be shorter than
+    /// [`CHUNK_SIZE`]
+    chunks: Vec<Option<Box<[F]>>>,
+}
+
+/// Vector folded into [`ErrorVector`], see [`ErrorVector::fold`]
+#[derive(Clone, Copy, Debug)]
+pub enum ErrorTerm<'t, F: PrimeField> {
+    /// Cross term `T_k`
+    Dense(&'t [F]),
+    /// `E` of another relaxed witness
+    Chunked(&'t ErrorVector<F>),
+}
+
+impl<'t, F: PrimeField> ErrorTerm<'t, F> {
+    fn len(&self) -> usize {
+        match self {
+            Self::Dense(values) => values.len(),
+            Self::Chunked(E) => E.len,
+        }
+    }
+
+    /// Elements of `index`-th chunk, `None` if all of them are zero
+    fn chunk(&self, index: usize) -> Option<&'t [F]> {
+        match self {
+            Self::Dense(values) => {
+                let chunk = &values[chunk_range(values.len(), index)];
+                chunk.iter().any(is_nonzero).then_some(chunk)
+            }
+            Self::Chunked(E) => E.chunks[index].as_deref(),
+        }
+    }
+}
+
+impl<F: PrimeField> ErrorVector<F> {
+    /// Vector of `len` zeros, no chunk is allocated
+    pub fn zero(len: usize) -> Self {
+        Self {
+            len,
+            chunks: vec![None; len.div_ceil(CHUNK_SIZE)],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// `true` if all elements are zero, only allocated chunks are scanned
+    pub fn is_zero(&self) -> bool {
+        self.chunks()
+            .all(|(_, chunk)| !chunk.iter().any(is_nonzero))
+    }
+
+    fn value(&self, index: usize) -> F {
+        self.chunks[index / CHUNK_SIZE]
+            .as_ref()
+            .map_or(F::ZERO, |chunk| chunk[index % CHUNK_SIZE])
+    }
+
+    pub fn get(&self, index: usize) -> Option<F> {
+        (index < self.len).then(|| self.value(index))
+    }
+
+    /// Mutable reference to element, its chunk is allocated if it wasn't
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut F> {
+        if index >= self.len {
+            return None;
+        }
+
+        let range = chunk_range(self.len, index / CHUNK_SIZE);
+        let chunk = self.chunks[index / CHUNK_SIZE]
+            .get_or_insert_with(|| vec![F::ZERO; range.len()].into_boxed_slice());
+        Some(&mut chunk[index % CHUNK_SIZE])
+    }
+
+    /// Allocated chunks together with the index of their first element
+    pub fn chunks(&self) -> impl Iterator<Item = (usize, &[F])> {
+        self.chunks
+            .iter()
+            .enumerate()
+            .filter_map(|(index, chunk)| Some((index * CHUNK_SIZE, chunk.as_deref()?)))
+    }
+
+    pub(crate) fn chunks_mut(&mut self) -> impl Iterator<Item = &mut [F]> {
+        self.chunks.iter_mut().filter_map(Option::as_deref_mut)
+    }
+
+    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = F> + '_ {
+        (0..self.len).into_par_iter().map(|index| self.value(index))
+    }
+
+    pub fn to_vec(&self) -> Vec<F> {
+        (0..self.chunks.len())
+            .flat_map(|index| {
+                let range = chunk_range(self.len, index);
+                match &self.chunks[index] {
+                    Some(chunk) => chunk.to_vec(),
+                    None => vec![F::ZERO; range.len()],
+                }
+            })
+            .collect()
+    }
+
+    /// Folds terms in place: `E += sum_k coeff_k * T_k`
+    ///
+    /// All terms must have the same length as `E`. A chunk, that is zero in every term, is
+    /// skipped, so a zero chunk of `E` stays unallocated
+    pub fn fold(&mut self, terms: &[(F, ErrorTerm<F>)]) {
+        let len = self.len;
+        debug_assert!(terms.iter().all(|(_, term)| term.len() == len));
+        self.chunks
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(index, chunk)| {
+                let range = chunk_range(len, index);
+                let terms = terms
+                    .iter()
+                    .filter_map(|(coeff, term)| Some((coeff, term.chunk(index)?)))
+                    .collect::<Vec<_>>();
+                if terms.is_empty() {
+                    return;
+                }
+
+                chunk
+                    .get_or_insert_with(|| vec![F::ZERO; range.len()].into_boxed_slice())
+                    .iter_mut()
+                    .enumerate()
+                    .for_each(|(i, ei)| {
+                        *ei = terms
+                            .iter()
+                            .fold(*ei, |acc, (coeff, tk)| acc + **coeff * tk[i])
+                    });
+            });
+    }
+
+    /// Commits to `E` without blinding, only nonzero elements of allocated chunks take part
+    /// in MSM, see [`CommitmentKey::commit_chunks`]
+    pub fn commit<C: CurveAffine<ScalarExt = F>>(
+        &self,
+        ck: &CommitmentKey<C>,
+    ) -> Result<C, commitment::Error> {
+        ck.commit_chunks(self.len, self.chunks())
+    }
+}
+
+impl<F: PrimeField> From<Vec<F>> for ErrorVector<F> {
+    fn from(values: Vec<F>) -> Self {
+        Self {
+            len: values.len(),
+            chunks: values
+                .chunks(CHUNK_SIZE)
+                .map(|chunk| chunk.iter().any(is_nonzero).then(|| chunk.into()))
+                .collect(),
+        }
+    }
+}
+
+impl<F: PrimeField> FromIterator<F> for ErrorVector<F> {
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+/// Compares values, so an allocated chunk of zeros is equal to the unallocated one
+impl<F: PrimeField> PartialEq for ErrorVector<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len
+            && self
+                .chunks
+                .iter()
+                .zip(other.chunks.iter())
+                .all(|(lhs, rhs)| match (lhs, rhs) {
+                    (Some(lhs), Some(rhs)) => lhs == rhs,
+                    (Some(chunk), None) | (None, Some(chunk)) => !chunk.iter().any(is_nonzero),
+                    (None, None) => true,
+                })
+    }
+}
+
+impl<F: PrimeField> Eq for ErrorVector<F> {}
+
+/// Serialized as a plain sequence of all elements, as `E` was stored before chunking
+impl<F: PrimeField + Serialize> Serialize for ErrorVector<F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq((0..self.len).map(|index| self.value(index)))
+    }
+}
+
+impl<'de, F: PrimeField + Deserialize<'de>> Deserialize<'de> for ErrorVector<F> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<F>::deserialize(deserializer).map(Self::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ff::Field;
+    use halo2curves::bn256::{Fr, G1Affine};
+
+    use super::*;
+
+    const LEN: usize = 3 * CHUNK_SIZE + 5;
+
+    #[test]
+    fn fold_skips_zero_chunks() {
+        let mut T1 = vec![Fr::ZERO; LEN];
+        T1[7] = Fr::from(3);
+        let mut T2 = vec![Fr::ZERO; LEN];
+        T2[LEN - 1] = Fr::from(5);
+        let E2 = ErrorVector::from(T1.clone());
+        let r = Fr::from(11);
+
+        let mut E = ErrorVector::zero(LEN);
+        E.fold(&[
+            (r, ErrorTerm::Dense(&T1)),
+            (r * r, ErrorTerm::Dense(&T2)),
+            (r * r * r, ErrorTerm::Chunked(&E2)),
+        ]);
+
+        let mut expected = vec![Fr::ZERO; LEN];
+        expected[7] = r * Fr::from(3) + r * r * r * Fr::from(3);
+        expected[LEN - 1] = r * r * Fr::from(5);
+        assert_eq!(E.to_vec(), expected);
+        assert_eq!(E, ErrorVector::from(expected));
+        assert_eq!(
+            E.chunks().map(|(offset, _)| offset).collect::<Vec<_>>(),
+            [0, 3 * CHUNK_SIZE]
+        );
+        assert_eq!(E.chunks().last().unwrap().1.len(), 5);
+    }
+
+    #[test]
+    fn commit() {
+        let ck = CommitmentKey::<G1Affine>::setup(12, b"error_vector");
+
+        let mut E = ErrorVector::<Fr>::zero(LEN);
+        assert_eq!(E.commit(&ck), ck.commit(&E.to_vec()));
+
+        *E.get_mut(CHUNK_SIZE + 1).unwrap() = Fr::from(7);
+        *E.get_mut(LEN - 1).unwrap() = -Fr::ONE;
+        assert_eq!(E.chunks().count(), 2);
+        assert_eq!(E.commit(&ck), ck.commit(&E.to_vec()));
+        assert_eq!(E.get(LEN), None);
+    }
+}
@@ -1,5 +1,6 @@
-use crate::polynomial::ColumnIndex;
+use crate::polynomial::{ColumnIndex, Expression};
 use ff::PrimeField;
+use rayon::prelude::*;
 
 #[derive(Debug, thiserror::Error, PartialEq, Eq, Clone)]
 pub enum Error {
@@ -94,6 +95,8 @@ pub struct LookupEvalDomain<'a, F: PrimeField> {
 pub struct PlonkEvalDomain<'a, F: PrimeField> {
     pub(crate) num_advice: usize,
     pub(crate) num_lookup: usize,
+    /// zero or one, see [`crate::plonk::PlonkStructure::num_instance_columns`]
+    pub(crate) num_instance: usize,
     // concatenation of challenges from two RelaxedPlonkInstance
     pub(crate) challenges: &'a [F],
     pub(crate) selectors: &'a [Vec<bool>],
@@ -102,6 +105,10 @@ pub struct PlonkEvalDomain<'a, F: PrimeField> {
     pub(crate) W1s: &'a [Vec<F>],
     // [`RelaxedPlonkWitness::W`] for second instance
     pub(crate) W2s: &'a [Vec<F>],
+    // [`RelaxedPlonkInstance::instance`] for first instance
+    pub(crate) instance1: &'a [F],
+    // [`RelaxedPlonkInstance::instance`] for second instance
+    pub(crate) instance2: &'a [F],
 }
 
 impl<'a, F: PrimeField> GetDataForEval<F> for LookupEvalDomain<'a, F> {
@@ -152,79 +159,329 @@ impl<'a, F: PrimeField> GetDataForEval<F> for PlonkEvalDomain<'a, F> {
     }
 
     fn eval_advice_var(&self, row: usize, index: usize) -> Result<F, Error> {
-        let row_size = self.row_size();
+        self.read_witness(&self.witness_position(index)?, self.row_size(), row)
+    }
+}
+
+/// Position of folded query value, see [`WitnessSource`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WitnessPosition {
+    is_first_instance: bool,
+    source: WitnessSource,
+    /// index of query within one instance, used for error reporting
+    index: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WitnessSource {
+    /// Advice or lookup value: `W[round][column * row_size + row]`, where `W` is
+    /// [`PlonkEvalDomain::W1s`] or [`PlonkEvalDomain::W2s`]
+    W { round: usize, column: usize },
+    /// Instance value: `instance[row]`, where `instance` is [`PlonkEvalDomain::instance1`] or
+    /// [`PlonkEvalDomain::instance2`]. Rows after public inputs are zero
+    Instance,
+}
+
+impl<'a, F: PrimeField> PlonkEvalDomain<'a, F> {
+    fn num_witness(&self, is_first_instance: bool) -> usize {
+        if is_first_instance {
+            self.W1s.len()
+        } else {
+            self.W2s.len()
+        }
+    }
+
+    /// Maps advice, lookup or instance query index into position inside witness or instance of
+    /// one of pairs
+    fn witness_position(&self, index: usize) -> Result<WitnessPosition, Error> {
         let num_advice = self.num_advice;
-        let num_lookup = self.num_lookup();
+        let num_lookup = self.num_lookup;
+        let instance_start = num_advice + num_lookup * 5;
         // maximum index for one instance
-        let max_width = num_advice + num_lookup * 5;
+        let max_width = instance_start + self.num_instance;
         let (is_first_instance, index) = if index < max_width {
             (true, index)
         } else {
             (false, index - max_width)
         };
-        let num_witness = if is_first_instance {
-            self.W1s.len()
-        } else {
-            self.W2s.len()
+        let num_witness = self.num_witness(is_first_instance);
+
+        let position = |round: usize, column: usize| WitnessPosition {
+            is_first_instance,
+            source: WitnessSource::W { round, column },
+            index,
         };
 
-        let index_map = |index: usize| -> Result<(usize, usize), Error> {
-            if index < num_advice {
-                return Ok((0, index));
-            }
+        if index < num_advice {
+            return Ok(position(0, index));
+        }
+        if index >= instance_start {
+            return Ok(WitnessPosition {
+                is_first_instance,
+                source: WitnessSource::Instance,
+                index,
+            });
+        }
 
-            let lookup_index = (index - num_advice) / 5;
-            let lookup_sub_index = (index - num_advice) % 5;
-            let (is_first_round, lookup_sub_index) = if lookup_sub_index < 3 {
-                (true, lookup_sub_index)
-            } else {
-                (false, lookup_sub_index - 3)
-            };
-            match num_witness {
-                2 => {
-                    if is_first_round {
-                        Ok((0, num_advice + lookup_index * 3 + lookup_sub_index))
-                    } else {
-                        Ok((1, lookup_index * 2 + lookup_sub_index))
-                    }
-                }
-                3 => {
-                    if is_first_round {
-                        Ok((1, lookup_index * 3 + lookup_sub_index))
-                    } else {
-                        Ok((2, lookup_index * 2 + lookup_sub_index))
-                    }
+        let lookup_index = (index - num_advice) / 5;
+        let lookup_sub_index = (index - num_advice) % 5;
+        let (is_first_round, lookup_sub_index) = if lookup_sub_index < 3 {
+            (true, lookup_sub_index)
+        } else {
+            (false, lookup_sub_index - 3)
+        };
+        match num_witness {
+            2 => {
+                if is_first_round {
+                    Ok(position(
+                        0,
+                        num_advice + lookup_index * 3 + lookup_sub_index,
+                    ))
+                } else {
+                    Ok(position(1, lookup_index * 2 + lookup_sub_index))
                 }
-                num_witness => Err(Error::InvalidWitnessIndex {
-                    num_witness,
-                    num_advice,
-                    num_lookup,
-                    index,
-                }),
             }
-        };
-
-        let (i, j) = index_map(index)?;
-        if is_first_instance {
-            if self.W1s.len() <= i || self.W1s[i].len() <= j * row_size + row {
-                Err(Error::InvalidWitnessIndex {
-                    num_witness,
-                    num_advice,
-                    num_lookup,
-                    index,
-                })
-            } else {
-                Ok(self.W1s[i][j * row_size + row])
+            3 => {
+                if is_first_round {
+                    Ok(position(1, lookup_index * 3 + lookup_sub_index))
+                } else {
+                    Ok(position(2, lookup_index * 2 + lookup_sub_index))
+                }
             }
-        } else if self.W2s.len() <= i || self.W2s[i].len() <= j * row_size + row {
-            Err(Error::InvalidWitnessIndex {
+            num_witness => Err(Error::InvalidWitnessIndex {
                 num_witness,
                 num_advice,
                 num_lookup,
                 index,
-            })
+            }),
+        }
+    }
+
+    fn read_witness(
+        &self,
+        position: &WitnessPosition,
+        row_size: usize,
+        row: usize,
+    ) -> Result<F, Error> {
+        let (W, instance) = if position.is_first_instance {
+            (self.W1s, self.instance1)
         } else {
-            Ok(self.W2s[i][j * row_size + row])
+            (self.W2s, self.instance2)
+        };
+
+        let (round, column) = match position.source {
+            WitnessSource::W { round, column } => (round, column),
+            WitnessSource::Instance => return Ok(instance.get(row).copied().unwrap_or(F::ZERO)),
+        };
+
+        W.get(round)
+            .and_then(|Wi| Wi.get(column * row_size + row))
+            .copied()
+            .ok_or(Error::InvalidWitnessIndex {
+                num_witness: W.len(),
+                num_advice: self.num_advice,
+                num_lookup: self.num_lookup,
+                index: position.index,
+            })
+    }
+}
+
+/// Flat view over [`PlonkEvalDomain`]
+///
+/// Query index to witness position mapping is resolved once at creation, so evaluation of any
+/// row is plain indexing. Used for row-by-row evaluation of whole table, e.g. while checking
+/// satisfiability or computing cross terms
+pub struct RowView<'a, F: PrimeField> {
+    domain: PlonkEvalDomain<'a, F>,
+    row_size: usize,
+    positions: Box<[Result<WitnessPosition, Error>]>,
+}
+
+impl<'a, F: PrimeField> RowView<'a, F> {
+    pub fn new(domain: PlonkEvalDomain<'a, F>) -> Self {
+        let max_width = domain.num_advice + domain.num_lookup * 5 + domain.num_instance;
+
+        Self {
+            row_size: domain.row_size(),
+            positions: (0..2 * max_width)
+                .map(|index| domain.witness_position(index))
+                .collect(),
+            domain,
+        }
+    }
+}
+
+impl<'a, F: PrimeField> GetDataForEval<F> for RowView<'a, F> {
+    fn num_lookup(&self) -> usize {
+        self.domain.num_lookup
+    }
+
+    fn get_challenges(&self) -> &impl AsRef<[F]> {
+        &self.domain.challenges
+    }
+
+    fn get_selectors(&self) -> &impl AsRef<[Vec<bool>]> {
+        &self.domain.selectors
+    }
+
+    fn get_fixed(&self) -> &impl AsRef<[Vec<F>]> {
+        &self.domain.fixed
+    }
+
+    fn row_size(&self) -> usize {
+        self.row_size
+    }
+
+    fn eval_advice_var(&self, row: usize, index: usize) -> Result<F, Error> {
+        let position = self
+            .positions
+            .get(index)
+            .ok_or(Error::ColumnVariableIndexOutOfBoundary {
+                column_index: index,
+            })?
+            .as_ref()
+            .map_err(Clone::clone)?;
+
+        self.domain.read_witness(position, self.row_size, row)
+    }
+}
+
+/// Values of sub-expression on all rows, see [`evaluate_columnwise`]
+enum ColumnValues<F> {
+    /// the same value on each row, e.g. constant or challenge
+    Constant(F),
+    Values(Vec<F>),
+}
+
+impl<F: PrimeField> ColumnValues<F> {
+    fn map(self, f: impl Fn(F) -> F + Sync + Send) -> Self {
+        match self {
+            Self::Constant(value) => Self::Constant(f(value)),
+            Self::Values(mut values) => {
+                values.par_iter_mut().for_each(|value| *value = f(*value));
+                Self::Values(values)
+            }
+        }
+    }
+
+    fn zip_with(self, other: Self, f: impl Fn(F, F) -> F + Sync + Send) -> Self {
+        match (self, other) {
+            (Self::Constant(lhs), Self::Constant(rhs)) => Self::Constant(f(lhs, rhs)),
+            (Self::Constant(lhs), Self::Values(rhs)) => Self::Values(rhs).map(|rhs| f(lhs, rhs)),
+            (Self::Values(lhs), Self::Constant(rhs)) => Self::Values(lhs).map(|lhs| f(lhs, rhs)),
+            (Self::Values(mut lhs), Self::Values(rhs)) => {
+                lhs.par_iter_mut()
+                    .zip_eq(rhs.par_iter())
+                    .for_each(|(lhs, rhs)| *lhs = f(*lhs, *rhs));
+                Self::Values(lhs)
+            }
+        }
+    }
+}
+
+/// Evaluates `expr` on all rows of `data` column by column
+///
+/// Unlike row-by-row evaluation with [`crate::polynomial::graph_evaluator::GraphEvaluator`],
+/// each node of expression is evaluated once into a whole column of values on rows of table and
+/// nodes are combined with element-wise operations. This trades memory (one column per live node)
+/// for vectorized & parallel loops.
+/// Rotations are resolved cyclically, same as in row-by-row evaluation.
+///
+/// Columns aren't interpolated into polynomials over an extended domain (no FFT/NTT), so it
+/// computes exactly the same values as row-by-row evaluation, just in another order
+pub fn evaluate_columnwise<F: PrimeField>(
+    expr: &Expression<F>,
+    data: &(impl GetDataForEval<F> + Sync),
+) -> Result<Vec<F>, Error> {
+    let row_size = data.row_size();
+
+    let values = expr.evaluate(
+        &|value| Ok(ColumnValues::Constant(value)),
+        &|query| {
+            (0..row_size)
+                .into_par_iter()
+                .map(|row| {
+                    let rotated = (row as i32 + query.rotation.0).rem_euclid(row_size as i32);
+                    data.eval_column_var(rotated as usize, query.index)
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(ColumnValues::Values)
+        },
+        &|index| data.eval_challenge(index).map(ColumnValues::Constant),
+        &|a| Ok(a?.map(|a| -a)),
+        &|a, b| Ok(a?.zip_with(b?, |a, b| a + b)),
+        &|a, b| Ok(a?.zip_with(b?, |a, b| a * b)),
+        &|a, k| Ok(a?.map(|a| a * k)),
+    )?;
+
+    Ok(match values {
+        ColumnValues::Constant(value) => vec![value; row_size],
+        ColumnValues::Values(values) => values,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ff::Field;
+    use halo2curves::pasta::Fp;
+
+    use super::*;
+
+    #[test]
+    fn row_view_matches_domain() {
+        const ROWS: usize = 4;
+        const NUM_ADVICE: usize = 2;
+        const NUM_LOOKUP: usize = 1;
+
+        let witness = |shift: u64| {
+            vec![
+                (0..(NUM_ADVICE + 3 * NUM_LOOKUP) * ROWS)
+                    .map(|i| Fp::from(shift + i as u64))
+                    .collect::<Vec<_>>(),
+                (0..2 * NUM_LOOKUP * ROWS)
+                    .map(|i| Fp::from(shift + 100 + i as u64))
+                    .collect(),
+            ]
+        };
+        let (W1, W2) = (witness(0), witness(1000));
+        let selectors = vec![vec![true; ROWS]];
+        let fixed = vec![vec![Fp::ONE; ROWS]];
+
+        let (instance1, instance2) = ([Fp::from(7), Fp::from(8)], [Fp::from(9), Fp::from(10)]);
+
+        let domain = || PlonkEvalDomain {
+            num_advice: NUM_ADVICE,
+            num_lookup: NUM_LOOKUP,
+            num_instance: 1,
+            challenges: &[],
+            selectors: &selectors,
+            fixed: &fixed,
+            W1s: &W1,
+            W2s: &W2,
+            instance1: &instance1,
+            instance2: &instance2,
+        };
+        let view = RowView::new(domain());
+        let domain = domain();
+
+        let max_width = NUM_ADVICE + 5 * NUM_LOOKUP + 1;
+        // instance is the last folded variable of each pair, rows after public inputs are zero
+        assert_eq!(domain.eval_advice_var(1, max_width - 1), Ok(Fp::from(8)));
+        assert_eq!(
+            domain.eval_advice_var(1, 2 * max_width - 1),
+            Ok(Fp::from(10))
+        );
+        assert_eq!(domain.eval_advice_var(2, max_width - 1), Ok(Fp::ZERO));
+
+        for row in 0..ROWS {
+            for index in 0..2 * max_width {
+                assert_eq!(
+                    view.eval_advice_var(row, index),
+                    domain.eval_advice_var(row, index),
+                    "row: {row}, index: {index}"
+                );
+                assert!(view.eval_advice_var(row, index).is_ok());
+            }
         }
     }
 }
@@ -44,7 +44,7 @@ use std::{
 use ff::PrimeField;
 use halo2_proofs::{plonk::ConstraintSystem, poly::Rotation};
 use rayon::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tracing::*;
 
 use crate::{
@@ -53,7 +53,10 @@ use crate::{
         util::compress_halo2_expression,
         PlonkStructure,
     },
-    polynomial::{graph_evaluator::GraphEvaluator, Expression, Query},
+    polynomial::{
+        expression::QueryIndexContext, graph_evaluator::GraphEvaluator, Expression, Halo2ExprError,
+        Query,
+    },
 };
 
 /// Lookup Argument
@@ -69,7 +72,7 @@ use crate::{
 /// into a single (i.e. non-vector) Expression:
 /// - lookup_poly = L(x_1,...,x_a) = a_1 + a_2*r + a_3*r^2 + ...
 /// - table_poly  = T(y_1,...,y_b) = t_1 + t_2*r + t_3*r^2 + ...
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Arguments<F: PrimeField> {
     /// vector of the compressed lookup expressions
     /// L_i(x_1,...,x_{a_i})
@@ -83,44 +86,58 @@ pub struct Arguments<F: PrimeField> {
 
 impl<F: PrimeField> Arguments<F> {
     /// Compresses a potentially vector Lookup Argument from a constraint system into non-vector expression.
-    pub fn compress_from(cs: &ConstraintSystem<F>) -> Option<Self> {
-        let max_lookup_len = cs
+    ///
+    /// Fails if some lookup queries instance column or halo2 challenge, that can't be converted,
+    /// see [`Expression::from_halo2_expr`]
+    pub fn compress_from(cs: &ConstraintSystem<F>) -> Result<Option<Self>, Halo2ExprError> {
+        let Some(max_lookup_len) = cs
             .lookups()
             .iter()
             .map(|arg| arg.input_expressions().len())
             .max()
-            .filter(|l| *l != 0)?;
+            .filter(|l| *l != 0)
+        else {
+            return Ok(None);
+        };
 
         let has_vector_lookup = max_lookup_len > 1;
+        // Lookups are evaluated over advice only, see `LookupEvalDomain`, so instance queries
+        // aren't mapped & rejected
+        let ctx = QueryIndexContext {
+            num_selectors: cs.num_selectors(),
+            num_fixed: cs.num_fixed_columns(),
+            num_advice: cs.num_advice_columns(),
+            ..Default::default()
+        };
 
         let (lookup_polys, table_polys) = cs
             .lookups()
             .iter()
             .map(|arg| {
-                (
+                Ok((
                     compress_halo2_expression(
                         arg.input_expressions(),
-                        cs.num_selectors(),
-                        cs.num_fixed_columns(),
+                        &ctx,
                         // compress vector table items with r1 (challenge_index = 0)
                         0,
-                    ),
+                    )?,
                     compress_halo2_expression(
                         arg.table_expressions(),
-                        cs.num_selectors(),
-                        cs.num_fixed_columns(),
+                        &ctx,
                         // compress vector lookups with r1 (challenge_index = 0)
                         0,
-                    ),
-                )
+                    )?,
+                ))
             })
+            .collect::<Result<Vec<_>, Halo2ExprError>>()?
+            .into_iter()
             .unzip();
 
-        Some(Self {
+        Ok(Some(Self {
             lookup_polys,
             table_polys,
             has_vector_lookup,
-        })
+        }))
     }
 
     /// TODO #159
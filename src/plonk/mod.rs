@@ -14,12 +14,19 @@
 //!
 //! Additionally, it defines a method is_sat on PlonkStructure to determine if
 //! a given Plonk instance and witness satisfy the circuit constraints.
-use std::{iter, num::NonZeroUsize, time::Instant};
+use std::{
+    collections::BTreeSet,
+    io, iter, mem,
+    num::NonZeroUsize,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
 
 use count_to_non_zero::*;
 use itertools::Itertools;
+use rand_core::OsRng;
 use rayon::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use some_to_err::*;
 use tracing::{debug, error, info, instrument, warn};
 
@@ -27,15 +34,17 @@ use ff::{Field, PrimeField};
 use halo2_proofs::arithmetic::{best_multiexp, CurveAffine};
 
 use crate::{
-    commitment::CommitmentKey,
+    commitment::{self, CommitmentKey},
     concat_vec,
     constants::NUM_CHALLENGE_BITS,
+    digest::{self, DigestToCurve},
     plonk::{
         self,
-        eval::{Error as EvalError, GetDataForEval, PlonkEvalDomain},
+        error_vector::{ErrorTerm, ErrorVector},
+        eval::{Error as EvalError, GetDataForEval, PlonkEvalDomain, RowView},
     },
     polynomial::{
-        expression::{HomogeneousExpression, QueryIndexContext},
+        expression::{ColumnIndex, HomogeneousExpression, QueryIndexContext},
         graph_evaluator::GraphEvaluator,
         grouped_poly::GroupedPoly,
         sparse::{matrix_multiply, SparseMatrix},
@@ -43,9 +52,11 @@ use crate::{
     },
     poseidon::{AbsorbInRO, ROTrait},
     sps::{Error as SpsError, SpecialSoundnessVerifier},
+    table,
     util::{concatenate_with_padding, fe_to_fe},
 };
 
+pub mod error_vector;
 pub mod eval;
 pub mod lookup;
 pub mod permutation;
@@ -57,6 +68,8 @@ pub enum Error {
     Sps(#[from] SpsError),
     #[error(transparent)]
     Eval(#[from] EvalError),
+    #[error(transparent)]
+    Commitment(#[from] commitment::Error),
     #[error("(Relaxed) plonk relation not satisfied: commitment mismatch")]
     CommitmentMismatch { mismatch_count: NonZeroUsize },
     #[error("(Relaxed) plonk relation not satisfied: commitment of E")]
@@ -70,6 +83,54 @@ pub enum Error {
         mismatch_count: NonZeroUsize,
         total_row: usize,
     },
+    #[error("Gate {gate_index} is a nonzero constant, so it can't be satisfied")]
+    ConstantGate { gate_index: usize },
+    #[error("Commitment of instance doesn't match instance values")]
+    InstanceCommitmentMismatch,
+    #[error("Commitment {name} is not a valid curve point")]
+    InvalidCommitment { name: &'static str },
+    #[error("Cross term {index} length mismatch: expected {expected}, but actual {actual}")]
+    CrossTermLengthMismatch {
+        index: usize,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Instances have different count of public inputs: {expected} != {actual}")]
+    NumIoMismatch { expected: usize, actual: usize },
+    #[error("Instance doesn't match structure: {field} expected {expected}, but actual {actual}")]
+    StructureMismatch {
+        field: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Homogeneous gates don't match original ones with u = 1 on row {row}")]
+    HomogenizationMismatch { row: usize },
+    #[error("Compressed witness can't be restored: {reason}")]
+    Decompress { reason: &'static str },
+    #[error("Malformed structure: {reason}")]
+    MalformedStructure { reason: &'static str },
+}
+
+/// Original gate, that isn't satisfied on some row, see [`PlonkStructure::unsatisfied_gates`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GateFailure {
+    /// Index of gate in order of `create_gate`, see [`PlonkStructure::gate_combination_powers`]
+    pub gate_index: usize,
+    /// Name of gate given in `create_gate` with name of constraint, if any, or `lookup {i}`
+    pub gate_name: String,
+    pub row: usize,
+    /// Columns & challenges queried by the gate, indexed as in [`QueryIndexContext`]
+    pub columns: Vec<ColumnIndex>,
+}
+
+impl std::fmt::Display for GateFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "gate #{} `{}` not satisfied at row {}, queries: {:?}",
+            self.gate_index, self.gate_name, self.row, self.columns
+        )
+    }
 }
 
 /// This structure is a representation of a compressed set of custom gates & lookup
@@ -143,6 +204,9 @@ pub struct PlonkStructure<F: PrimeField> {
     pub(crate) fixed_columns: Vec<Vec<F>>,
 
     pub(crate) num_advice_columns: usize,
+    /// Count of instance columns queried by gates: zero or one, values of the column are
+    /// [`PlonkInstance::instance`]
+    pub(crate) num_instance_columns: usize,
 
     /// We follow the special soundness protocol(SPS), section 3.1 in [Protostar](https://eprint.iacr.org/2023/620)
     /// let k = num_challenges; when k > 0, we add extra verifier round, this is slightly different
@@ -152,21 +216,107 @@ pub struct PlonkStructure<F: PrimeField> {
     /// specify the witness size of each prover round
     pub(crate) round_sizes: Vec<usize>,
 
+    /// Derived from `gates`, so it isn't serialized, but rebuilt on deserialization
+    ///
+    /// TODO #262: after we switch from Sangaria IVC to IVC with cyclefold + protogalaxy
+    /// we will remove the field custom_gates_lookup_compressed
+    #[serde(skip_serializing)]
     pub(crate) custom_gates_lookup_compressed: CompressedGates<F>,
 
-    /// TODO #262: after we switch from Sangaria IVC to IVC with cyclefold + protogalaxy
-    /// we will remove the field custom_gates_lookup_compressed and make gates field serializable
-    /// instead
     /// we use uncompressed gates instead of
     /// custom_gates_lookup_compressed in protogalaxy folding scheme
-    #[serde(skip_serializing)]
     pub(crate) gates: Vec<Expression<F>>,
+    /// Names of [`PlonkStructure::gates`], given to them in circuit, used only for diagnostics
+    ///
+    /// Not serialized, so renaming of gate doesn't change digest of structure
+    #[serde(skip_serializing)]
+    pub(crate) gate_names: Vec<String>,
+    /// Index of each of [`PlonkStructure::gates`] in order of `create_gate`, lookups follow
+    /// custom gates. Gates are sorted to not depend on that order, so it's kept for diagnostics
+    /// only & isn't serialized
+    #[serde(skip_serializing)]
+    pub(crate) gate_indices: Vec<usize>,
 
     pub(crate) permutation_matrix: SparseMatrix<F>,
     pub(crate) lookup_arguments: Option<lookup::Arguments<F>>,
 }
 
-#[derive(Clone, Debug)]
+impl<'de, F: PrimeField + Deserialize<'de>> Deserialize<'de> for PlonkStructure<F> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// Serialized fields of [`PlonkStructure`] in the same order
+        #[derive(Deserialize)]
+        #[serde(bound(deserialize = "F: Deserialize<'de>"))]
+        struct Serialized<F: PrimeField> {
+            k: usize,
+            num_io: usize,
+            selectors: Vec<Vec<bool>>,
+            fixed_columns: Vec<Vec<F>>,
+            num_advice_columns: usize,
+            num_instance_columns: usize,
+            num_challenges: usize,
+            round_sizes: Vec<usize>,
+            gates: Vec<Expression<F>>,
+            permutation_matrix: SparseMatrix<F>,
+            lookup_arguments: Option<lookup::Arguments<F>>,
+        }
+
+        let Serialized {
+            k,
+            num_io,
+            selectors,
+            fixed_columns,
+            num_advice_columns,
+            num_instance_columns,
+            num_challenges,
+            round_sizes,
+            gates,
+            permutation_matrix,
+            lookup_arguments,
+        } = Serialized::deserialize(deserializer)?;
+
+        let (num_lookups, has_vector_lookup) = lookup_shape(lookup_arguments.as_ref());
+        let custom_gates_lookup_compressed = CompressedGates::new(
+            &gates,
+            &mut QueryIndexContext {
+                num_selectors: selectors.len(),
+                num_fixed: fixed_columns.len(),
+                num_advice: num_advice_columns,
+                num_lookups,
+                num_instance: num_instance_columns,
+                num_challenges: table::lookup_challenges(num_lookups, has_vector_lookup),
+            },
+        );
+
+        let S = Self {
+            k,
+            num_io,
+            selectors,
+            fixed_columns,
+            num_advice_columns,
+            num_instance_columns,
+            num_challenges,
+            round_sizes,
+            custom_gates_lookup_compressed,
+            gates,
+            gate_names: vec![],
+            gate_indices: vec![],
+            permutation_matrix,
+            lookup_arguments,
+        };
+        S.validate().map_err(serde::de::Error::custom)?;
+
+        Ok(S)
+    }
+}
+
+/// `(num_lookups, has_vector_lookup)` of lookup argument, if any
+fn lookup_shape<F: PrimeField>(lookup_arguments: Option<&lookup::Arguments<F>>) -> (usize, bool) {
+    lookup_arguments.map_or((0, false), |arg| {
+        (arg.lookup_polys.len(), arg.has_vector_lookup)
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct PlonkInstance<C: CurveAffine> {
     /// `W_commitments = round_sizes.len()`, see [`PlonkStructure::round_sizes`]
     pub(crate) W_commitments: Vec<C>,
@@ -180,40 +330,100 @@ pub struct PlonkInstance<C: CurveAffine> {
     /// r3: combine all custom gates (P_i) and lookup relations (L_i), e.g.:
     /// (P_1, P_2, L_1, L_2) -> P_1 + r3*P_2 + r3^2*L_1 + r3^3*L_2
     pub(crate) challenges: Vec<C::ScalarExt>,
+    /// Optional commitment of `instance`, see [`PlonkInstance::commit_instance`]
+    ///
+    /// If present, it's absorbed into RO together with raw `instance` values
+    pub(crate) instance_commitment: Option<C>,
 }
 
+/// Decoded instance is checked by [`PlonkInstance::validate`], same as [`PlonkStructure`]
+impl<'de, C> Deserialize<'de> for PlonkInstance<C>
+where
+    C: CurveAffine + Deserialize<'de>,
+    C::ScalarExt: Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// Serialized fields of [`PlonkInstance`] in the same order
+        #[derive(Deserialize)]
+        #[serde(bound(deserialize = "C: Deserialize<'de>, C::ScalarExt: Deserialize<'de>"))]
+        struct Serialized<C: CurveAffine> {
+            W_commitments: Vec<C>,
+            instance: Vec<C::ScalarExt>,
+            challenges: Vec<C::ScalarExt>,
+            instance_commitment: Option<C>,
+        }
+
+        let Serialized {
+            W_commitments,
+            instance,
+            challenges,
+            instance_commitment,
+        } = Serialized::deserialize(deserializer)?;
+
+        let u = Self {
+            W_commitments,
+            instance,
+            challenges,
+            instance_commitment,
+        };
+        u.validate().map_err(serde::de::Error::custom)?;
+
+        Ok(u)
+    }
+}
+
+/// Instance without rounds, public inputs & challenges, i.e. `PlonkInstance::new(0, 0, 0)`
+///
+/// Arity of public inputs is defined by `num_io` of [`PlonkStructure`], so there is
+/// no sensible nonempty default
 impl<C: CurveAffine> Default for PlonkInstance<C> {
     fn default() -> Self {
         Self {
             W_commitments: vec![],
-            instance: vec![C::ScalarExt::ZERO, C::ScalarExt::ZERO], // TODO Fix Me
+            instance: vec![],
             challenges: vec![],
+            instance_commitment: None,
         }
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PlonkWitness<F: PrimeField> {
     /// length of W equals number of prover rounds, see [`PlonkStructure`]
     pub(crate) W: Vec<Vec<F>>,
+    /// blinding factor of each round commitment, see [`CommitmentKey::commit_hiding`]
+    ///
+    /// Zero for non-hiding commitments
+    pub(crate) blinds: Vec<F>,
 }
 
 impl<F: PrimeField> PlonkWitness<F> {
     pub fn new(round_sizes: &[usize]) -> Self {
         Self {
             W: round_sizes.iter().map(|sz| vec![F::ZERO; *sz]).collect(),
+            blinds: vec![F::ZERO; round_sizes.len()],
         }
     }
 
-    pub fn to_relax(&self, k_table_size: usize) -> RelaxedPlonkWitness<F> {
+    /// Witness values of each prover round
+    pub fn W_mut(&mut self) -> &mut [Vec<F>] {
+        &mut self.W
+    }
+
+    /// Moves `W` into relaxed witness, so nothing but the chunk list of zero `E` is allocated
+    ///
+    /// There is no borrowing version: copy of `W` is explicit at call site, if the witness is
+    /// still needed
+    pub fn into_relax(mut self, k_table_size: usize) -> RelaxedPlonkWitness<F> {
         RelaxedPlonkWitness {
-            W: self.W.clone(),
-            E: vec![F::ZERO; 1 << k_table_size].into_boxed_slice(),
+            W: mem::take(&mut self.W),
+            W_blinds: mem::take(&mut self.blinds),
+            E: ErrorVector::zero(1 << k_table_size),
         }
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct RelaxedPlonkInstance<C: CurveAffine> {
     pub(crate) W_commitments: Vec<C>,
     pub(crate) E_commitment: C,
@@ -222,28 +432,270 @@ pub struct RelaxedPlonkInstance<C: CurveAffine> {
     pub(crate) challenges: Vec<C::ScalarExt>,
     /// homogenous variable u
     pub(crate) u: C::ScalarExt,
+    /// commitment of `instance`, folded linearly, see [`PlonkInstance::instance_commitment`]
+    pub(crate) instance_commitment: Option<C>,
 }
 
-#[derive(Clone, Debug)]
+/// Decoded instance is checked by [`RelaxedPlonkInstance::validate`]
+impl<'de, C> Deserialize<'de> for RelaxedPlonkInstance<C>
+where
+    C: CurveAffine + Deserialize<'de>,
+    C::ScalarExt: Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// Serialized fields of [`RelaxedPlonkInstance`] in the same order
+        #[derive(Deserialize)]
+        #[serde(bound(deserialize = "C: Deserialize<'de>, C::ScalarExt: Deserialize<'de>"))]
+        struct Serialized<C: CurveAffine> {
+            W_commitments: Vec<C>,
+            E_commitment: C,
+            instance: Vec<C::ScalarExt>,
+            challenges: Vec<C::ScalarExt>,
+            u: C::ScalarExt,
+            instance_commitment: Option<C>,
+        }
+
+        let Serialized {
+            W_commitments,
+            E_commitment,
+            instance,
+            challenges,
+            u,
+            instance_commitment,
+        } = Serialized::deserialize(deserializer)?;
+
+        let U = Self {
+            W_commitments,
+            E_commitment,
+            instance,
+            challenges,
+            u,
+            instance_commitment,
+        };
+        U.validate().map_err(serde::de::Error::custom)?;
+
+        Ok(U)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct RelaxedPlonkWitness<F: PrimeField> {
     /// each vector element in W is a vector folded from an old [`RelaxedPlonkWitness.W`] and [`PlonkWitness.W`]
     pub(crate) W: Vec<Vec<F>>,
-    pub(crate) E: Box<[F]>,
+    /// blinding factors of `W` commitments, folded linearly together with `W`
+    pub(crate) W_blinds: Vec<F>,
+    /// `E` is committed without blinding, as well as cross terms
+    ///
+    /// Stored by chunks, zero chunks aren't allocated, see [`ErrorVector`]
+    pub(crate) E: ErrorVector<F>,
+}
+
+/// Overwrites witness values with zeros on drop, enabled by `zeroize` feature
+///
+/// Lengths of vectors are kept, only their contents are wiped
+#[cfg(feature = "zeroize")]
+mod wipe {
+    use std::{ptr, sync::atomic};
+
+    use ff::PrimeField;
+    use zeroize::{Zeroize, ZeroizeOnDrop};
+
+    use super::{PlonkWitness, RelaxedPlonkWitness};
+
+    fn wipe<F: PrimeField>(values: &mut [F]) {
+        // volatile writes & the fence keep compiler from eliding stores to memory about to be freed
+        values
+            .iter_mut()
+            .for_each(|value| unsafe { ptr::write_volatile(value, F::ZERO) });
+        atomic::compiler_fence(atomic::Ordering::SeqCst);
+    }
+
+    impl<F: PrimeField> Zeroize for PlonkWitness<F> {
+        fn zeroize(&mut self) {
+            self.W.iter_mut().for_each(|W| wipe(W));
+            wipe(&mut self.blinds);
+        }
+    }
+
+    impl<F: PrimeField> Drop for PlonkWitness<F> {
+        fn drop(&mut self) {
+            self.zeroize();
+        }
+    }
+
+    impl<F: PrimeField> ZeroizeOnDrop for PlonkWitness<F> {}
+
+    impl<F: PrimeField> Zeroize for RelaxedPlonkWitness<F> {
+        fn zeroize(&mut self) {
+            self.W.iter_mut().for_each(|W| wipe(W));
+            wipe(&mut self.W_blinds);
+            self.E.chunks_mut().for_each(wipe);
+        }
+    }
+
+    impl<F: PrimeField> Drop for RelaxedPlonkWitness<F> {
+        fn drop(&mut self) {
+            self.zeroize();
+        }
+    }
+
+    impl<F: PrimeField> ZeroizeOnDrop for RelaxedPlonkWitness<F> {}
+}
+
+/// Storage form of [`RelaxedPlonkWitness`], see [`RelaxedPlonkWitness::compress`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompressedWitness<F: PrimeField> {
+    W: CompressedRounds<F>,
+    W_blinds: Vec<F>,
+    /// `None` if all elements of `E` are zero, e.g. right after the base step
+    E: Option<Box<[F]>>,
+    E_len: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum CompressedRounds<F> {
+    /// Each round of `W` as is
+    Full(Vec<Vec<F>>),
+    /// Nonzero `(index, W[index] - prev.W[index])` of each round against previous witness
+    Delta(Vec<Vec<(usize, F)>>),
+}
+
+impl<F: PrimeField> CompressedWitness<F> {
+    /// Restores witness compressed with [`RelaxedPlonkWitness::compress`]
+    ///
+    /// `prev` must be the same witness, that was passed to [`RelaxedPlonkWitness::compress`].
+    /// It's ignored if `W` was stored as is. `E` length is checked against `S` before allocation
+    pub fn decompress(
+        &self,
+        S: &PlonkStructure<F>,
+        prev: Option<&RelaxedPlonkWitness<F>>,
+    ) -> Result<RelaxedPlonkWitness<F>, Error> {
+        if self.E_len != 1 << S.k {
+            return Err(Error::Decompress {
+                reason: "length of `E` doesn't match structure size",
+            });
+        }
+        if self.E.as_ref().is_some_and(|E| E.len() != self.E_len) {
+            return Err(Error::Decompress {
+                reason: "stored `E` doesn't match its length",
+            });
+        }
+
+        let W = match &self.W {
+            CompressedRounds::Full(W) => W.clone(),
+            CompressedRounds::Delta(deltas) => {
+                let prev = prev.ok_or(Error::Decompress {
+                    reason: "witness stored as deltas, but previous witness is missing",
+                })?;
+                if prev.W.len() != deltas.len() {
+                    return Err(Error::Decompress {
+                        reason: "rounds count of previous witness doesn't match",
+                    });
+                }
+
+                prev.W
+                    .iter()
+                    .zip_eq(deltas.iter())
+                    .map(|(prev_W, deltas)| {
+                        let mut W = prev_W.clone();
+                        for (index, delta) in deltas {
+                            *W.get_mut(*index).ok_or(Error::Decompress {
+                                reason: "delta index is out of previous witness round",
+                            })? += delta;
+                        }
+                        Ok(W)
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?
+            }
+        };
+
+        Ok(RelaxedPlonkWitness {
+            W,
+            W_blinds: self.W_blinds.clone(),
+            E: self
+                .E
+                .as_ref()
+                .map_or_else(|| ErrorVector::zero(self.E_len), |E| E.to_vec().into()),
+        })
+    }
+}
+
+/// Machine-readable report on [`PlonkStructure`], see [`PlonkStructure::summary`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitSummary {
+    pub k: usize,
+    pub num_rows: usize,
+    pub num_fixed_columns: usize,
+    pub num_advice_columns: usize,
+    /// degree of homogeneous compressed gates
+    pub gate_degree: usize,
+    /// count of monomials of expanded homogeneous compressed gates
+    pub num_monomials: usize,
+    pub num_cross_terms: usize,
+}
+
+/// Timing breakdown of [`PlonkStructure::is_sat_relaxed_profiled`]
+#[derive(Debug, Clone, Default)]
+pub struct SatProfile {
+    /// evaluation of homogeneous gates on all rows
+    pub eval_time: Duration,
+    /// commitment of all rounds of `W`
+    pub w_commit_time: Duration,
+    /// commitment of `E`
+    pub e_commit_time: Duration,
+    /// rows where gates evaluation doesn't match `E`
+    pub failing_rows: Vec<usize>,
 }
 
 // TODO #31 docs
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RelaxedPlonkTrace<C: CurveAffine> {
     pub U: RelaxedPlonkInstance<C>,
     pub W: RelaxedPlonkWitness<C::Scalar>,
 }
 
 // TODO #31 docs
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlonkTrace<C: CurveAffine> {
     pub u: PlonkInstance<C>,
     pub w: PlonkWitness<C::Scalar>,
 }
 
+/// Reference to instance-witness pair of any kind, see [`PlonkStructure::verify`]
+#[derive(Clone, Copy)]
+pub enum AnyTrace<'link, C: CurveAffine> {
+    Plonk(&'link PlonkTrace<C>),
+    Relaxed(&'link RelaxedPlonkTrace<C>),
+}
+
+impl<'link, C: CurveAffine> From<&'link PlonkTrace<C>> for AnyTrace<'link, C> {
+    fn from(trace: &'link PlonkTrace<C>) -> Self {
+        Self::Plonk(trace)
+    }
+}
+
+impl<'link, C: CurveAffine> From<&'link RelaxedPlonkTrace<C>> for AnyTrace<'link, C> {
+    fn from(trace: &'link RelaxedPlonkTrace<C>) -> Self {
+        Self::Relaxed(trace)
+    }
+}
+
+/// Witness bundle of the base (not folded) step of IVC, see [`PlonkStructure::prove_base`]
+///
+/// Despite the name, it isn't a proof in the succinct sense & hides nothing: it carries the full
+/// witness of the base step, which is checked by [`PlonkStructure::verify_base`] with
+/// [`PlonkStructure::is_sat`]. Don't send it to a party, that mustn't learn the witness
+#[derive(Clone, Debug)]
+pub struct BaseProof<F: PrimeField> {
+    pub(crate) W: PlonkWitness<F>,
+}
+
+impl<F: PrimeField> BaseProof<F> {
+    pub fn witness(&self) -> &PlonkWitness<F> {
+        &self.W
+    }
+}
+
 /// Generalized trait to get witness
 ///
 /// Used to generalize:
@@ -307,29 +759,94 @@ impl<C: CurveAffine> GetChallenges<C::ScalarExt> for RelaxedPlonkTrace<C> {
     }
 }
 
+/// Generalized trait to get values of instance column, see [`PlonkInstance::instance`]
+///
+/// Used to generalize:
+/// - [`PlonkInstance`]
+/// - [`RelaxedPlonkInstance`]
+/// - [`RelaxedPlonkTrace`]
+/// - [`PlonkTrace`]
+pub(crate) trait GetInstance<F: PrimeField> {
+    fn get_instance(&self) -> &[F];
+}
+impl<C: CurveAffine> GetInstance<C::ScalarExt> for PlonkInstance<C> {
+    fn get_instance(&self) -> &[C::ScalarExt] {
+        &self.instance
+    }
+}
+impl<C: CurveAffine> GetInstance<C::ScalarExt> for RelaxedPlonkInstance<C> {
+    fn get_instance(&self) -> &[C::ScalarExt] {
+        &self.instance
+    }
+}
+impl<C: CurveAffine> GetInstance<C::ScalarExt> for PlonkTrace<C> {
+    fn get_instance(&self) -> &[C::ScalarExt] {
+        self.u.get_instance()
+    }
+}
+impl<C: CurveAffine> GetInstance<C::ScalarExt> for RelaxedPlonkTrace<C> {
+    fn get_instance(&self) -> &[C::ScalarExt] {
+        self.U.get_instance()
+    }
+}
+
 impl<C: CurveAffine> PlonkTrace<C> {
-    pub fn to_relax(&self, k: usize) -> RelaxedPlonkTrace<C> {
+    /// See [`PlonkWitness::into_relax`]
+    pub fn into_relax(self, k: usize) -> RelaxedPlonkTrace<C> {
         RelaxedPlonkTrace {
             U: self.u.to_relax(),
-            W: self.w.to_relax(k),
+            W: self.w.into_relax(k),
         }
     }
 }
 
+/// Absorbs commitment of instance, if it's present, and raw instance values with their length
+///
+/// Values are absorbed even with commitment: nothing on the verifier side checks the incoming
+/// commitment against `instance`, so the commitment alone doesn't bind the folded values
+fn absorb_instance<C: CurveAffine, RO: ROTrait<C::Base>>(
+    ro: &mut RO,
+    instance: &[C::ScalarExt],
+    instance_commitment: Option<&C>,
+) {
+    if let Some(commitment) = instance_commitment {
+        ro.absorb_point(commitment);
+    }
+    ro.absorb_field_slice(&to_base_fields::<C>(instance));
+}
+
+/// Scalars converted into base field to be absorbed, e.g. by [`ROTrait::absorb_field_slice`]
+fn to_base_fields<C: CurveAffine>(scalars: &[C::ScalarExt]) -> Vec<C::Base> {
+    scalars.iter().map(|s| fe_to_fe(s).unwrap()).collect()
+}
+
+/// Both sides of folding must agree on the use of instance commitment, otherwise it would be
+/// silently dropped from the folded instance
+fn check_instance_commitment_presence<C>(lhs: Option<&C>, rhs: Option<&C>) -> Result<(), Error> {
+    if lhs.is_some() != rhs.is_some() {
+        return Err(Error::StructureMismatch {
+            field: "instance commitments",
+            expected: lhs.iter().count(),
+            actual: rhs.iter().count(),
+        });
+    }
+    Ok(())
+}
+
 impl<C: CurveAffine, RO: ROTrait<C::Base>> AbsorbInRO<C::Base, RO> for PlonkInstance<C> {
     fn absorb_into(&self, ro: &mut RO) {
-        ro.absorb_point_iter(self.W_commitments.iter())
-            .absorb_field_iter(self.instance.iter().map(|inst| fe_to_fe(inst).unwrap()))
-            .absorb_field_iter(self.challenges.iter().map(|cha| fe_to_fe(cha).unwrap()));
+        ro.absorb_point_slice(&self.W_commitments);
+        absorb_instance(ro, &self.instance, self.instance_commitment.as_ref());
+        ro.absorb_field_slice(&to_base_fields::<C>(&self.challenges));
     }
 }
 
 impl<C: CurveAffine, RO: ROTrait<C::Base>> AbsorbInRO<C::Base, RO> for RelaxedPlonkInstance<C> {
     fn absorb_into(&self, ro: &mut RO) {
-        ro.absorb_point_iter(self.W_commitments.iter())
-            .absorb_point(&self.E_commitment)
-            .absorb_field_iter(self.instance.iter().map(|inst| fe_to_fe(inst).unwrap()))
-            .absorb_field_iter(self.challenges.iter().map(|cha| fe_to_fe(cha).unwrap()))
+        ro.absorb_point_slice(&self.W_commitments)
+            .absorb_point(&self.E_commitment);
+        absorb_instance(ro, &self.instance, self.instance_commitment.as_ref());
+        ro.absorb_field_slice(&to_base_fields::<C>(&self.challenges))
             .absorb_field(fe_to_fe(&self.u).unwrap());
     }
 }
@@ -343,7 +860,7 @@ impl<F: PrimeField> PlonkStructure<F> {
     /// return the number of variables to be folded
     /// each lookup argument will add 5 variables (l,t,m,h,g)
     pub fn num_fold_vars(&self) -> usize {
-        self.num_advice_columns + 5 * self.num_lookups()
+        self.num_advice_columns + 5 * self.num_lookups() + self.num_instance_columns
     }
 
     pub fn num_lookups(&self) -> usize {
@@ -362,137 +879,526 @@ impl<F: PrimeField> PlonkStructure<F> {
             .unwrap_or(false)
     }
 
-    pub fn is_sat<C, RO: ROTrait<C::Base>>(
+    /// Checks trace of any kind with the matching routine: [`PlonkStructure::is_sat`] for
+    /// [`PlonkTrace`] & [`PlonkStructure::is_sat_relaxed`] for [`RelaxedPlonkTrace`]
+    ///
+    /// `ro_nark` is used only for plain trace, to check challenges of special soundness protocol
+    pub fn verify<'link, C, RO: ROTrait<C::Base>>(
         &self,
         ck: &CommitmentKey<C>,
         ro_nark: &mut RO,
-        U: &PlonkInstance<C>,
-        W: &PlonkWitness<F>,
+        trace: impl Into<AnyTrace<'link, C>>,
     ) -> Result<(), Error>
     where
         C: CurveAffine<ScalarExt = F>,
     {
-        U.sps_verify(ro_nark)?;
-
-        let data = PlonkEvalDomain {
-            num_advice: self.num_advice_columns,
-            num_lookup: self.num_lookups(),
-            challenges: &U.challenges,
-            selectors: &self.selectors,
-            fixed: &self.fixed_columns,
-            W1s: &W.W,
-            W2s: &[],
-        };
-
-        let total_row = 1 << self.k;
-
-        let evaluator = GraphEvaluator::new(self.custom_gates_lookup_compressed.compressed());
-        (0..total_row)
-            .into_par_iter()
-            .map(|row| {
-                evaluator
-                    .evaluate(&data, row)
-                    .map(|row_result| if row_result.eq(&F::ZERO) { 0 } else { 1 })
-            })
-            .try_reduce(
-                || 0,
-                |mismatch_count, is_missed| Ok(mismatch_count + is_missed),
-            )
-            .map(|mismatch_count| {
-                Some(Error::EvaluationMismatch {
-                    mismatch_count: NonZeroUsize::new(mismatch_count)?,
-                    total_row,
-                })
-            })?
-            .err_or(())?;
-
-        if !self.is_sat_log_derivative(&W.W) {
-            return Err(Error::LogDerivativeNotSat);
+        match trace.into() {
+            AnyTrace::Plonk(PlonkTrace { u, w }) => self.is_sat(ck, ro_nark, u, w),
+            AnyTrace::Relaxed(RelaxedPlonkTrace { U, W }) => self.is_sat_relaxed(ck, U, W),
         }
+    }
 
-        U.W_commitments
-            .iter()
-            .zip_eq(W.W.iter())
-            .filter_map(|(Ci, Wi)| ck.commit(Wi).unwrap().ne(Ci).then_some(()))
-            .count_to_non_zero()
-            .map(|mismatch_count| Error::CommitmentMismatch { mismatch_count })
-            .err_or(())?;
+    /// Proves the base step: checks `U`, `W` with [`PlonkStructure::is_sat`] & wraps `W` into
+    /// [`BaseProof`]
+    ///
+    /// `ro_nark` must be in the same state, as the one used to run special soundness protocol
+    pub fn prove_base<C, RO: ROTrait<C::Base>>(
+        &self,
+        ck: &CommitmentKey<C>,
+        ro_nark: &mut RO,
+        U: &PlonkInstance<C>,
+        W: &PlonkWitness<F>,
+    ) -> Result<BaseProof<F>, Error>
+    where
+        C: CurveAffine<ScalarExt = F>,
+    {
+        self.is_sat(ck, ro_nark, U, W)?;
+        Ok(BaseProof { W: W.clone() })
+    }
 
-        Ok(())
+    /// Verifies [`BaseProof`] of `U` & returns the accumulator instance to fold next steps into
+    ///
+    /// The returned instance is [`PlonkInstance::to_relax`] of `U`, the same as the prover side
+    /// gets with [`PlonkTrace::into_relax`], so the base step is absorbed into RO exactly like an
+    /// accumulator produced by folding
+    pub fn verify_base<C, RO: ROTrait<C::Base>>(
+        &self,
+        ck: &CommitmentKey<C>,
+        ro_nark: &mut RO,
+        U: &PlonkInstance<C>,
+        proof: &BaseProof<F>,
+    ) -> Result<RelaxedPlonkInstance<C>, Error>
+    where
+        C: CurveAffine<ScalarExt = F>,
+    {
+        self.is_sat(ck, ro_nark, U, &proof.W)?;
+        Ok(U.to_relax())
     }
 
-    pub fn is_sat_relaxed<C>(
+    pub fn is_sat<C, RO: ROTrait<C::Base>>(
         &self,
         ck: &CommitmentKey<C>,
-        U: &RelaxedPlonkInstance<C>,
-        W: &RelaxedPlonkWitness<F>,
+        ro_nark: &mut RO,
+        U: &PlonkInstance<C>,
+        W: &PlonkWitness<F>,
     ) -> Result<(), Error>
     where
         C: CurveAffine<ScalarExt = F>,
     {
-        let total_row = 1 << self.k;
-
-        let data = PlonkEvalDomain {
-            num_advice: self.num_advice_columns,
-            num_lookup: self.num_lookups(),
-            challenges: &concat_vec!(&U.challenges, &[U.u]),
-            selectors: &self.selectors,
-            fixed: &self.fixed_columns,
-            W1s: &W.W,
-            W2s: &[],
-        };
+        self.is_sat_concurrent(ck, ro_nark, U, W, false)
+    }
 
-        let evaluator = GraphEvaluator::new(self.custom_gates_lookup_compressed.homogeneous());
-        (0..total_row)
-            .into_par_iter()
-            .map(|row| {
-                evaluator.evaluate(&data, row).map(|eval_of_row| {
-                    let expected = W.E[row];
-
-                    if eval_of_row.eq(&expected) {
-                        0
-                    } else {
-                        warn!("row {row} invalid: expected {expected:?}, but {eval_of_row:?}");
-                        1
-                    }
-                })
-            })
-            .try_reduce(
-                || 0,
-                |mismatch_count, is_missed| Ok(mismatch_count + is_missed),
-            )
-            .map(|mismatch_count| {
-                Some(Error::EvaluationMismatch {
-                    mismatch_count: NonZeroUsize::new(mismatch_count)?,
-                    total_row,
-                })
-            })?
-            .err_or(())?;
+    /// Same as [`PlonkStructure::is_sat`], but stops evaluation of rows as soon as witness
+    /// commitments are found to mismatch
+    ///
+    /// If both commitments and relation are broken, [`Error::CommitmentMismatch`] is returned
+    /// instead of the relation error reported by [`PlonkStructure::is_sat`]
+    pub fn is_sat_fail_fast<C, RO: ROTrait<C::Base>>(
+        &self,
+        ck: &CommitmentKey<C>,
+        ro_nark: &mut RO,
+        U: &PlonkInstance<C>,
+        W: &PlonkWitness<F>,
+    ) -> Result<(), Error>
+    where
+        C: CurveAffine<ScalarExt = F>,
+    {
+        self.is_sat_concurrent(ck, ro_nark, U, W, true)
+    }
 
-        if !self.is_sat_log_derivative(&W.W) {
-            return Err(Error::LogDerivativeNotSat);
-        }
+    /// Checks relation & commitments of [`PlonkStructure::is_sat`] concurrently, so MSM overlaps
+    /// with evaluation of rows
+    fn is_sat_concurrent<C, RO: ROTrait<C::Base>>(
+        &self,
+        ck: &CommitmentKey<C>,
+        ro_nark: &mut RO,
+        U: &PlonkInstance<C>,
+        W: &PlonkWitness<F>,
+        fail_fast: bool,
+    ) -> Result<(), Error>
+    where
+        C: CurveAffine<ScalarExt = F>,
+    {
+        U.sps_verify(ro_nark)?;
 
-        U.W_commitments
-            .iter()
-            .zip_eq(W.W.iter())
-            .filter_map(|(Ci, Wi)| ck.commit(Wi).unwrap().ne(Ci).then_some(()))
-            .count_to_non_zero()
-            .map(|mismatch_count| Error::CommitmentMismatch { mismatch_count })
-            .err_or(())?;
+        let interrupted = AtomicBool::new(false);
+        let (relation, commitments) = rayon::join(
+            || self.is_sat_relation_interruptible(U, W, &interrupted),
+            || {
+                let result = check_witness_commitments(ck, U, W);
+                if fail_fast && result.is_err() {
+                    interrupted.store(true, Ordering::Relaxed);
+                }
+                result
+            },
+        );
 
-        if ck.commit(&W.E).unwrap().ne(&U.E_commitment) {
-            return Err(Error::ECommitmentMismatch);
+        if fail_fast {
+            commitments?;
+            relation?;
+        } else {
+            relation?;
+            commitments?;
         }
 
-        Ok(())
+        check_instance_commitment(ck, &U.instance, U.instance_commitment.as_ref())
     }
 
-    // permutation check for folding instance-witness pair
-    pub fn is_sat_perm<C>(
+    /// Checks only relation part of [`PlonkStructure::is_sat`]: custom gates & lookup
+    ///
+    /// Neither commitments, nor challenges derivation from them are checked, so `U` can be made
+    /// without commitments at all. Useful for fast iteration on circuit logic, since it avoids
+    /// MSM, but it's not a replacement for the full check
+    pub fn is_sat_relation_only<C>(
         &self,
-        U: &RelaxedPlonkInstance<C>,
-        W: &RelaxedPlonkWitness<F>,
+        U: &PlonkInstance<C>,
+        W: &PlonkWitness<F>,
+    ) -> Result<(), Error>
+    where
+        C: CurveAffine<ScalarExt = F>,
+    {
+        self.is_sat_relation_interruptible(U, W, &AtomicBool::new(false))
+    }
+
+    /// Same as [`PlonkStructure::is_sat_relation_only`], but stops evaluation of rows once
+    /// `interrupted` is set, the result is meaningless in this case
+    fn is_sat_relation_interruptible<C>(
+        &self,
+        U: &PlonkInstance<C>,
+        W: &PlonkWitness<F>,
+        interrupted: &AtomicBool,
+    ) -> Result<(), Error>
+    where
+        C: CurveAffine<ScalarExt = F>,
+    {
+        let total_row = 1 << self.k;
+
+        if let Some(index) = self
+            .gates
+            .iter()
+            .position(|gate| gate.as_constant().is_some_and(|value| value != F::ZERO))
+        {
+            return Err(Error::ConstantGate {
+                gate_index: self.gate_index(index),
+            });
+        }
+
+        let compressed = self.custom_gates_lookup_compressed.compressed();
+        // if all gates are trivially satisfied, there is nothing to evaluate
+        if compressed.as_constant() != Some(F::ZERO) {
+            self.residuals(U, W)
+                .map(|residual| (!interrupted.load(Ordering::Relaxed)).then_some(residual))
+                .while_some()
+                .try_fold(
+                    || 0,
+                    |mismatch_count, residual| {
+                        residual.map(|(_row, value)| mismatch_count + usize::from(value != F::ZERO))
+                    },
+                )
+                .try_reduce(|| 0, |lhs_count, rhs_count| Ok(lhs_count + rhs_count))
+                .map(|mismatch_count| {
+                    Some(Error::EvaluationMismatch {
+                        mismatch_count: NonZeroUsize::new(mismatch_count)?,
+                        total_row,
+                    })
+                })?
+                .err_or(())?;
+        }
+
+        if interrupted.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        if !self.is_sat_log_derivative(&W.W) {
+            return Err(Error::LogDerivativeNotSat);
+        }
+
+        Ok(())
+    }
+
+    /// View on rows of a single (not folded) witness & instance, on which gates are evaluated
+    fn row_view<'a>(
+        &'a self,
+        challenges: &'a [F],
+        W: &'a [Vec<F>],
+        instance: &'a [F],
+    ) -> RowView<'a, F> {
+        RowView::new(PlonkEvalDomain {
+            num_advice: self.num_advice_columns,
+            num_lookup: self.num_lookups(),
+            num_instance: self.num_instance_columns,
+            challenges,
+            selectors: &self.selectors,
+            fixed: &self.fixed_columns,
+            W1s: W,
+            W2s: &[],
+            instance1: instance,
+            instance2: &[],
+        })
+    }
+
+    /// Evaluates compressed gates (see [`PlonkStructure::is_sat_relation_only`]) on each row &
+    /// yields `(row, residual)`, the pair satisfies gates iff all residuals are zero
+    ///
+    /// Rows are evaluated in batches, so order of items is arbitrary. Evaluation error of a batch
+    /// is yielded instead of its rows
+    pub fn residuals<'a, C>(
+        &'a self,
+        U: &'a PlonkInstance<C>,
+        W: &'a PlonkWitness<F>,
+    ) -> impl ParallelIterator<Item = Result<(usize, F), EvalError>> + 'a
+    where
+        C: CurveAffine<ScalarExt = F>,
+    {
+        let data = self.row_view(&U.challenges, &W.W, &U.instance);
+        let evaluator = GraphEvaluator::new(self.custom_gates_lookup_compressed.compressed());
+
+        let total_row = 1 << self.k;
+        (0..total_row.div_ceil(EVAL_BATCH_SIZE))
+            .into_par_iter()
+            .flat_map_iter(move |batch| {
+                let start = batch * EVAL_BATCH_SIZE;
+                let rows = start..(start + EVAL_BATCH_SIZE).min(total_row);
+
+                match evaluator.evaluate_batch(&data, rows.clone()) {
+                    Ok(residuals) => rows.zip(residuals).map(Ok).collect::<Vec<_>>(),
+                    Err(err) => vec![Err(err)],
+                }
+            })
+    }
+
+    /// Evaluates each original gate separately on each row & reports all pairs of gate & row,
+    /// where the gate isn't satisfied
+    ///
+    /// Unlike [`PlonkStructure::is_sat_relation_only`], which evaluates only the compressed gate
+    /// & tells how many rows failed, this one tells which gates failed. It's much slower, so it's
+    /// supposed to be called after relation check fails, to debug a circuit
+    pub fn unsatisfied_gates<C>(
+        &self,
+        U: &PlonkInstance<C>,
+        W: &PlonkWitness<F>,
+    ) -> Result<Vec<GateFailure>, Error>
+    where
+        C: CurveAffine<ScalarExt = F>,
+    {
+        let total_row = 1 << self.k;
+        let data = self.row_view(&U.challenges, &W.W, &U.instance);
+
+        let mut failures = vec![];
+        for (index, gate) in self.gates.iter().enumerate() {
+            let residuals = match gate.as_constant() {
+                Some(value) => vec![value; total_row],
+                None => evaluate_rows_batched(&GraphEvaluator::new(gate), &data, total_row)?,
+            };
+
+            let mut columns = BTreeSet::new();
+            gate.poly_set(&mut columns);
+            let columns = columns.into_iter().collect::<Vec<_>>();
+
+            let gate_index = self.gate_index(index);
+            let gate_name = self
+                .gate_names
+                .get(index)
+                .cloned()
+                .unwrap_or_else(|| format!("gate {gate_index}"));
+
+            failures.extend(
+                residuals
+                    .iter()
+                    .positions(|residual| *residual != F::ZERO)
+                    .map(|row| GateFailure {
+                        gate_index,
+                        gate_name: gate_name.clone(),
+                        row,
+                        columns: columns.clone(),
+                    }),
+            );
+        }
+
+        Ok(failures)
+    }
+
+    /// Computes expected error vector `E` of relaxed pair, i.e. evaluation of homogeneous
+    /// compressed gates on each row
+    ///
+    /// For a satisfying pair it's equal to [`RelaxedPlonkWitness::E`], so it can be used to find
+    /// out whether failure is in error term or in commitments
+    pub fn compute_E<C>(
+        &self,
+        U: &RelaxedPlonkInstance<C>,
+        W: &RelaxedPlonkWitness<F>,
+    ) -> Result<Box<[F]>, Error>
+    where
+        C: CurveAffine<ScalarExt = F>,
+    {
+        let challenges = concat_vec!(&U.challenges, &[U.u]);
+        let data = self.row_view(&challenges, &W.W, &U.instance);
+
+        let homogeneous = self.custom_gates_lookup_compressed.homogeneous();
+        if let Some(value) = homogeneous.as_constant() {
+            return Ok(vec![value; 1 << self.k].into_boxed_slice());
+        }
+
+        let evaluator = GraphEvaluator::new(homogeneous);
+        Ok(evaluate_rows_batched(&evaluator, &data, 1 << self.k)?.into_boxed_slice())
+    }
+
+    /// Same as [`PlonkStructure::compute_E`], but gates are evaluated column-wise, see
+    /// [`eval::evaluate_columnwise`]
+    pub fn compute_E_columnwise<C>(
+        &self,
+        U: &RelaxedPlonkInstance<C>,
+        W: &RelaxedPlonkWitness<F>,
+    ) -> Result<Box<[F]>, Error>
+    where
+        C: CurveAffine<ScalarExt = F>,
+    {
+        let challenges = concat_vec!(&U.challenges, &[U.u]);
+        let data = self.row_view(&challenges, &W.W, &U.instance);
+
+        Ok(
+            eval::evaluate_columnwise(self.custom_gates_lookup_compressed.homogeneous(), &data)?
+                .into_boxed_slice(),
+        )
+    }
+
+    /// Checks, that homogeneous version of compressed gates with `u = 1` is equal to the
+    /// compressed gates themselves
+    ///
+    /// Both are evaluated on all rows of a random witness with random challenges, so a mismatch
+    /// means a bug of homogenization, that would be hidden in [`PlonkStructure::is_sat_relaxed`]
+    pub fn assert_homogeneous_consistent(&self) -> Result<(), Error> {
+        let total_row = 1 << self.k;
+        let challenges = iter::repeat_with(|| F::random(OsRng))
+            .take(self.num_challenges)
+            .collect::<Vec<_>>();
+        let W = self
+            .round_sizes
+            .iter()
+            .map(|size| iter::repeat_with(|| F::random(OsRng)).take(*size).collect())
+            .collect::<Vec<Vec<F>>>();
+        let instance = iter::repeat_with(|| F::random(OsRng))
+            .take(self.num_io)
+            .collect::<Vec<_>>();
+
+        let evaluate = |expr: &Expression<F>, challenges: &[F]| -> Result<Vec<F>, EvalError> {
+            if let Some(value) = expr.as_constant() {
+                return Ok(vec![value; total_row]);
+            }
+
+            let data = self.row_view(challenges, &W, &instance);
+            evaluate_rows_batched(&GraphEvaluator::new(expr), &data, total_row)
+        };
+
+        let compressed = evaluate(
+            self.custom_gates_lookup_compressed.compressed(),
+            &challenges,
+        )?;
+        let homogeneous = evaluate(
+            self.custom_gates_lookup_compressed.homogeneous(),
+            &concat_vec!(&challenges, &[F::ONE]),
+        )?;
+
+        compressed
+            .iter()
+            .zip_eq(homogeneous.iter())
+            .position(|(lhs, rhs)| lhs != rhs)
+            .map(|row| Error::HomogenizationMismatch { row })
+            .err_or(())
+    }
+
+    pub fn is_sat_relaxed<C>(
+        &self,
+        ck: &CommitmentKey<C>,
+        U: &RelaxedPlonkInstance<C>,
+        W: &RelaxedPlonkWitness<F>,
+    ) -> Result<(), Error>
+    where
+        C: CurveAffine<ScalarExt = F>,
+    {
+        let failing_rows_count = self
+            .compute_E(U, W)?
+            .par_iter()
+            .zip_eq(W.E.par_iter())
+            .enumerate()
+            .filter(|(row, (eval_of_row, expected))| {
+                let is_missed = **eval_of_row != *expected;
+                if is_missed {
+                    warn!("row {row} invalid: expected {expected:?}, but {eval_of_row:?}");
+                }
+                is_missed
+            })
+            .count();
+
+        let (W_commitments, E_commitment) = W.commit(ck)?;
+
+        self.relaxed_verdict(ck, U, W, failing_rows_count, &W_commitments, &E_commitment)
+    }
+
+    /// Same as [`PlonkStructure::is_sat_relaxed`], but also returns timing of its phases
+    ///
+    /// Unlike the plain version, all phases are always run, so timings are complete even for
+    /// unsatisfied pair (except evaluation error)
+    pub fn is_sat_relaxed_profiled<C>(
+        &self,
+        ck: &CommitmentKey<C>,
+        U: &RelaxedPlonkInstance<C>,
+        W: &RelaxedPlonkWitness<F>,
+    ) -> (Result<(), Error>, SatProfile)
+    where
+        C: CurveAffine<ScalarExt = F>,
+    {
+        let mut profile = SatProfile::default();
+
+        let timer = Instant::now();
+        let E = self.compute_E(U, W);
+        profile.eval_time = timer.elapsed();
+
+        let E = match E {
+            Ok(E) => E,
+            Err(err) => return (Err(err), profile),
+        };
+        profile.failing_rows = E
+            .par_iter()
+            .zip_eq(W.E.par_iter())
+            .enumerate()
+            .filter_map(|(row, (eval_of_row, expected))| (*eval_of_row != expected).then_some(row))
+            .collect();
+
+        let timer = Instant::now();
+        let W_commitments =
+            W.W.par_iter()
+                .zip_eq(W.W_blinds.par_iter())
+                .map(|(Wi, blind)| ck.commit_hiding(Wi, blind))
+                .collect::<Result<Vec<_>, _>>();
+        profile.w_commit_time = timer.elapsed();
+
+        let timer = Instant::now();
+        let E_commitment = W.E.commit(ck);
+        profile.e_commit_time = timer.elapsed();
+
+        let verdict = W_commitments
+            .and_then(|W_commitments| Ok((W_commitments, E_commitment?)))
+            .map_err(Error::from)
+            .and_then(|(W_commitments, E_commitment)| {
+                self.relaxed_verdict(
+                    ck,
+                    U,
+                    W,
+                    profile.failing_rows.len(),
+                    &W_commitments,
+                    &E_commitment,
+                )
+            });
+
+        (verdict, profile)
+    }
+
+    /// Checks relaxed pair by already evaluated & committed parts, shared by
+    /// [`PlonkStructure::is_sat_relaxed`] & [`PlonkStructure::is_sat_relaxed_profiled`]
+    fn relaxed_verdict<C>(
+        &self,
+        ck: &CommitmentKey<C>,
+        U: &RelaxedPlonkInstance<C>,
+        W: &RelaxedPlonkWitness<F>,
+        failing_rows_count: usize,
+        W_commitments: &[C],
+        E_commitment: &C,
+    ) -> Result<(), Error>
+    where
+        C: CurveAffine<ScalarExt = F>,
+    {
+        if let Some(mismatch_count) = NonZeroUsize::new(failing_rows_count) {
+            return Err(Error::EvaluationMismatch {
+                mismatch_count,
+                total_row: 1 << self.k,
+            });
+        }
+
+        if !self.is_sat_log_derivative(&W.W) {
+            return Err(Error::LogDerivativeNotSat);
+        }
+
+        U.W_commitments
+            .iter()
+            .zip_eq(W_commitments.iter())
+            .filter_map(|(expected, actual)| expected.ne(actual).then_some(()))
+            .count_to_non_zero()
+            .map(|mismatch_count| Error::CommitmentMismatch { mismatch_count })
+            .err_or(())?;
+
+        if E_commitment.ne(&U.E_commitment) {
+            return Err(Error::ECommitmentMismatch);
+        }
+
+        check_instance_commitment(ck, &U.instance, U.instance_commitment.as_ref())
+    }
+
+    // permutation check for folding instance-witness pair
+    pub fn is_sat_perm<C>(
+        &self,
+        U: &RelaxedPlonkInstance<C>,
+        W: &RelaxedPlonkWitness<F>,
     ) -> Result<(), Error>
     where
         C: CurveAffine<ScalarExt = F>,
@@ -550,10 +1456,158 @@ impl<F: PrimeField> PlonkStructure<F> {
         }
     }
 
+    /// Checks consistency of structure received from untrusted source, e.g. deserialized one
+    ///
+    /// Lengths of columns & prover rounds are checked against `k`, permutation indices against
+    /// the table & count of challenges against gates, the same way as they're derived by
+    /// [`table::ConstraintSystemMetainfo::build`]
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.k >= usize::BITS as usize {
+            return Err(Error::MalformedStructure {
+                reason: "k is too large",
+            });
+        }
+        let nrow = 1 << self.k;
+
+        if self.num_instance_columns > 1 {
+            return Err(Error::MalformedStructure {
+                reason: "only single instance column is supported",
+            });
+        }
+
+        self.selectors
+            .iter()
+            .try_for_each(|column| check_len("selector column", nrow, column.len()))?;
+        self.fixed_columns
+            .iter()
+            .try_for_each(|column| check_len("fixed column", nrow, column.len()))?;
+
+        let table_len = nrow
+            .checked_mul(self.num_advice_columns)
+            .and_then(|advice_len| advice_len.checked_add(self.num_io))
+            .ok_or(Error::MalformedStructure {
+                reason: "table size overflows",
+            })?;
+        if self
+            .permutation_matrix
+            .iter()
+            .any(|(row, column, _)| *row >= table_len || *column >= table_len)
+        {
+            return Err(Error::MalformedStructure {
+                reason: "permutation index is out of table",
+            });
+        }
+
+        let (num_lookups, has_vector_lookup) = lookup_shape(self.lookup_arguments.as_ref());
+        let round_sizes = table::round_sizes(
+            self.k,
+            self.num_advice_columns,
+            num_lookups,
+            has_vector_lookup,
+        );
+        if self.round_sizes != round_sizes {
+            return Err(Error::MalformedStructure {
+                reason: "round sizes don't match columns & lookups",
+            });
+        }
+
+        check_len(
+            "challenges",
+            self.custom_gates_lookup_compressed
+                .compressed()
+                .num_challenges(),
+            self.num_challenges,
+        )
+    }
+
     pub fn get_degree_for_folding(&self) -> usize {
         self.custom_gates_lookup_compressed.grouped().len()
     }
 
+    /// Digest of structure to bind transcripts to it, absorbed as part of `pp_digest` by
+    /// [`crate::nifs::vanilla::VanillaFS::setup_params`]
+    ///
+    /// Covers everything, that is serialized: `k`, column counts, selectors & fixed columns,
+    /// gates, copy constraints & lookups
+    pub fn digest<C: CurveAffine>(&self) -> Result<C, io::Error>
+    where
+        F: Serialize,
+    {
+        digest::DefaultHasher::digest_to_curve(self)
+    }
+
+    /// Checks that both structures describe the same circuit
+    ///
+    /// Compares only data, that comes from circuit itself: size, columns, gates, copy constraints
+    /// & lookups. Everything derived from them (compressed gates, rounds, challenges) is ignored
+    pub fn same_circuit(&self, other: &Self) -> bool {
+        self.k == other.k
+            && self.num_io == other.num_io
+            && self.num_advice_columns == other.num_advice_columns
+            && self.num_instance_columns == other.num_instance_columns
+            && self.selectors == other.selectors
+            && self.fixed_columns == other.fixed_columns
+            && self.gates == other.gates
+            && self.permutation_matrix == other.permutation_matrix
+            && self.lookup_arguments == other.lookup_arguments
+    }
+
+    /// Number of cross terms `[T_1, ..., T_{d-1}]` produced while folding instances of this
+    /// structure, where `d` is degree of homogeneous version of compressed gates
+    pub fn num_cross_terms(&self) -> usize {
+        self.get_degree_for_folding().saturating_sub(1)
+    }
+
+    /// Number of original custom gates & lookup expressions, combined into one compressed gate
+    pub fn num_gates(&self) -> usize {
+        self.gates.len()
+    }
+
+    /// Exponent of combination challenge `y` applied to each original gate, in order of
+    /// `create_gate` with lookups after custom gates
+    ///
+    /// Sorted [`PlonkStructure::gates`] are combined as `p_1 * y^{n-1} + ... + p_n`, so powers
+    /// are `[n - 1, ..., 0]` permuted back into `create_gate` order. A single gate isn't combined
+    /// at all & its power is zero
+    pub fn gate_combination_powers(&self) -> Vec<u32> {
+        let num_gates = self.num_gates();
+        let mut powers = vec![0; num_gates];
+        for index in 0..num_gates {
+            powers[self.gate_index(index)] = (num_gates - 1 - index) as u32;
+        }
+        powers
+    }
+
+    /// Index in order of `create_gate` of `index`-th of [`PlonkStructure::gates`]
+    ///
+    /// Structure restored from bytes has no original order, so its own order is used
+    fn gate_index(&self, index: usize) -> usize {
+        self.gate_indices.get(index).copied().unwrap_or(index)
+    }
+
+    /// Collects [`CircuitSummary`] of this structure
+    ///
+    /// Gate degree & monomials are counted on homogeneous compressed gates, i.e. on the
+    /// expression, that is actually evaluated while folding
+    pub fn summary(&self) -> CircuitSummary {
+        let homogeneous = self.custom_gates_lookup_compressed.homogeneous();
+
+        CircuitSummary {
+            k: self.k,
+            num_rows: 1 << self.k,
+            num_fixed_columns: self.fixed_columns.len(),
+            num_advice_columns: self.num_advice_columns,
+            gate_degree: homogeneous.degree(&QueryIndexContext::from(self)),
+            num_monomials: homogeneous.num_monomials(),
+            num_cross_terms: self.num_cross_terms(),
+        }
+    }
+
+    /// Length of each cross term vector, equal to number of rows `2^k`
+    pub fn cross_term_len(&self) -> usize {
+        1 << self.k
+    }
+
     pub fn dry_run_sps_protocol<C: CurveAffine<ScalarExt = F>>(&self) -> PlonkTrace<C> {
         PlonkTrace {
             u: PlonkInstance::new(self.num_io, self.num_challenges, self.round_sizes.len()),
@@ -561,6 +1615,28 @@ impl<F: PrimeField> PlonkStructure<F> {
         }
     }
 
+    /// Builds [`PlonkInstance`] of this structure from its parts, e.g. received from other
+    /// implementation
+    ///
+    /// Count of public inputs, round commitments & challenges are checked against the structure,
+    /// as well as validity of commitments, see [`StructureBound::validate_for`]
+    pub fn make_instance<C: CurveAffine<ScalarExt = F>>(
+        &self,
+        W_commitments: Vec<C>,
+        instance: Vec<F>,
+        challenges: Vec<F>,
+    ) -> Result<PlonkInstance<C>, Error> {
+        let U = PlonkInstance {
+            W_commitments,
+            instance,
+            challenges,
+            instance_commitment: None,
+        };
+        U.validate_for(self)?;
+
+        Ok(U)
+    }
+
     /// run special soundness protocol to generate witnesses and challenges
     /// depending on whether we have multiple gates, lookup arguments and whether
     /// we have vector lookup, we will call different sub-sps protocol
@@ -571,13 +1647,29 @@ impl<F: PrimeField> PlonkStructure<F> {
         advice: &[Vec<F>],
         ro_nark: &mut RO,
         num_challenges: usize,
+    ) -> Result<(PlonkInstance<C>, PlonkWitness<F>), SpsError> {
+        self.run_sps_protocol_hiding(ck, instance, advice, ro_nark, num_challenges, &[])
+    }
+
+    /// Same as [`PlonkStructure::run_sps_protocol`], but commits each round witness with
+    /// corresponding blinding factor from `blinds`, see [`CommitmentKey::commit_hiding`]
+    ///
+    /// Missing blinding factors are treated as zero
+    pub fn run_sps_protocol_hiding<C: CurveAffine<ScalarExt = F>, RO: ROTrait<C::Base>>(
+        &self,
+        ck: &CommitmentKey<C>,
+        instance: &[F],
+        advice: &[Vec<F>],
+        ro_nark: &mut RO,
+        num_challenges: usize,
+        blinds: &[F],
     ) -> Result<(PlonkInstance<C>, PlonkWitness<F>), SpsError> {
         debug!("run sps protocol with {num_challenges} challenges");
         match num_challenges {
-            0 => self.run_sps_protocol_0(instance, advice, ck),
-            1 => self.run_sps_protocol_1(instance, advice, ck, ro_nark),
-            2 => self.run_sps_protocol_2(instance, advice, ck, ro_nark),
-            3 => self.run_sps_protocol_3(instance, advice, ck, ro_nark),
+            0 => self.run_sps_protocol_0(instance, advice, ck, blinds),
+            1 => self.run_sps_protocol_1(instance, advice, ck, ro_nark, blinds),
+            2 => self.run_sps_protocol_2(instance, advice, ck, ro_nark, blinds),
+            3 => self.run_sps_protocol_3(instance, advice, ck, ro_nark, blinds),
             challenges_count => Err(SpsError::UnsupportedChallengesCount { challenges_count }),
         }
     }
@@ -589,10 +1681,11 @@ impl<F: PrimeField> PlonkStructure<F> {
         instance: &[F],
         advice: &[Vec<F>],
         ck: &CommitmentKey<C>,
+        blinds: &[F],
     ) -> Result<(PlonkInstance<C>, PlonkWitness<F>), SpsError> {
         let W1 = concatenate_with_padding(advice, 1 << self.k);
         let C1 = ck
-            .commit(&W1)
+            .commit_hiding(&W1, &blind_of_round(blinds, 0))
             .map_err(|err| SpsError::WrongCommitmentSize {
                 annotation: "W1",
                 err,
@@ -603,8 +1696,12 @@ impl<F: PrimeField> PlonkStructure<F> {
                 W_commitments: vec![C1],
                 instance: instance.to_vec(),
                 challenges: vec![],
+                instance_commitment: None,
+            },
+            PlonkWitness {
+                W: vec![W1],
+                blinds: vec![blind_of_round(blinds, 0)],
             },
-            PlonkWitness { W: vec![W1] },
         ))
     }
 
@@ -619,8 +1716,10 @@ impl<F: PrimeField> PlonkStructure<F> {
         advice: &[Vec<F>],
         ck: &CommitmentKey<C>,
         ro_nark: &mut RO,
+        blinds: &[F],
     ) -> Result<(PlonkInstance<C>, PlonkWitness<F>), SpsError> {
-        let (mut plonk_instance, plonk_witness) = self.run_sps_protocol_0(instance, advice, ck)?;
+        let (mut plonk_instance, plonk_witness) =
+            self.run_sps_protocol_0(instance, advice, ck, blinds)?;
 
         ro_nark
             .absorb_field_iter(instance.iter().map(|inst| fe_to_fe(inst).unwrap()))
@@ -644,6 +1743,7 @@ impl<F: PrimeField> PlonkStructure<F> {
         advice: &[Vec<F>],
         ck: &CommitmentKey<C>,
         ro_nark: &mut RO,
+        blinds: &[F],
     ) -> Result<(PlonkInstance<C>, PlonkWitness<F>), SpsError> {
         let k_power_of_2 = 1 << self.k;
 
@@ -665,7 +1765,7 @@ impl<F: PrimeField> PlonkStructure<F> {
         .concat();
 
         let C1 = ck
-            .commit(&W1)
+            .commit_hiding(&W1, &blind_of_round(blinds, 0))
             .map_err(|err| SpsError::WrongCommitmentSize {
                 annotation: "W1",
                 err,
@@ -685,7 +1785,7 @@ impl<F: PrimeField> PlonkStructure<F> {
         );
 
         let C2 = ck
-            .commit(&W2)
+            .commit_hiding(&W2, &blind_of_round(blinds, 1))
             .map_err(|err| SpsError::WrongCommitmentSize {
                 annotation: "W2",
                 err,
@@ -697,8 +1797,12 @@ impl<F: PrimeField> PlonkStructure<F> {
                 W_commitments: vec![C1, C2],
                 instance: instance.to_vec(),
                 challenges: vec![r1, r2],
+                instance_commitment: None,
+            },
+            PlonkWitness {
+                W: vec![W1, W2],
+                blinds: (0..2).map(|round| blind_of_round(blinds, round)).collect(),
             },
-            PlonkWitness { W: vec![W1, W2] },
         ))
     }
 
@@ -712,6 +1816,7 @@ impl<F: PrimeField> PlonkStructure<F> {
         advice: &[Vec<F>],
         ck: &CommitmentKey<C>,
         ro_nark: &mut RO,
+        blinds: &[F],
     ) -> Result<(PlonkInstance<C>, PlonkWitness<F>), SpsError> {
         ro_nark.absorb_field_iter(instance.iter().map(|inst| fe_to_fe(inst).unwrap()));
 
@@ -720,7 +1825,7 @@ impl<F: PrimeField> PlonkStructure<F> {
         // round 1
         let W1 = concatenate_with_padding(advice, k_power_of_2);
         let C1 = ck
-            .commit(&W1)
+            .commit_hiding(&W1, &blind_of_round(blinds, 0))
             .map_err(|err| SpsError::WrongCommitmentSize {
                 annotation: "W1",
                 err,
@@ -740,7 +1845,7 @@ impl<F: PrimeField> PlonkStructure<F> {
             k_power_of_2,
         );
         let C2 = ck
-            .commit(&W2)
+            .commit_hiding(&W2, &blind_of_round(blinds, 1))
             .map_err(|err| SpsError::WrongCommitmentSize {
                 annotation: "W2",
                 err,
@@ -756,7 +1861,7 @@ impl<F: PrimeField> PlonkStructure<F> {
         );
 
         let C3 = ck
-            .commit(&W3)
+            .commit_hiding(&W3, &blind_of_round(blinds, 2))
             .map_err(|err| SpsError::WrongCommitmentSize {
                 annotation: "W3",
                 err,
@@ -768,42 +1873,288 @@ impl<F: PrimeField> PlonkStructure<F> {
                 W_commitments: vec![C1, C2, C3],
                 instance: instance.to_vec(),
                 challenges: vec![r1, r2, r3],
+                instance_commitment: None,
             },
             PlonkWitness {
                 W: vec![W1, W2, W3],
+                blinds: (0..3).map(|round| blind_of_round(blinds, round)).collect(),
             },
         ))
     }
 }
 
-impl<C: CurveAffine> PlonkInstance<C> {
-    pub fn new(num_io: usize, num_challenges: usize, num_witness: usize) -> Self {
-        Self {
-            W_commitments: vec![CommitmentKey::<C>::default_value(); num_witness],
-            instance: vec![C::ScalarExt::ZERO; num_io],
-            challenges: vec![C::ScalarExt::ZERO; num_challenges],
-        }
+/// Blinding factor of `round` commitment, zero if missing
+fn blind_of_round<F: PrimeField>(blinds: &[F], round: usize) -> F {
+    blinds.get(round).copied().unwrap_or(F::ZERO)
+}
+
+/// Checks that `point` received from untrusted source is a point of curve
+///
+/// All curves of supported cycles have prime order, so any point on curve is also in the prime
+/// order subgroup
+fn validate_commitment<C: CurveAffine>(name: &'static str, point: &C) -> Result<(), Error> {
+    if bool::from(point.is_on_curve()) {
+        Ok(())
+    } else {
+        Err(Error::InvalidCommitment { name })
     }
+}
 
-    pub fn to_relax(&self) -> RelaxedPlonkInstance<C> {
+/// Minimal length of vector, that is folded in parallel by [`fold_values`], rayon overhead
+/// exceeds the work for shorter ones
+const PARALLEL_FOLD_THRESHOLD: usize = 64;
+
+/// Folds vectors element-wise: `lhs + r * rhs`
+fn fold_values<F: PrimeField>(lhs: &[F], rhs: &[F], r: &F, parallel: bool) -> Vec<F> {
+    if parallel {
+        lhs.par_iter()
+            .zip_eq(rhs)
+            .map(|(a, b)| *a + *r * b)
+            .collect()
+    } else {
+        lhs.iter().zip_eq(rhs).map(|(a, b)| *a + *r * b).collect()
+    }
+}
+
+/// Same as [`fold_values`], but folds into `lhs` in place: `lhs += r * rhs`
+fn fold_values_assign<F: PrimeField>(lhs: &mut [F], rhs: &[F], r: &F, parallel: bool) {
+    if parallel {
+        lhs.par_iter_mut()
+            .zip_eq(rhs)
+            .for_each(|(a, b)| *a += *r * b);
+    } else {
+        lhs.iter_mut().zip_eq(rhs).for_each(|(a, b)| *a += *r * b);
+    }
+}
+
+/// Count of rows evaluated by one [`GraphEvaluator::evaluate_batch`] call
+const EVAL_BATCH_SIZE: usize = 1 << 10;
+
+/// Evaluates rows `0..total_row` in parallel, each task evaluates [`EVAL_BATCH_SIZE`] rows
+fn evaluate_rows_batched<F: PrimeField>(
+    evaluator: &GraphEvaluator<F>,
+    data: &(impl GetDataForEval<F> + Sync),
+    total_row: usize,
+) -> Result<Vec<F>, EvalError> {
+    Ok((0..total_row.div_ceil(EVAL_BATCH_SIZE))
+        .into_par_iter()
+        .map(|batch| {
+            let start = batch * EVAL_BATCH_SIZE;
+            evaluator.evaluate_batch(data, start..(start + EVAL_BATCH_SIZE).min(total_row))
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .concat())
+}
+
+/// Checks `W_commitments` of `U` against commitments of `W` columns with their blinds, the
+/// error contains the count of mismatched commitments
+fn check_witness_commitments<C: CurveAffine>(
+    ck: &CommitmentKey<C>,
+    U: &PlonkInstance<C>,
+    W: &PlonkWitness<C::ScalarExt>,
+) -> Result<(), Error> {
+    U.W_commitments
+        .iter()
+        .zip_eq(W.W.iter().zip_eq(W.blinds.iter()))
+        .filter_map(|(Ci, (Wi, blind))| ck.commit_hiding(Wi, blind).unwrap().ne(Ci).then_some(()))
+        .count_to_non_zero()
+        .map(|mismatch_count| Error::CommitmentMismatch { mismatch_count })
+        .err_or(())
+}
+
+/// Checks `instance_commitment`, if it's present, against `instance` values
+fn check_instance_commitment<C: CurveAffine>(
+    ck: &CommitmentKey<C>,
+    instance: &[C::ScalarExt],
+    instance_commitment: Option<&C>,
+) -> Result<(), Error> {
+    match instance_commitment {
+        Some(expected) if ck.commit(instance).ok().as_ref() != Some(expected) => {
+            Err(Error::InstanceCommitmentMismatch)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Artifact, which shape is defined by [`PlonkStructure`], e.g. instance or witness
+///
+/// Data received from untrusted source must be checked with [`StructureBound::validate_for`]
+/// before use with `S`, otherwise malformed data panics deep inside folding, see
+/// [`crate::artifact::decode_for`]
+pub trait StructureBound<F: PrimeField> {
+    /// Checks lengths of `self` against `S` & validity of commitments, if any
+    fn validate_for(&self, S: &PlonkStructure<F>) -> Result<(), Error>;
+}
+
+fn check_len(field: &'static str, expected: usize, actual: usize) -> Result<(), Error> {
+    if expected != actual {
+        return Err(Error::StructureMismatch {
+            field,
+            expected,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+fn check_rounds_len<F>(S: &PlonkStructure<F>, W: &[Vec<F>], blinds: &[F]) -> Result<(), Error>
+where
+    F: PrimeField,
+{
+    check_len("W", S.round_sizes.len(), W.len())?;
+    check_len("W blinds", S.round_sizes.len(), blinds.len())?;
+    S.round_sizes
+        .iter()
+        .zip_eq(W)
+        .try_for_each(|(size, W)| check_len("W round", *size, W.len()))
+}
+
+fn check_instance_len<C: CurveAffine>(
+    S: &PlonkStructure<C::ScalarExt>,
+    W_commitments: &[C],
+    instance: &[C::ScalarExt],
+    challenges: &[C::ScalarExt],
+) -> Result<(), Error> {
+    if instance.len() != S.num_io {
+        return Err(Error::NumIoMismatch {
+            expected: S.num_io,
+            actual: instance.len(),
+        });
+    }
+    check_len("W_commitments", S.round_sizes.len(), W_commitments.len())?;
+    check_len("challenges", S.num_challenges, challenges.len())
+}
+
+impl<C: CurveAffine> StructureBound<C::ScalarExt> for PlonkInstance<C> {
+    fn validate_for(&self, S: &PlonkStructure<C::ScalarExt>) -> Result<(), Error> {
+        check_instance_len(S, &self.W_commitments, &self.instance, &self.challenges)?;
+        self.validate()
+    }
+}
+
+impl<C: CurveAffine> StructureBound<C::ScalarExt> for RelaxedPlonkInstance<C> {
+    fn validate_for(&self, S: &PlonkStructure<C::ScalarExt>) -> Result<(), Error> {
+        check_instance_len(S, &self.W_commitments, &self.instance, &self.challenges)?;
+        self.validate()
+    }
+}
+
+impl<F: PrimeField> StructureBound<F> for PlonkWitness<F> {
+    fn validate_for(&self, S: &PlonkStructure<F>) -> Result<(), Error> {
+        check_rounds_len(S, &self.W, &self.blinds)
+    }
+}
+
+impl<F: PrimeField> StructureBound<F> for RelaxedPlonkWitness<F> {
+    fn validate_for(&self, S: &PlonkStructure<F>) -> Result<(), Error> {
+        check_rounds_len(S, &self.W, &self.W_blinds)?;
+        check_len("E", 1 << S.k, self.E.len())
+    }
+}
+
+impl<C: CurveAffine> StructureBound<C::ScalarExt> for PlonkTrace<C> {
+    fn validate_for(&self, S: &PlonkStructure<C::ScalarExt>) -> Result<(), Error> {
+        self.u.validate_for(S)?;
+        self.w.validate_for(S)
+    }
+}
+
+impl<C: CurveAffine> StructureBound<C::ScalarExt> for RelaxedPlonkTrace<C> {
+    fn validate_for(&self, S: &PlonkStructure<C::ScalarExt>) -> Result<(), Error> {
+        self.U.validate_for(S)?;
+        self.W.validate_for(S)
+    }
+}
+
+impl<C: CurveAffine> PlonkInstance<C> {
+    /// Validates commitments of instance, expected to be called before folding of instance
+    /// received from untrusted source
+    ///
+    /// Called on deserialization and by [`StructureBound::validate_for`] together with shape
+    /// checks, so decoded instances are always validated
+    pub fn validate(&self) -> Result<(), Error> {
+        self.W_commitments
+            .iter()
+            .try_for_each(|W| validate_commitment("W", W))?;
+        self.instance_commitment
+            .iter()
+            .try_for_each(|X| validate_commitment("instance", X))
+    }
+
+    pub fn new(num_io: usize, num_challenges: usize, num_witness: usize) -> Self {
+        Self {
+            W_commitments: vec![CommitmentKey::<C>::identity_commitment(); num_witness],
+            instance: vec![C::ScalarExt::ZERO; num_io],
+            challenges: vec![C::ScalarExt::ZERO; num_challenges],
+            instance_commitment: None,
+        }
+    }
+
+    /// Commits `instance` values with `ck`, so the commitment is absorbed into RO together with
+    /// values and folded together with them
+    ///
+    /// Both sides of folding must agree on the use of instance commitment, otherwise folding
+    /// returns [`Error::StructureMismatch`]
+    pub fn commit_instance(&mut self, ck: &CommitmentKey<C>) -> Result<(), commitment::Error> {
+        self.instance_commitment = Some(ck.commit(&self.instance)?);
+        Ok(())
+    }
+
+    pub fn instance_commitment(&self) -> Option<&C> {
+        self.instance_commitment.as_ref()
+    }
+
+    pub fn to_relax(&self) -> RelaxedPlonkInstance<C> {
         RelaxedPlonkInstance {
             W_commitments: self.W_commitments.clone(),
             E_commitment: C::identity(),
             instance: self.instance.clone(),
             challenges: self.challenges.clone(),
             u: C::ScalarExt::ONE,
+            instance_commitment: self.instance_commitment,
         }
     }
 }
 
 impl<C: CurveAffine> RelaxedPlonkInstance<C> {
+    pub fn instance_commitment(&self) -> Option<&C> {
+        self.instance_commitment.as_ref()
+    }
+
+    /// Same as [`PlonkInstance::validate`], also checks commitment of `E`
+    pub fn validate(&self) -> Result<(), Error> {
+        self.W_commitments
+            .iter()
+            .try_for_each(|W| validate_commitment("W", W))?;
+        self.instance_commitment
+            .iter()
+            .try_for_each(|X| validate_commitment("instance", X))?;
+        validate_commitment("E", &self.E_commitment)
+    }
+
     pub fn new(num_io: usize, num_challenges: usize, num_witness: usize) -> Self {
         Self {
-            W_commitments: vec![CommitmentKey::<C>::default_value(); num_witness],
-            E_commitment: CommitmentKey::<C>::default_value(),
+            W_commitments: vec![CommitmentKey::<C>::identity_commitment(); num_witness],
+            E_commitment: CommitmentKey::<C>::identity_commitment(),
             instance: vec![C::ScalarExt::ZERO; num_io],
             challenges: vec![C::ScalarExt::ZERO; num_challenges],
             u: C::ScalarExt::ZERO,
+            instance_commitment: None,
+        }
+    }
+
+    /// Same as [`RelaxedPlonkInstance::new`], but commitments of zero `W` & `E` are
+    /// [`CommitmentKey::empty_commitment`] of `ck`
+    pub fn new_with_ck(
+        ck: &CommitmentKey<C>,
+        num_io: usize,
+        num_challenges: usize,
+        num_witness: usize,
+    ) -> Self {
+        Self {
+            W_commitments: vec![ck.empty_commitment(); num_witness],
+            E_commitment: ck.empty_commitment(),
+            ..Self::new(num_io, num_challenges, num_witness)
         }
     }
 
@@ -822,52 +2173,176 @@ impl<C: CurveAffine> RelaxedPlonkInstance<C> {
     /// # Returns
     /// The folded `RelaxedPlonkInstance` after combining the instances and commitments.
     /// for detail of how fold works, please refer to: [nifs](https://hackmd.io/d7syox5tTeaxkepc9nLvHw?view#31-NIFS)
+    ///
+    /// # Errors
+    /// [`Error::NumIoMismatch`] if instances have different count of public inputs, i.e. they
+    /// aren't produced by the same circuit
     #[instrument(name = "fold_plonk_instance", skip_all)]
-    pub fn fold(&self, U2: &PlonkInstance<C>, cross_term_commits: &[C], r: &C::ScalarExt) -> Self {
-        let W_commitments = self
-            .W_commitments
-            .iter()
-            .zip(U2.W_commitments.clone())
+    pub fn fold(
+        &self,
+        U2: &PlonkInstance<C>,
+        cross_term_commits: &[C],
+        r: &C::ScalarExt,
+    ) -> Result<Self, Error> {
+        Ok(self.fold_audited(U2, cross_term_commits, r)?.0)
+    }
+
+    /// Same as [`RelaxedPlonkInstance::fold`], but also returns powers `[r^1, r^2, ...]` applied
+    /// to each of `cross_term_commits` while folding `E_commitment`
+    ///
+    /// Returned powers can be recorded & replayed with [`RelaxedPlonkInstance::fold_E_commitment`]
+    pub fn fold_audited(
+        &self,
+        U2: &PlonkInstance<C>,
+        cross_term_commits: &[C],
+        r: &C::ScalarExt,
+    ) -> Result<(Self, Vec<C::ScalarExt>), Error> {
+        let mut folded = self.clone();
+        let powers_of_r = folded.fold_assign_audited(U2, cross_term_commits, r)?;
+        Ok((folded, powers_of_r))
+    }
+
+    /// Same as [`RelaxedPlonkInstance::fold`], but updates `self` in place, reusing its buffers
+    ///
+    /// On [`Error::NumIoMismatch`] `self` is left untouched
+    #[instrument(name = "fold_plonk_instance_assign", skip_all)]
+    pub fn fold_assign(
+        &mut self,
+        U2: &PlonkInstance<C>,
+        cross_term_commits: &[C],
+        r: &C::ScalarExt,
+    ) -> Result<(), Error> {
+        self.fold_assign_audited(U2, cross_term_commits, r)
+            .map(|_| ())
+    }
+
+    fn fold_assign_audited(
+        &mut self,
+        U2: &PlonkInstance<C>,
+        cross_term_commits: &[C],
+        r: &C::ScalarExt,
+    ) -> Result<Vec<C::ScalarExt>, Error> {
+        if self.instance.len() != U2.instance.len() {
+            return Err(Error::NumIoMismatch {
+                expected: self.instance.len(),
+                actual: U2.instance.len(),
+            });
+        }
+        check_instance_commitment_presence(
+            self.instance_commitment.as_ref(),
+            U2.instance_commitment.as_ref(),
+        )?;
+
+        self.W_commitments
+            .iter_mut()
+            .zip(U2.W_commitments.iter())
             .enumerate()
-            .map(|(W_index, (W1, W2))| {
-                let rW = best_multiexp(&[*r], &[W2]).into();
-                let res = *W1 + rW;
+            .for_each(|(W_index, (W1, W2))| {
+                let rW = best_multiexp(&[*r], &[*W2]).into();
+                let res = (*W1 + rW).into();
                 debug!(
                     "W1 = {W1:?}; W2 = {W2:?}; rW2[{W_index}] = {rW:?}; rW1 + rW2 * r = {res:?}"
                 );
-                res.into()
-            })
-            .collect::<Vec<C>>();
+                *W1 = res;
+            });
 
-        let instance = self
-            .instance
-            .par_iter()
-            .zip(&U2.instance)
-            .map(|(a, b)| *a + *r * b)
-            .collect::<Vec<C::ScalarExt>>();
+        fold_values_assign(
+            &mut self.instance,
+            &U2.instance,
+            r,
+            U2.instance.len() >= PARALLEL_FOLD_THRESHOLD,
+        );
+        fold_values_assign(&mut self.challenges, &U2.challenges, r, false);
 
-        let challenges = self
-            .challenges
-            .iter()
-            .zip_eq(U2.challenges.iter())
-            .map(|(a, b)| *a + *r * b)
-            .collect::<Vec<C::ScalarExt>>();
+        self.u += *r;
 
-        let u = self.u + *r;
+        let powers_of_r = powers_of_r(*r)
+            .take(cross_term_commits.len())
+            .collect::<Vec<_>>();
+        self.E_commitment = self.fold_E_commitment(cross_term_commits, &powers_of_r);
+
+        // presence on both sides is checked above
+        self.instance_commitment =
+            self.instance_commitment
+                .zip(U2.instance_commitment)
+                .map(|(C1, C2)| {
+                    let rC2: C = best_multiexp(&[*r], &[C2]).into();
+                    (C1 + rC2).into()
+                });
+
+        Ok(powers_of_r)
+    }
 
-        let comm_E = cross_term_commits
+    /// Same as [`RelaxedPlonkInstance::fold`], but `U2` is relaxed too, so its `u`, `E` &
+    /// challenges are combined as is
+    ///
+    /// With cross terms `[T_1, ..., T_{d-1}]` folded error term is
+    /// `E1 + sum_k r^k * T_k + r^d * E2`, so `cross_term_commits` must be computed with `u2` of
+    /// `U2`, see [`crate::nifs::vanilla::VanillaFS::commit_cross_terms_relaxed`]
+    pub fn fold_relaxed(
+        &self,
+        U2: &RelaxedPlonkInstance<C>,
+        cross_term_commits: &[C],
+        r: &C::ScalarExt,
+    ) -> Result<Self, Error> {
+        if self.instance.len() != U2.instance.len() {
+            return Err(Error::NumIoMismatch {
+                expected: self.instance.len(),
+                actual: U2.instance.len(),
+            });
+        }
+        check_instance_commitment_presence(
+            self.instance_commitment.as_ref(),
+            U2.instance_commitment.as_ref(),
+        )?;
+
+        let fold_point = |C1: &C, C2: &C| -> C {
+            let rC2: C = best_multiexp(&[*r], &[*C2]).into();
+            (*C1 + rC2).into()
+        };
+
+        let W_commitments = self
+            .W_commitments
             .iter()
-            .zip(iter::successors(Some(*r), |el| Some(*el * *r))) // r^1, r^2, ...
-            .map(|(tk, power_of_r)| best_multiexp(&[power_of_r], &[*tk]).into())
-            .fold(self.E_commitment, |acc, x| (acc + x).into());
+            .zip_eq(U2.W_commitments.iter())
+            .map(|(W1, W2)| fold_point(W1, W2))
+            .collect::<Vec<C>>();
 
-        RelaxedPlonkInstance {
+        let instance = fold_values(
+            &self.instance,
+            &U2.instance,
+            r,
+            self.instance.len() >= PARALLEL_FOLD_THRESHOLD,
+        );
+        let challenges = fold_values(&self.challenges, &U2.challenges, r, false);
+
+        let E_terms = concat_vec!(cross_term_commits, &[U2.E_commitment]);
+        let powers_of_r = powers_of_r(*r).take(E_terms.len()).collect::<Vec<_>>();
+
+        Ok(RelaxedPlonkInstance {
             W_commitments,
-            E_commitment: comm_E,
+            E_commitment: self.fold_E_commitment(&E_terms, &powers_of_r),
             instance,
-            u,
+            u: self.u + *r * U2.u,
             challenges,
-        }
+            // presence on both sides is checked above
+            instance_commitment: self
+                .instance_commitment
+                .zip(U2.instance_commitment)
+                .map(|(C1, C2)| fold_point(&C1, &C2)),
+        })
+    }
+
+    /// Folds commitment of error vector with given powers of `r`:
+    /// `Comm(E) + sum_k powers_of_r[k] * Comm(T_k)`
+    ///
+    /// Instance side counterpart of [`fold_E`]
+    pub fn fold_E_commitment(&self, cross_term_commits: &[C], powers_of_r: &[C::ScalarExt]) -> C {
+        cross_term_commits
+            .iter()
+            .zip_eq(powers_of_r)
+            .map(|(tk, power_of_r)| best_multiexp(&[*power_of_r], &[*tk]).into())
+            .fold(self.E_commitment, |acc, x| (acc + x).into())
     }
 }
 
@@ -879,51 +2354,201 @@ impl<F: PrimeField> RelaxedPlonkWitness<F> {
     pub fn new(k_table_size: usize, round_sizes: &[usize]) -> Self {
         Self {
             W: round_sizes.iter().map(|sz| vec![F::ZERO; *sz]).collect(),
-            E: iter::repeat(F::ZERO).take(1 << k_table_size).collect(),
+            W_blinds: vec![F::ZERO; round_sizes.len()],
+            E: ErrorVector::zero(1 << k_table_size),
+        }
+    }
+
+    /// Error vector, see [`ErrorVector`]
+    pub fn E_mut(&mut self) -> &mut ErrorVector<F> {
+        &mut self.E
+    }
+
+    /// Commits to each round of `W` and to `E`
+    ///
+    /// Returns `(W_commitments, E_commitment)`, which is expected to match the
+    /// [`RelaxedPlonkInstance`] of this witness
+    pub fn commit<C: CurveAffine<ScalarExt = F>>(
+        &self,
+        ck: &CommitmentKey<C>,
+    ) -> Result<(Vec<C>, C), commitment::Error> {
+        let (W_commitments, E_commitment) = rayon::join(
+            || {
+                self.W
+                    .par_iter()
+                    .zip_eq(self.W_blinds.par_iter())
+                    .map(|(Wi, blind)| ck.commit_hiding(Wi, blind))
+                    .collect::<Result<Vec<_>, _>>()
+            },
+            || self.E.commit(ck),
+        );
+
+        Ok((W_commitments?, E_commitment?))
+    }
+
+    /// Compresses witness for storage between folding steps
+    ///
+    /// `E` is dropped if it's all zeros. If `prev` is present & has the same round sizes, `W` is
+    /// stored as sparse deltas against `prev.W`, so the same `prev` must be passed to
+    /// [`CompressedWitness::decompress`]. Otherwise `W` is stored as is
+    pub fn compress(&self, prev: Option<&Self>) -> CompressedWitness<F> {
+        let same_shape = |prev: &&Self| prev.W.iter().map(Vec::len).eq(self.W.iter().map(Vec::len));
+
+        let W = match prev.filter(same_shape) {
+            Some(prev) => CompressedRounds::Delta(
+                self.W
+                    .iter()
+                    .zip_eq(prev.W.iter())
+                    .map(|(W, prev_W)| {
+                        W.iter()
+                            .zip_eq(prev_W.iter())
+                            .map(|(w, prev_w)| *w - prev_w)
+                            .enumerate()
+                            .filter(|(_, delta)| !bool::from(delta.is_zero()))
+                            .collect()
+                    })
+                    .collect(),
+            ),
+            None => CompressedRounds::Full(self.W.clone()),
+        };
+
+        CompressedWitness {
+            W,
+            W_blinds: self.W_blinds.clone(),
+            E: (!self.E.is_zero()).then(|| self.E.to_vec().into_boxed_slice()),
+            E_len: self.E.len(),
         }
     }
 
     #[instrument(name = "fold_witness", skip_all)]
-    pub fn fold(&self, W2: &PlonkWitness<F>, cross_terms: &[Box<[F]>], r: &F) -> Self {
+    pub fn fold(
+        &self,
+        W2: &PlonkWitness<F>,
+        cross_terms: &[Box<[F]>],
+        r: &F,
+    ) -> Result<Self, Error> {
+        let mut folded = self.clone();
+        folded.fold_assign(W2, cross_terms, r)?;
+        Ok(folded)
+    }
+
+    /// Witness counterpart of [`RelaxedPlonkInstance::fold_relaxed`]: `E2` of `W2` is folded as
+    /// the last cross term, i.e. with `r^d`
+    pub fn fold_relaxed(
+        &self,
+        W2: &RelaxedPlonkWitness<F>,
+        cross_terms: &[Box<[F]>],
+        r: &F,
+    ) -> Result<Self, Error> {
+        let mut folded = self.clone();
+        folded.fold_assign_with(&W2.W, &W2.W_blinds, cross_terms, Some(&W2.E), r)?;
+        Ok(folded)
+    }
+
+    /// Same as [`RelaxedPlonkWitness::fold`], but updates `self` in place without allocations
+    ///
+    /// Each cross term must have the same length as `E`, otherwise
+    /// [`Error::CrossTermLengthMismatch`] is returned and `self` is left untouched
+    #[instrument(name = "fold_witness_assign", skip_all)]
+    pub fn fold_assign(
+        &mut self,
+        W2: &PlonkWitness<F>,
+        cross_terms: &[Box<[F]>],
+        r: &F,
+    ) -> Result<(), Error> {
+        self.fold_assign_with(&W2.W, &W2.blinds, cross_terms, None, r)
+    }
+
+    /// Folds `W2` with its blinds into `W`, `cross_terms` & then `E2` into `E`, see [`fold_E`]
+    fn fold_assign_with<T: AsRef<[F]>>(
+        &mut self,
+        W2: &[Vec<F>],
+        W2_blinds: &[F],
+        cross_terms: &[T],
+        E2: Option<&ErrorVector<F>>,
+        r: &F,
+    ) -> Result<(), Error> {
+        check_cross_terms_len(self.E.len(), cross_terms)?;
+        if let Some(E2) = E2.filter(|E2| E2.len() != self.E.len()) {
+            return Err(Error::CrossTermLengthMismatch {
+                index: cross_terms.len(),
+                expected: self.E.len(),
+                actual: E2.len(),
+            });
+        }
+
         debug!("start W: {} len", self.W.len());
-        let W = self
-            .W
-            .iter()
-            .zip_eq(W2.W.iter())
-            .map(|(vec1, vec2)| {
-                vec1.par_iter()
+        self.W
+            .iter_mut()
+            .zip_eq(W2.iter())
+            .for_each(|(vec1, vec2)| {
+                vec1.par_iter_mut()
                     .zip_eq(vec2.par_iter())
-                    .map(|(w1, w2)| *w1 + *r * *w2)
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
+                    .for_each(|(w1, w2)| *w1 += *r * *w2)
+            });
+        self.W_blinds
+            .iter_mut()
+            .zip_eq(W2_blinds.iter())
+            .for_each(|(b1, b2)| *b1 += *r * b2);
 
         debug!(
             "start E {} len & cross term {} len",
             self.E.len(),
             cross_terms.len()
         );
+        fold_E(&mut self.E, cross_terms, E2, r);
 
-        // r^1, r^2, ...
-        let powers_or_r = iter::successors(Some(*r), |el| Some(*el * r))
-            .take(cross_terms.len())
-            .collect::<Box<[_]>>();
-        let E = self
-            .E
-            .par_iter()
-            .enumerate()
-            .map(|(i, ei)| {
-                cross_terms
-                    .iter()
-                    .zip_eq(powers_or_r.iter().copied())
-                    .fold(*ei, |acc, (tk, power_of_r)| acc + power_of_r * tk[i])
-            })
-            .collect();
+        Ok(())
+    }
+}
 
-        RelaxedPlonkWitness { W, E }
+/// Checks that every cross term `T_k` has exactly `expected` (the length of `E`) elements
+fn check_cross_terms_len<F, T: AsRef<[F]>>(
+    expected: usize,
+    cross_terms: &[T],
+) -> Result<(), Error> {
+    match cross_terms
+        .iter()
+        .map(<T as AsRef<[F]>>::as_ref)
+        .enumerate()
+        .find(|(_, tk)| tk.len() != expected)
+    {
+        Some((index, tk)) => Err(Error::CrossTermLengthMismatch {
+            index,
+            expected,
+            actual: tk.len(),
+        }),
+        None => Ok(()),
     }
 }
 
+/// `r^1, r^2, ...` - coefficients of cross terms `T_1, T_2, ...` while folding `E`
+fn powers_of_r<F: PrimeField>(r: F) -> impl Iterator<Item = F> {
+    iter::successors(Some(r), move |el| Some(*el * r))
+}
+
+/// Folds error vector in place: `E += sum_k r^k * T_k`, `E2` if present is the last term
+///
+/// Witness side counterpart of [`RelaxedPlonkInstance::fold_E_commitment`], both use
+/// [`powers_of_r`]. Chunks, which are zero in all terms, are skipped, see [`ErrorVector::fold`]
+pub(crate) fn fold_E<F: PrimeField, T: AsRef<[F]>>(
+    E: &mut ErrorVector<F>,
+    cross_terms: &[T],
+    E2: Option<&ErrorVector<F>>,
+    r: &F,
+) {
+    let terms = powers_of_r(*r)
+        .zip(
+            cross_terms
+                .iter()
+                .map(|tk| ErrorTerm::Dense(tk.as_ref()))
+                .chain(E2.map(ErrorTerm::Chunked)),
+        )
+        .collect::<Vec<_>>();
+
+    E.fold(&terms);
+}
+
 // Evaluates the witness data for each gate in the PLONK structure.
 ///
 /// This function iterates through the gates of a provided [`PlonkStructure`],
@@ -935,9 +2560,9 @@ impl<F: PrimeField> RelaxedPlonkWitness<F> {
 ///
 /// # Parameters
 /// - `S`: A reference to a [`PlonkStructure`] containing the circuit structure and gates.
-/// - `trace`: An object that provides both challenges and witness values through the
-///            [`GetChallenges`] and [`GetWitness`] traits. In can be: [`PlonkWitness`],
-///            [`RelaxedPlonkWitness`], [`RelaxedPlonkTrace`], [`PlonkTrace`] etc
+/// - `trace`: An object that provides challenges, witness & instance values through the
+///            [`GetChallenges`], [`GetWitness`] and [`GetInstance`] traits. In can be:
+///            [`RelaxedPlonkTrace`], [`PlonkTrace`] etc
 ///
 /// # Returns
 /// An iterator that produces [`Result<C::ScalarExt, eval::Error>`] items. Each item is either the
@@ -947,17 +2572,23 @@ impl<F: PrimeField> RelaxedPlonkWitness<F> {
 /// In other words iterator: `[gate1(row0), ..., gate1(rowN), gate2(0), ...]`
 pub(crate) fn iter_evaluate_witness<'link, C: CurveAffine>(
     S: &'link PlonkStructure<C::ScalarExt>,
-    trace: &'link (impl Sync + GetChallenges<C::ScalarExt> + GetWitness<C::ScalarExt>),
+    trace: &'link (impl Sync
+                + GetChallenges<C::ScalarExt>
+                + GetWitness<C::ScalarExt>
+                + GetInstance<C::ScalarExt>),
 ) -> impl 'link + Send + Iterator<Item = Result<C::ScalarExt, eval::Error>> {
     S.gates.iter().flat_map(|gate| {
         let eval_domain = PlonkEvalDomain {
             num_advice: S.num_advice_columns,
             num_lookup: S.num_lookups(),
+            num_instance: S.num_instance_columns,
             selectors: &S.selectors,
             fixed: &S.fixed_columns,
             challenges: trace.get_challenges(),
             W1s: trace.get_witness(),
             W2s: &[],
+            instance1: trace.get_instance(),
+            instance2: &[],
         };
 
         let evaluator = GraphEvaluator::new(gate);
@@ -1108,3 +2739,330 @@ pub(crate) mod test_eval_witness {
             });
     }
 }
+
+#[cfg(test)]
+mod relaxed_witness_tests {
+    use halo2curves::{bn256, group::prime::PrimeCurveAffine, CurveAffine};
+
+    use super::*;
+
+    type Curve = bn256::G1Affine;
+    type Field = <Curve as CurveAffine>::ScalarExt;
+
+    #[test]
+    fn commit() {
+        const K: usize = 4;
+        let ck = CommitmentKey::<Curve>::setup(K + 1, b"relaxed_witness_commit");
+
+        let fresh = RelaxedPlonkWitness::<Field>::new(K, &[1 << K, 1 << K]);
+        let (W_commitments, E_commitment) = fresh.commit(&ck).unwrap();
+        assert_eq!(E_commitment, CommitmentKey::<Curve>::identity_commitment());
+        assert!(W_commitments
+            .iter()
+            .all(|Ci| Ci.eq(&CommitmentKey::<Curve>::identity_commitment())));
+
+        // zero witness commits to the configured empty value, same as fresh instance
+        let ck_with_empty = ck.clone().with_empty_commitment(Curve::generator());
+        let U = RelaxedPlonkInstance::new_with_ck(&ck_with_empty, 0, 0, 2);
+        assert_eq!(
+            fresh.commit(&ck_with_empty).unwrap(),
+            (U.W_commitments.clone(), U.E_commitment)
+        );
+        assert_eq!(U.E_commitment, Curve::generator());
+        assert_ne!(ck_with_empty, ck);
+
+        let W = RelaxedPlonkWitness {
+            W: vec![
+                (0..1 << K).map(Field::from).collect(),
+                (0..2 << K).map(|i| Field::from(i * 3 + 1)).collect(),
+            ],
+            W_blinds: vec![Field::from(5), Field::from(6)],
+            E: (0..1 << K).map(|i| Field::from(i + 7)).collect(),
+        };
+        let (W_commitments, E_commitment) = W.commit(&ck).unwrap();
+        assert_eq!(
+            W_commitments,
+            W.W.iter()
+                .zip_eq(W.W_blinds.iter())
+                .map(|(Wi, blind)| ck.commit_hiding(Wi, blind).unwrap())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(E_commitment, ck.commit(&W.E.to_vec()).unwrap());
+    }
+
+    #[test]
+    fn fold_assign() {
+        const K: usize = 3;
+        let round_sizes = [2 << K];
+
+        let incoming = (1..4u64)
+            .map(|step| PlonkWitness {
+                W: vec![(0..2 << K).map(|i| Field::from(i * step + 1)).collect()],
+                blinds: vec![Field::from(step)],
+            })
+            .collect::<Vec<_>>();
+        let cross_terms = (1..4u64)
+            .map(|step| {
+                (0..3)
+                    .map(|k| (0..1 << K).map(|i| Field::from(i + k * step)).collect())
+                    .collect::<Vec<Box<[_]>>>()
+            })
+            .collect::<Vec<_>>();
+        let rs = [Field::from(7), Field::from(11), Field::from(13)];
+
+        let mut from_scratch = RelaxedPlonkWitness::<Field>::new(K, &round_sizes);
+        let mut incremental = from_scratch.clone();
+        let mut expected_E = from_scratch.E.to_vec();
+
+        for ((W2, T), r) in incoming.iter().zip(cross_terms.iter()).zip(rs.iter()) {
+            from_scratch = from_scratch.fold(W2, T, r).unwrap();
+            incremental.fold_assign(W2, T, r).unwrap();
+
+            for (i, e) in expected_E.iter_mut().enumerate() {
+                *e += *r * T[0][i] + *r * r * T[1][i] + *r * r * r * T[2][i];
+            }
+        }
+
+        assert_eq!(incremental.W, from_scratch.W);
+        assert_eq!(incremental.W_blinds, from_scratch.W_blinds);
+        assert_eq!(incremental.E, from_scratch.E);
+        assert_eq!(incremental.E.to_vec(), expected_E);
+    }
+
+    #[test]
+    fn fold_short_cross_term() {
+        const K: usize = 3;
+
+        let acc = RelaxedPlonkWitness::<Field>::new(K, &[1 << K]);
+        let W2 = PlonkWitness {
+            W: vec![vec![Field::from(1); 1 << K]],
+            blinds: vec![Field::from(1)],
+        };
+        let cross_terms: Vec<Box<[Field]>> = vec![
+            vec![Field::from(1); 1 << K].into_boxed_slice(),
+            vec![Field::from(1); (1 << K) - 1].into_boxed_slice(),
+        ];
+
+        let expected = || Error::CrossTermLengthMismatch {
+            index: 1,
+            expected: 1 << K,
+            actual: (1 << K) - 1,
+        };
+        assert_eq!(
+            acc.fold(&W2, &cross_terms, &Field::from(2)).err(),
+            Some(expected())
+        );
+
+        let mut in_place = acc.clone();
+        assert_eq!(
+            in_place.fold_assign(&W2, &cross_terms, &Field::from(2)),
+            Err(expected())
+        );
+        assert_eq!(in_place.E, acc.E);
+    }
+
+    #[test]
+    fn into_relax() {
+        const K: usize = 4;
+
+        let W = PlonkWitness {
+            W: vec![
+                (0..1 << K).map(Field::from).collect(),
+                (0..2 << K).map(|i| Field::from(i * 3 + 1)).collect(),
+            ],
+            blinds: vec![Field::from(5), Field::from(6)],
+        };
+
+        let expected = W.clone();
+        let buffers = W.W.iter().map(|Wi| Wi.as_ptr()).collect::<Vec<_>>();
+        let moved = W.into_relax(K);
+
+        assert_eq!(moved.W, expected.W);
+        assert_eq!(moved.W_blinds, expected.blinds);
+        // rounds are moved, not copied
+        assert_eq!(
+            moved.W.iter().map(|Wi| Wi.as_ptr()).collect::<Vec<_>>(),
+            buffers
+        );
+        assert_eq!(moved.E, ErrorVector::zero(1 << K));
+        assert_eq!(moved.E.chunks().count(), 0);
+    }
+}
+
+#[cfg(test)]
+mod instance_tests {
+    use halo2curves::bn256::{Fq, G1Affine};
+
+    use super::*;
+
+    #[test]
+    fn validate() {
+        let ck = CommitmentKey::<G1Affine>::setup(1, b"validate");
+
+        let mut u = PlonkInstance::<G1Affine>::new(1, 0, 2);
+        u.W_commitments[1] = ck[1];
+        assert_eq!(u.validate(), Ok(()));
+
+        let mut U = u.to_relax();
+        U.E_commitment = ck[0];
+        assert_eq!(U.validate(), Ok(()));
+
+        let tampered = G1Affine {
+            x: ck[1].x,
+            y: ck[1].y + Fq::one(),
+        };
+
+        u.W_commitments[1] = tampered;
+        assert_eq!(u.validate(), Err(Error::InvalidCommitment { name: "W" }));
+
+        U.E_commitment = tampered;
+        assert_eq!(U.validate(), Err(Error::InvalidCommitment { name: "E" }));
+
+        let bytes = bincode::serialize(&u).unwrap();
+        assert!(bincode::deserialize::<PlonkInstance<G1Affine>>(&bytes).is_err());
+        let bytes = bincode::serialize(&U).unwrap();
+        assert!(bincode::deserialize::<RelaxedPlonkInstance<G1Affine>>(&bytes).is_err());
+
+        let bytes = bincode::serialize(&PlonkInstance::<G1Affine>::new(1, 0, 2)).unwrap();
+        assert!(bincode::deserialize::<PlonkInstance<G1Affine>>(&bytes).is_ok());
+    }
+
+    #[test]
+    fn default_is_empty() {
+        assert_eq!(
+            PlonkInstance::<G1Affine>::default(),
+            PlonkInstance::new(0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn fold_values_serial_parallel() {
+        use halo2curves::bn256::Fr;
+
+        let r = Fr::from(7);
+        for len in [2, PARALLEL_FOLD_THRESHOLD, 4096] {
+            let lhs = (0..len as u64).map(Fr::from).collect::<Vec<_>>();
+            let rhs = (0..len as u64).map(|i| Fr::from(i * i)).collect::<Vec<_>>();
+
+            let serial = fold_values(&lhs, &rhs, &r, false);
+            assert_eq!(serial, fold_values(&lhs, &rhs, &r, true));
+            assert_eq!(serial[1], Fr::from(1 + 7));
+        }
+    }
+
+    #[test]
+    fn fold_num_io_mismatch() {
+        use halo2curves::bn256::Fr;
+
+        let U1 = PlonkInstance::<G1Affine>::new(2, 0, 1).to_relax();
+        let u2 = PlonkInstance::<G1Affine>::new(3, 0, 1);
+
+        assert_eq!(
+            U1.fold(&u2, &[], &Fr::from(3)),
+            Err(Error::NumIoMismatch {
+                expected: 2,
+                actual: 3
+            })
+        );
+    }
+
+    #[test]
+    fn fold_instance_commitment_presence() {
+        use halo2curves::bn256::Fr;
+
+        let ck = CommitmentKey::<G1Affine>::setup(2, b"fold_instance_commitment_presence");
+
+        let U1 = PlonkInstance::<G1Affine>::new(2, 0, 1).to_relax();
+        let mut u2 = PlonkInstance::<G1Affine>::new(2, 0, 1);
+        u2.commit_instance(&ck).unwrap();
+
+        let expected = Err(Error::StructureMismatch {
+            field: "instance commitments",
+            expected: 0,
+            actual: 1,
+        });
+        assert_eq!(U1.fold(&u2, &[], &Fr::from(3)), expected);
+        assert_eq!(U1.fold_relaxed(&u2.to_relax(), &[], &Fr::from(3)), expected);
+    }
+
+    #[test]
+    fn fold_challenges() {
+        use halo2curves::bn256::Fr;
+
+        let mut U1 = PlonkInstance::<G1Affine>::new(1, 1, 1).to_relax();
+        U1.challenges = vec![Fr::from(5)];
+
+        let mut u2 = PlonkInstance::<G1Affine>::new(1, 1, 1);
+        u2.challenges = vec![Fr::from(7)];
+
+        let r = Fr::from(3);
+        let U = U1.fold(&u2, &[], &r).unwrap();
+
+        assert_eq!(U.challenges, vec![Fr::from(5 + 3 * 7)]);
+        assert_eq!(U.u, Fr::from(1 + 3));
+
+        // second fold accumulates on top of already relaxed challenge
+        let U = U.fold(&u2, &[], &r).unwrap();
+        assert_eq!(U.challenges, vec![Fr::from(5 + 3 * 7 + 3 * 7)]);
+    }
+
+    #[test]
+    fn fold_audited() {
+        use halo2curves::bn256::Fr;
+
+        let ck = CommitmentKey::<G1Affine>::setup(2, b"fold_audited");
+
+        let mut U1 = PlonkInstance::<G1Affine>::new(1, 0, 1).to_relax();
+        U1.E_commitment = ck[0];
+        let u2 = PlonkInstance::<G1Affine>::new(1, 0, 1);
+        let cross_term_commits = ck[1..].to_vec();
+        let r = Fr::from(7);
+
+        let (U, powers) = U1.fold_audited(&u2, &cross_term_commits, &r).unwrap();
+        assert_eq!(U, U1.fold(&u2, &cross_term_commits, &r).unwrap());
+        assert_eq!(powers, vec![r, r * r, r * r * r]);
+
+        assert_eq!(
+            U1.fold_E_commitment(&cross_term_commits, &powers),
+            U.E_commitment
+        );
+    }
+
+    #[test]
+    fn fold_assign_instance() {
+        use halo2curves::bn256::Fr;
+
+        let ck = CommitmentKey::<G1Affine>::setup(3, b"fold_assign_instance");
+
+        let mut U1 = PlonkInstance::<G1Affine>::new(2, 1, 1).to_relax();
+        U1.E_commitment = ck[0];
+        U1.challenges = vec![Fr::from(5)];
+
+        let mut u2 = PlonkInstance::<G1Affine>::new(2, 1, 1);
+        u2.W_commitments = vec![ck[1]];
+        u2.instance = vec![Fr::from(2), Fr::from(3)];
+        u2.challenges = vec![Fr::from(7)];
+        let cross_term_commits = ck[2..4].to_vec();
+
+        let mut incremental = U1.clone();
+        let mut from_scratch = U1;
+        for r in [Fr::from(3), Fr::from(11)] {
+            from_scratch = from_scratch.fold(&u2, &cross_term_commits, &r).unwrap();
+            incremental
+                .fold_assign(&u2, &cross_term_commits, &r)
+                .unwrap();
+        }
+        assert_eq!(incremental, from_scratch);
+
+        let before = incremental.clone();
+        let u3 = PlonkInstance::<G1Affine>::new(3, 1, 1);
+        assert_eq!(
+            incremental.fold_assign(&u3, &[], &Fr::from(3)),
+            Err(Error::NumIoMismatch {
+                expected: 2,
+                actual: 3
+            })
+        );
+        assert_eq!(incremental, before);
+    }
+}
@@ -1,8 +1,8 @@
 use crate::plonk::permutation::Assembly;
 use crate::polynomial::sparse::SparseMatrix;
-use crate::polynomial::Expression;
+use crate::polynomial::{expression::QueryIndexContext, Expression, Halo2ExprError};
 use ff::PrimeField;
-use halo2_proofs::plonk::{Any, Column};
+use halo2_proofs::plonk::{Any, Column, Fixed};
 use halo2_proofs::plonk::{ConstraintSystem, Expression as PE};
 use std::collections::HashSet;
 
@@ -45,6 +45,15 @@ pub(crate) fn fill_sparse_matrix<F: PrimeField>(
     num_io: usize,
     columns: &[Column<Any>],
 ) -> SparseMatrix<F> {
+    // fixed columns are allowed only as constants columns, which are replaced by gates, see
+    // [`crate::table::ConstantsConfig`]
+    let columns = columns
+        .iter()
+        .filter(|column| *column.column_type() != Any::Fixed)
+        .copied()
+        .collect::<Vec<_>>();
+    let columns = columns.as_slice();
+
     let num_columns = if num_io > 0 {
         num_advice + 1
     } else {
@@ -70,47 +79,70 @@ pub(crate) fn fill_sparse_matrix<F: PrimeField>(
 /// compress a vector of halo2 expressions into one by random linear combine a challenge
 pub(crate) fn compress_halo2_expression<F: PrimeField>(
     exprs: &[PE<F>],
-    num_selectors: usize,
-    num_fixed: usize,
+    ctx: &QueryIndexContext,
     challenge_index: usize,
-) -> Expression<F> {
+) -> Result<Expression<F>, Halo2ExprError> {
     let y = Expression::Challenge(challenge_index);
     if exprs.len() > 1 {
         exprs
             .iter()
-            .map(|expr| Expression::from_halo2_expr(expr, num_selectors, num_fixed))
-            .fold(Expression::Constant(F::ZERO), |acc, expr| {
-                Expression::Sum(
-                    Box::new(expr),
+            .map(|expr| Expression::from_halo2_expr(expr, ctx))
+            .try_fold(Expression::Constant(F::ZERO), |acc, expr| {
+                Ok(Expression::Sum(
+                    Box::new(expr?),
                     Box::new(Expression::Product(Box::new(acc), Box::new(y.clone()))),
-                )
+                ))
             })
     } else {
-        Expression::from_halo2_expr(&exprs[0], num_selectors, num_fixed)
+        Expression::from_halo2_expr(&exprs[0], ctx)
     }
 }
 
 /// compress a vector of [`Expression`] into one by random linear combine a challenge
+///
+/// `exprs[i]` is multiplied by `y^(n - 1 - i)`, as with Horner's scheme, but expressions are
+/// combined by a balanced tree, so depth of result grows logarithmically with count of gates and
+/// recursive walkers over it (evaluation, homogenization, etc.) don't overflow the stack. Halves
+/// of the tree are built in parallel
 pub(crate) fn compress_expression<F: PrimeField>(
     exprs: &[Expression<F>],
     challenge_index: usize,
 ) -> Expression<F> {
-    let y = Expression::Challenge(challenge_index);
-    if exprs.len() > 1 {
-        exprs
-            .iter()
-            .fold(Expression::Constant(F::ZERO), |acc, expr| {
+    /// `y^exp` by squaring, `exp > 0`
+    fn power<F: PrimeField>(y: &Expression<F>, exp: usize) -> Expression<F> {
+        if exp == 1 {
+            return y.clone();
+        }
+
+        let half = power(y, exp / 2);
+        let square = Expression::Product(Box::new(half.clone()), Box::new(half));
+        if exp % 2 == 1 {
+            Expression::Product(Box::new(square), Box::new(y.clone()))
+        } else {
+            square
+        }
+    }
+
+    fn combine<F: PrimeField>(exprs: &[Expression<F>], y: &Expression<F>) -> Expression<F> {
+        match exprs {
+            [] => Expression::Constant(F::ZERO),
+            [expr] => expr.clone(),
+            exprs => {
+                let (lo, hi) = exprs.split_at(exprs.len() / 2);
+                let (lo_expr, hi_expr) = rayon::join(|| combine(lo, y), || combine(hi, y));
+
                 Expression::Sum(
-                    Box::new(expr.clone()),
-                    Box::new(Expression::Product(Box::new(acc), Box::new(y.clone()))),
+                    Box::new(Expression::Product(
+                        Box::new(lo_expr),
+                        Box::new(power(y, hi.len())),
+                    )),
+                    Box::new(hi_expr),
                 )
-            })
-    } else {
-        exprs
-            .first()
-            .cloned()
-            .unwrap_or(Expression::Constant(F::ZERO))
+            }
+        }
     }
+
+    combine(exprs, &Expression::Challenge(challenge_index))
 }
 
 /// Construct sparse matrix P of size `N*N` from copy constraints since folding will change values of
@@ -130,6 +162,19 @@ pub(crate) fn construct_permutation_matrix<F: PrimeField>(
     let columns = &cs.permutation.columns;
     let instance_column_idx = get_instance_column_index(columns);
 
+    // Copies with constants are checked by gates, see [`crate::table::ConstantsConfig`], so
+    // cells of constants columns are skipped in cycles
+    let is_constant = |column: usize| {
+        Column::<Fixed>::try_from(columns[column])
+            .is_ok_and(|column| cs.constants().contains(&column))
+    };
+    let next_not_constant = |mut cell: (usize, usize)| {
+        while is_constant(cell.0) {
+            cell = permutation.mapping[cell.0][cell.1];
+        }
+        cell
+    };
+
     // Same as [`column_index`], but with already ready `instance_column_idx`
     let get_column_index = |column: &Column<Any>| -> usize {
         column.index()
@@ -145,10 +190,15 @@ pub(crate) fn construct_permutation_matrix<F: PrimeField>(
     let num_rows = 1 << k_table_size;
     let mut sparse_matrix_p = Vec::new();
     for (left_col, mapping_vec) in permutation.mapping.iter().enumerate() {
+        if is_constant(left_col) {
+            continue;
+        }
+
         for (left_row, cycle) in mapping_vec.iter().enumerate() {
             if left_row >= num_io && Some(left_col).eq(&instance_column_idx) {
                 continue;
             }
+            let cycle = next_not_constant(*cycle);
 
             let left_col = get_column_index(&columns[left_col]);
             let right_col = get_column_index(&columns[cycle.0]);
@@ -168,3 +218,49 @@ pub(crate) fn construct_permutation_matrix<F: PrimeField>(
         columns,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use ff::Field;
+    use halo2curves::pasta::Fp;
+
+    use super::*;
+
+    #[test]
+    fn compress_expression_balanced() {
+        const NUM_GATES: u64 = 1000;
+
+        let exprs = (0..NUM_GATES)
+            .map(|i| Expression::Constant(Fp::from(i)))
+            .collect::<Vec<_>>();
+        let y = Fp::from(3);
+
+        let compressed = compress_expression(&exprs, 0);
+        let evaluate = |expr: &Expression<Fp>| {
+            expr.evaluate(
+                &|value| value,
+                &|_| unreachable!(),
+                &|_| y,
+                &|a| -a,
+                &|a, b| a + b,
+                &|a, b| a * b,
+                &|a, k| a * k,
+            )
+        };
+
+        // Horner's scheme: `exprs[i] * y^(n - 1 - i)`
+        let expected = (0..NUM_GATES).fold(Fp::ZERO, |acc, i| acc * y + Fp::from(i));
+        assert_eq!(evaluate(&compressed), expected);
+
+        let depth = compressed.evaluate(
+            &|_| 1,
+            &|_| 1,
+            &|_| 1,
+            &|a| a + 1,
+            &|a: usize, b| a.max(b) + 1,
+            &|a: usize, b| a.max(b) + 1,
+            &|a, _| a + 1,
+        );
+        assert!(depth < 64, "depth: {depth}");
+    }
+}
@@ -1,16 +1,24 @@
 use std::{
     cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd},
-    collections::{BTreeSet, HashSet},
+    collections::{BTreeMap, BTreeSet, HashSet},
     fmt,
     fmt::{Debug, Display},
-    ops::{self, Add, Mul, Neg, Sub},
+    ops::{self, Add, Mul, Neg, Range, Sub},
 };
 
 use ff::PrimeField;
 use halo2_proofs::{plonk::Expression as PE, poly::Rotation};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{plonk::PlonkStructure, util::trim_leading_zeros};
+
+/// Error of [`Expression::from_halo2_expr`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Halo2ExprError {
+    #[error("halo2 {kind} queries can't be converted into expression")]
+    UnsupportedQuery { kind: &'static str },
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub enum ColumnIndex {
     Challenge { column_index: usize },
@@ -43,6 +51,7 @@ pub struct QueryIndexContext {
     pub num_advice: usize,
     pub num_challenges: usize,
     pub num_lookups: usize,
+    pub num_instance: usize,
 }
 impl<F: PrimeField> From<&PlonkStructure<F>> for QueryIndexContext {
     fn from(S: &PlonkStructure<F>) -> Self {
@@ -52,13 +61,50 @@ impl<F: PrimeField> From<&PlonkStructure<F>> for QueryIndexContext {
             num_selectors: S.selectors.len(),
             num_challenges: S.num_challenges,
             num_lookups: S.num_lookups(),
+            num_instance: S.num_instance_columns,
         }
     }
 }
 
+/// Ranges of [`Query::index`] reserved for each kind of column, see
+/// [`QueryIndexContext::column_index_layout`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnIndexLayout {
+    pub selectors: Range<usize>,
+    pub fixed: Range<usize>,
+    pub advice: Range<usize>,
+    /// `(l, t, m, h, g)` polynomials of each lookup argument
+    pub lookups: Range<usize>,
+    /// Instance columns, values are taken from [`crate::plonk::PlonkInstance::instance`]
+    pub instance: Range<usize>,
+}
+
 impl QueryIndexContext {
+    /// Layout of polynomial query indexes: selectors, fixed, advice, lookup polynomials & then
+    /// instance columns
+    ///
+    /// Advice, lookup & instance queries are folded, so they form one block of
+    /// [`QueryIndexContext::num_fold_vars`] variables, that is repeated for the second pair of
+    /// folding, see [`QueryIndexContext::shift_advice_index`]. Instance column holds `num_io`
+    /// values, rows below them are read as zero. Challenges (including combining challenge `y` &
+    /// homogeneous variable `u`) are queried separately with [`Expression::Challenge`]
+    pub fn column_index_layout(&self) -> ColumnIndexLayout {
+        let fixed_start = self.num_selectors;
+        let advice_start = fixed_start + self.num_fixed;
+        let lookups_start = advice_start + self.num_advice;
+        let instance_start = lookups_start + 5 * self.num_lookups;
+
+        ColumnIndexLayout {
+            selectors: 0..fixed_start,
+            fixed: fixed_start..advice_start,
+            advice: advice_start..lookups_start,
+            lookups: lookups_start..instance_start,
+            instance: instance_start..instance_start + self.num_instance,
+        }
+    }
+
     pub fn num_fold_vars(self) -> usize {
-        self.num_advice + self.num_lookups * 5
+        self.num_advice + self.num_lookups * 5 + self.num_instance
     }
 
     pub fn shift_advice_index(self, advice_poly_index: usize) -> usize {
@@ -68,12 +114,19 @@ impl QueryIndexContext {
     pub fn shift_lookup_index(self, lookup_poly_index: usize) -> usize {
         lookup_poly_index + self.num_fold_vars()
     }
+
+    pub fn shift_instance_index(self, instance_index: usize) -> usize {
+        instance_index + self.num_fold_vars()
+    }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Query {
     pub index: usize,
-    #[serde(serialize_with = "serialize_rotation")]
+    #[serde(
+        serialize_with = "serialize_rotation",
+        deserialize_with = "deserialize_rotation"
+    )]
     pub rotation: Rotation,
 }
 
@@ -82,22 +135,31 @@ pub enum QueryType {
     Fixed,
     Advice,
     Lookup,
+    Instance,
 }
 
 impl Query {
     pub fn subtype(&self, ctx: &QueryIndexContext) -> QueryType {
-        if self.index < ctx.num_selectors {
-            QueryType::Selector
-        } else if self.index < ctx.num_selectors + ctx.num_fixed {
-            QueryType::Fixed
-        } else if self.index < ctx.num_selectors + ctx.num_fixed + ctx.num_advice {
-            QueryType::Advice
-        } else if self.index
-            < ctx.num_selectors + ctx.num_fixed + ctx.num_advice + (5 * ctx.num_lookups)
-        {
-            QueryType::Lookup
+        self.try_subtype(ctx)
+            .unwrap_or_else(|| unreachable!("unknown index {} in {ctx:?}", self.index))
+    }
+
+    /// Same as [`Query::subtype`], but `None` if index is out of `ctx` layout
+    pub fn try_subtype(&self, ctx: &QueryIndexContext) -> Option<QueryType> {
+        let layout = ctx.column_index_layout();
+
+        if layout.selectors.contains(&self.index) {
+            Some(QueryType::Selector)
+        } else if layout.fixed.contains(&self.index) {
+            Some(QueryType::Fixed)
+        } else if layout.advice.contains(&self.index) {
+            Some(QueryType::Advice)
+        } else if layout.lookups.contains(&self.index) {
+            Some(QueryType::Lookup)
+        } else if layout.instance.contains(&self.index) {
+            Some(QueryType::Instance)
         } else {
-            unreachable!("unknown index {} in {ctx:?}", self.index)
+            None
         }
     }
 }
@@ -109,7 +171,13 @@ fn serialize_rotation<S: serde::ser::Serializer>(
     v.0.serialize(serializer)
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+fn deserialize_rotation<'de, D: serde::de::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Rotation, D::Error> {
+    i32::deserialize(deserializer).map(Rotation)
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Expression<F> {
     Constant(F),
     Polynomial(Query),
@@ -299,40 +367,88 @@ impl<F: PrimeField> Expression<F> {
         }
     }
 
-    pub fn from_halo2_expr(expr: &PE<F>, num_selector: usize, num_fixed: usize) -> Self {
-        match expr {
+    /// [`Display`] with variables named by kind of their column: `selector_i`, `fixed_i`,
+    /// `advice_i`, `lookup_i`, `instance_i`, challenges are `r_i`
+    ///
+    /// Indexes are relative to the kind, i.e. `advice_0` is the first advice column. Rotation
+    /// is printed in brackets, e.g. `advice_0[+1]`. Query out of `ctx` layout is `x_i` with its
+    /// raw index.
+    ///
+    /// `ctx.num_challenges` is the count of challenges before compression of gates, so the next
+    /// two are named as in [`crate::plonk::CompressedGates`]: the combining challenge is `y` &
+    /// the homogenizing variable is `u`
+    pub fn named<'link>(&'link self, ctx: &'link QueryIndexContext) -> NamedExpression<'link, F> {
+        NamedExpression {
+            expr: self,
+            ctx,
+            latex: false,
+        }
+    }
+
+    /// Same as [`Expression::named`], but in LaTeX notation, e.g. `a_{0}^{(+1)} \cdot q_{0}`
+    pub fn to_latex(&self, ctx: &QueryIndexContext) -> String {
+        NamedExpression {
+            expr: self,
+            ctx,
+            latex: true,
+        }
+        .to_string()
+    }
+
+    /// Converts halo2 gate or lookup expression, see [`QueryIndexContext::column_index_layout`]
+    ///
+    /// Only `ctx.num_instance` first instance columns are mapped. Fails with
+    /// [`Halo2ExprError::UnsupportedQuery`] on queries, that have no counterpart in
+    /// [`Expression`], see [`Expression::unsupported_halo2_query`]
+    pub fn from_halo2_expr(expr: &PE<F>, ctx: &QueryIndexContext) -> Result<Self, Halo2ExprError> {
+        let layout = ctx.column_index_layout();
+        let convert = |expr: &PE<F>| Self::from_halo2_expr(expr, ctx);
+
+        Ok(match expr {
             PE::Constant(c) => Expression::Constant(*c),
             PE::Selector(sel) => Expression::Polynomial(Query {
-                index: sel.index(),
+                index: layout.selectors.start + sel.index(),
                 rotation: Rotation(0),
             }),
             PE::Fixed(query) => Expression::Polynomial(Query {
-                index: num_selector + query.column_index(),
+                index: layout.fixed.start + query.column_index(),
                 rotation: query.rotation(),
             }),
             PE::Advice(query) => Expression::Polynomial(Query {
-                index: num_selector + num_fixed + query.column_index(),
+                index: layout.advice.start + query.column_index(),
                 rotation: query.rotation(),
             }),
-            PE::Negated(a) => {
-                let a = Self::from_halo2_expr(a, num_selector, num_fixed);
-                -a
-            }
-            PE::Sum(a, b) => {
-                let a = Self::from_halo2_expr(a, num_selector, num_fixed);
-                let b = Self::from_halo2_expr(b, num_selector, num_fixed);
-                a + b
-            }
-            PE::Product(a, b) => {
-                let a = Self::from_halo2_expr(a, num_selector, num_fixed);
-                let b = Self::from_halo2_expr(b, num_selector, num_fixed);
-                a * b
+            PE::Instance(query) if query.column_index() < ctx.num_instance => {
+                Expression::Polynomial(Query {
+                    index: layout.instance.start + query.column_index(),
+                    rotation: query.rotation(),
+                })
             }
-            PE::Scaled(a, k) => {
-                let a = Self::from_halo2_expr(a, num_selector, num_fixed);
-                a * *k
+            PE::Negated(a) => -convert(a)?,
+            PE::Sum(a, b) => convert(a)? + convert(b)?,
+            PE::Product(a, b) => convert(a)? * convert(b)?,
+            PE::Scaled(a, k) => convert(a)? * *k,
+            PE::Instance(_) => return Err(Halo2ExprError::UnsupportedQuery { kind: "instance" }),
+            // Values of halo2 challenges aren't a part of `PlonkInstance::challenges`, so they
+            // can't be folded
+            PE::Challenge(_) => return Err(Halo2ExprError::UnsupportedQuery { kind: "challenge" }),
+        })
+    }
+
+    /// Kind of the first halo2 query in `expr`, which can't be converted by
+    /// [`Expression::from_halo2_expr`]: `"instance"` for instance columns starting from
+    /// `num_instance` or `"challenge"`
+    pub fn unsupported_halo2_query(expr: &PE<F>, num_instance: usize) -> Option<&'static str> {
+        let find = |expr: &PE<F>| Self::unsupported_halo2_query(expr, num_instance);
+
+        match expr {
+            PE::Instance(query) if query.column_index() >= num_instance => Some("instance"),
+            PE::Challenge(_) => Some("challenge"),
+            PE::Constant(_) | PE::Selector(_) | PE::Fixed(_) | PE::Advice(_) | PE::Instance(_) => {
+                None
             }
-            _ => unimplemented!("not supported"),
+            PE::Negated(a) | PE::Scaled(a, _) => find(a),
+            PE::Sum(a, b) | PE::Product(a, b) => find(a).or_else(|| find(b)),
         }
     }
 
@@ -366,7 +482,7 @@ impl<F: PrimeField> Expression<F> {
             Polynomial(polynomial) => HomogeneousExpression {
                 expr: Polynomial(*polynomial),
                 degree: match polynomial.subtype(ctx) {
-                    QueryType::Advice | QueryType::Lookup => 1,
+                    QueryType::Advice | QueryType::Lookup | QueryType::Instance => 1,
                     _other => 0,
                 },
             },
@@ -429,11 +545,146 @@ impl<F: PrimeField> Expression<F> {
         }
     }
 
+    /// Value of expression, if it doesn't depend on any query or challenge
+    ///
+    /// Product with constant zero is considered as constant zero
+    pub fn as_constant(&self) -> Option<F> {
+        self.evaluate(
+            &Some,
+            &|_| None,
+            &|_| None,
+            &|a| a.map(|a| -a),
+            &|a, b| Some(a? + b?),
+            &|a, b| match (a, b) {
+                (Some(zero), _) | (_, Some(zero)) if zero.is_zero_vartime() => Some(F::ZERO),
+                (a, b) => Some(a? * b?),
+            },
+            &|a, k| match a {
+                _ if k.is_zero_vartime() => Some(F::ZERO),
+                a => Some(a? * k),
+            },
+        )
+    }
+
+    /// Canonical binary encoding of expression, equal bytes mean equal expressions
+    ///
+    /// Each node is written as a tag byte followed by its fixed-size fields in little endian, so
+    /// encoding doesn't depend on `Serialize` of `F` & is the same on every platform
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        self.write_canonical(&mut bytes);
+        bytes
+    }
+
+    fn write_canonical(&self, bytes: &mut Vec<u8>) {
+        match self {
+            Expression::Constant(value) => {
+                bytes.push(0);
+                bytes.extend_from_slice(value.to_repr().as_ref());
+            }
+            Expression::Polynomial(query) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&(query.index as u64).to_le_bytes());
+                bytes.extend_from_slice(&query.rotation.0.to_le_bytes());
+            }
+            Expression::Challenge(index) => {
+                bytes.push(2);
+                bytes.extend_from_slice(&(*index as u64).to_le_bytes());
+            }
+            Expression::Negated(a) => {
+                bytes.push(3);
+                a.write_canonical(bytes);
+            }
+            Expression::Sum(a, b) => {
+                bytes.push(4);
+                a.write_canonical(bytes);
+                b.write_canonical(bytes);
+            }
+            Expression::Product(a, b) => {
+                bytes.push(5);
+                a.write_canonical(bytes);
+                b.write_canonical(bytes);
+            }
+            Expression::Scaled(a, k) => {
+                bytes.push(6);
+                a.write_canonical(bytes);
+                bytes.extend_from_slice(k.to_repr().as_ref());
+            }
+        }
+    }
+
+    /// Count of multiplications (products & scalings) required to evaluate expression
+    pub fn num_products(&self) -> usize {
+        self.evaluate(
+            &|_| 0,
+            &|_| 0,
+            &|_| 0,
+            &|a| a,
+            &|a, b| a + b,
+            &|a, b| a + b + 1,
+            &|a, _| a + 1,
+        )
+    }
+
+    /// Count of monomials of expression expanded into sum of products, like terms aren't merged
+    pub fn num_monomials(&self) -> usize {
+        self.evaluate(
+            &|_| 1,
+            &|_| 1,
+            &|_| 1,
+            &|a| a,
+            &|a, b| a.saturating_add(b),
+            &|a, b| a.saturating_mul(b),
+            &|a, _| a,
+        )
+    }
+
+    /// Degree of expression in each variable (query or challenge) it depends on
+    ///
+    /// Like [`Expression::num_monomials`], counted on expanded expression without merging of
+    /// like terms, so cancelled terms are still counted
+    pub fn variable_degrees(&self) -> BTreeMap<ColumnIndex, usize> {
+        self.evaluate(
+            &|_| BTreeMap::new(),
+            &|poly| {
+                BTreeMap::from([(
+                    ColumnIndex::Polynominal {
+                        rotation: poly.rotation.0,
+                        column_index: poly.index,
+                    },
+                    1,
+                )])
+            },
+            &|index| {
+                BTreeMap::from([(
+                    ColumnIndex::Challenge {
+                        column_index: index,
+                    },
+                    1,
+                )])
+            },
+            &|a| a,
+            &|mut a, b| {
+                b.into_iter().for_each(|(var, degree)| {
+                    let entry = a.entry(var).or_default();
+                    *entry = (*entry).max(degree);
+                });
+                a
+            },
+            &|mut a, b| {
+                b.into_iter()
+                    .for_each(|(var, degree)| *a.entry(var).or_default() += degree);
+                a
+            },
+            &|a, _| a,
+        )
+    }
+
     pub fn degree(&self, ctx: &QueryIndexContext) -> usize {
         self.evaluate(
             &|_| 0,
             &|poly| match poly.subtype(ctx) {
-                QueryType::Advice | QueryType::Lookup => 1,
+                QueryType::Advice | QueryType::Lookup | QueryType::Instance => 1,
                 _other => 0,
             },
             &|_| 1,
@@ -448,6 +699,90 @@ impl<F: PrimeField> Expression<F> {
     }
 }
 
+/// See [`Expression::named`]
+pub struct NamedExpression<'link, F> {
+    expr: &'link Expression<F>,
+    ctx: &'link QueryIndexContext,
+    latex: bool,
+}
+
+impl<F: PrimeField> NamedExpression<'_, F> {
+    fn variable(&self, query: &Query) -> String {
+        let layout = self.ctx.column_index_layout();
+        let (text, latex, index) = match query.try_subtype(self.ctx) {
+            Some(QueryType::Selector) => ("selector", "q", query.index - layout.selectors.start),
+            Some(QueryType::Fixed) => ("fixed", "f", query.index - layout.fixed.start),
+            Some(QueryType::Advice) => ("advice", "a", query.index - layout.advice.start),
+            Some(QueryType::Lookup) => ("lookup", "l", query.index - layout.lookups.start),
+            Some(QueryType::Instance) => ("instance", "p", query.index - layout.instance.start),
+            None => ("x", "x", query.index),
+        };
+
+        let rotation = query.rotation.0;
+        match (self.latex, rotation.cmp(&0)) {
+            (false, Ordering::Equal) => format!("{text}_{index}"),
+            (false, Ordering::Less) => format!("{text}_{index}[{rotation}]"),
+            (false, Ordering::Greater) => format!("{text}_{index}[+{rotation}]"),
+            (true, Ordering::Equal) => format!("{latex}_{{{index}}}"),
+            (true, Ordering::Less) => format!("{latex}_{{{index}}}^{{({rotation})}}"),
+            (true, Ordering::Greater) => format!("{latex}_{{{index}}}^{{(+{rotation})}}"),
+        }
+    }
+
+    fn write(&self, expr: &Expression<F>, f: &mut fmt::Formatter) -> fmt::Result {
+        let mul = if self.latex { " \\cdot " } else { " * " };
+        let write_factor = |expr: &Expression<F>, f: &mut fmt::Formatter| {
+            if let Expression::Sum(_, _) = expr {
+                write!(f, "(")?;
+                self.write(expr, f)?;
+                write!(f, ")")
+            } else {
+                self.write(expr, f)
+            }
+        };
+
+        match expr {
+            Expression::Constant(c) => write!(f, "{}", trim_leading_zeros(format!("{c:?}"))),
+            Expression::Polynomial(query) => write!(f, "{}", self.variable(query)),
+            Expression::Challenge(index) if *index == self.ctx.num_challenges => write!(f, "y"),
+            Expression::Challenge(index) if *index == self.ctx.num_challenges + 1 => {
+                write!(f, "u")
+            }
+            Expression::Challenge(index) if self.latex => write!(f, "r_{{{index}}}"),
+            Expression::Challenge(index) => write!(f, "r_{index}"),
+            Expression::Negated(a) => {
+                write!(f, "-")?;
+                write_factor(a, f)
+            }
+            Expression::Sum(lhs, rhs) => {
+                self.write(lhs, f)?;
+                if let Expression::Negated(b) = &**rhs {
+                    write!(f, " - ")?;
+                    write_factor(b, f)
+                } else {
+                    write!(f, " + ")?;
+                    self.write(rhs, f)
+                }
+            }
+            Expression::Product(lhs, rhs) => {
+                write_factor(lhs, f)?;
+                write!(f, "{mul}")?;
+                write_factor(rhs, f)
+            }
+            Expression::Scaled(a, k) => {
+                write!(f, "{}{mul}", trim_leading_zeros(format!("{k:?}")))?;
+                write_factor(a, f)
+            }
+        }
+    }
+}
+
+impl<F: PrimeField> Display for NamedExpression<'_, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(self.expr, f)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Default)]
 pub struct HomogeneousExpression<F: PrimeField> {
     pub expr: Expression<F>,
@@ -515,7 +850,7 @@ pub fn challenge_in_degree<F: PrimeField>(
 
 #[cfg(test)]
 mod tests {
-    use std::array;
+    use std::{array, collections::BTreeMap};
 
     use ff::PrimeField;
     // use pasta_curves::{Fp, pallas};
@@ -526,6 +861,128 @@ mod tests {
 
     use super::super::expression::*;
 
+    #[test]
+    fn simplify() {
+        let x = |index| {
+            Expression::<Fp>::Polynomial(Query {
+                index,
+                rotation: Rotation(0),
+            })
+        };
+        let c = |value: u64| Expression::Constant(Fp::from(value));
+
+        // ((x0 + 0) * 1 + 2 * 3) * (x1 * 0 + 1) = x0 + 6
+        let expr = ((x(0) + c(0)) * c(1) + c(2) * c(3)) * (x(1) * c(0) + c(1));
+        let simplified = expr.simplify();
+        assert_eq!(simplified, x(0) + c(6));
+        assert!(simplified.num_products() < expr.num_products());
+
+        // already simple expression isn't changed
+        let expr = x(0) * x(1) - x(2);
+        assert_eq!(expr.simplify(), expr);
+    }
+
+    #[test]
+    fn canonical_bytes() {
+        let x = |index, rotation| {
+            Expression::<Fp>::Polynomial(Query {
+                index,
+                rotation: Rotation(rotation),
+            })
+        };
+
+        let expr = x(0, 0) * x(1, 0) - x(2, 0);
+        assert_eq!(expr.canonical_bytes(), expr.clone().canonical_bytes());
+
+        // operands order, rotation & constants are all distinguished
+        assert_ne!(
+            (x(0, 0) + x(1, 0)).canonical_bytes(),
+            (x(1, 0) + x(0, 0)).canonical_bytes()
+        );
+        assert_ne!(x(0, 0).canonical_bytes(), x(0, -1).canonical_bytes());
+        assert_ne!(
+            Expression::Constant(Fp::from(2)).canonical_bytes(),
+            Expression::Constant(Fp::from(3)).canonical_bytes()
+        );
+    }
+
+    #[test]
+    fn variable_degrees() {
+        let x = |index| {
+            Expression::<Fp>::Polynomial(Query {
+                index,
+                rotation: Rotation(0),
+            })
+        };
+        let var = |column_index| ColumnIndex::Polynominal {
+            rotation: 0,
+            column_index,
+        };
+
+        // x0^2 * x1 * r0 + x1^3 - 5
+        let expr = x(0) * x(0) * x(1) * Expression::Challenge(0) + x(1) * x(1) * x(1)
+            - Expression::Constant(Fp::from(5));
+        assert_eq!(
+            expr.variable_degrees(),
+            BTreeMap::from([
+                (var(0), 2),
+                (var(1), 3),
+                (ColumnIndex::Challenge { column_index: 0 }, 1),
+            ])
+        );
+    }
+
+    #[test]
+    fn named() {
+        let ctx = QueryIndexContext {
+            num_selectors: 1,
+            num_fixed: 1,
+            num_advice: 2,
+            num_challenges: 1,
+            num_lookups: 0,
+            num_instance: 0,
+        };
+        let query = |index, rotation| {
+            Expression::<Fp>::Polynomial(Query {
+                index,
+                rotation: Rotation(rotation),
+            })
+        };
+
+        // selector_0 * (advice_0 + fixed_0 - advice_1[+1]) * r_0
+        let expr =
+            query(0, 0) * (query(2, 0) + query(1, 0) - query(3, 1)) * Expression::Challenge(0);
+        assert_eq!(
+            expr.named(&ctx).to_string(),
+            "selector_0 * (advice_0 + fixed_0 - advice_1[+1]) * r_0"
+        );
+        assert_eq!(
+            expr.to_latex(&ctx),
+            "q_{0} \\cdot (a_{0} + f_{0} - a_{1}^{(+1)}) \\cdot r_{0}"
+        );
+
+        // combining challenge & homogenizing variable follow challenges of `ctx`
+        let expr = query(2, 0) * Expression::Challenge(1) + query(3, 0) * Expression::Challenge(2);
+        assert_eq!(expr.named(&ctx).to_string(), "advice_0 * y + advice_1 * u");
+
+        // query out of layout doesn't panic
+        assert_eq!(query(7, -1).named(&ctx).to_string(), "x_7[-1]");
+    }
+
+    #[test]
+    fn num_monomials() {
+        let x = |index| {
+            Expression::<Fp>::Polynomial(Query {
+                index,
+                rotation: Rotation(0),
+            })
+        };
+
+        // (x0 + x1) * (x2 - 1) * 3 + x3 = x0*x2*3 - x0*3 + x1*x2*3 - x1*3 + x3
+        let expr = (x(0) + x(1)) * (x(2) - Expression::Constant(Fp::from(1))) * Fp::from(3) + x(3);
+        assert_eq!(expr.num_monomials(), 5);
+    }
+
     #[traced_test]
     #[test]
     fn test_expression() {
@@ -603,4 +1060,65 @@ mod tests {
             "((Z_0 * r_0 + Z_0 * Z_1) * r_0 + Z_0 * Z_1 * Z_2) * r_0 * r_0 + Z_0 * Z_1 * Z_2 * Z_3 * Z_4"
         );
     }
+
+    #[test]
+    fn column_index_layout() {
+        let ctx = QueryIndexContext {
+            num_selectors: 2,
+            num_fixed: 3,
+            num_advice: 4,
+            num_challenges: 1,
+            num_lookups: 1,
+            num_instance: 1,
+        };
+
+        let layout = ctx.column_index_layout();
+        assert_eq!(
+            layout,
+            ColumnIndexLayout {
+                selectors: 0..2,
+                fixed: 2..5,
+                advice: 5..9,
+                lookups: 9..14,
+                instance: 14..15,
+            }
+        );
+        assert_eq!(ctx.num_fold_vars(), 4 + 5 + 1);
+
+        let subtype = |index| {
+            Query {
+                index,
+                rotation: Rotation(0),
+            }
+            .subtype(&ctx)
+        };
+        assert!(layout
+            .selectors
+            .all(|i| matches!(subtype(i), QueryType::Selector)));
+        assert!(layout.fixed.all(|i| matches!(subtype(i), QueryType::Fixed)));
+        assert!(layout
+            .advice
+            .all(|i| matches!(subtype(i), QueryType::Advice)));
+        assert!(layout
+            .lookups
+            .all(|i| matches!(subtype(i), QueryType::Lookup)));
+        assert!(layout
+            .instance
+            .all(|i| matches!(subtype(i), QueryType::Instance)));
+    }
+
+    #[test]
+    fn as_constant() {
+        let a = Expression::<Fp>::Polynomial(Query {
+            index: 0,
+            rotation: Rotation(0),
+        });
+        let c = |value: u64| Expression::Constant(Fp::from(value));
+
+        assert_eq!((c(2) * c(3) - c(1)).as_constant(), Some(Fp::from(5)));
+        assert_eq!((a.clone() * c(0) + c(7)).as_constant(), Some(Fp::from(7)));
+        assert_eq!((a.clone() * Fp::from(0)).as_constant(), Some(Fp::from(0)));
+        assert_eq!((a.clone() + c(1)).as_constant(), None);
+        assert_eq!((Expression::Challenge(0) * c(2)).as_constant(), None);
+    }
 }
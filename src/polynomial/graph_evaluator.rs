@@ -40,6 +40,8 @@
 ///
 /// It is an adaptation for our needs of the [code from
 /// halo2](https://github.com/privacy-scaling-explorations/halo2/blob/main/halo2_backend/src/plonk/evaluation.rs#L200)
+use std::ops::Range;
+
 use ff::PrimeField;
 use halo2_proofs::poly::Rotation;
 use tracing::*;
@@ -49,6 +51,9 @@ use crate::plonk::eval::{Error as EvalError, GetDataForEval};
 use super::Expression;
 
 /// Return the index in the polynomial of size `isize` after rotation `rot`.
+///
+/// Rotations wrap around, e.g. `Rotation(-1)` at row `0` is the last row. Rotations by the whole
+/// table or more are rejected while building the structure
 fn get_rotation_idx(idx: usize, rot: i32, num_row: usize) -> usize {
     (((idx as i32) + rot).rem_euclid(num_row as i32)) as usize
 }
@@ -364,7 +369,27 @@ impl<F: PrimeField> GraphEvaluator<F> {
         getter: &impl GetDataForEval<F>,
         row_index: usize,
     ) -> Result<F, EvalError> {
+        self.evaluate_with(&mut self.instance(), getter, row_index)
+    }
+
+    /// Same as [`GraphEvaluator::evaluate`] for each row of `rows`, but evaluation buffers are
+    /// allocated once for the whole range
+    pub fn evaluate_batch(
+        &self,
+        getter: &impl GetDataForEval<F>,
+        rows: Range<usize>,
+    ) -> Result<Vec<F>, EvalError> {
         let mut data = self.instance();
+        rows.map(|row_index| self.evaluate_with(&mut data, getter, row_index))
+            .collect()
+    }
+
+    fn evaluate_with(
+        &self,
+        data: &mut EvaluationData<F>,
+        getter: &impl GetDataForEval<F>,
+        row_index: usize,
+    ) -> Result<F, EvalError> {
         // All rotation index values
         for (rot_idx, rot) in self.rotations.iter().enumerate() {
             data.rotations[rot_idx] = get_rotation_idx(row_index, *rot, getter.row_size());
@@ -633,4 +658,53 @@ mod tests {
             Ok((advice00 + advice01 + advice01) * (fixed00 + advice00))
         );
     }
+
+    #[traced_test]
+    #[test]
+    fn evaluate_batch() {
+        const N: usize = 16;
+        let mut rnd = rand::thread_rng();
+        let random_column = |rnd: &mut rand::rngs::ThreadRng| {
+            (0..N)
+                .map(|_| Scalar::random(&mut *rnd))
+                .collect::<Vec<_>>()
+        };
+
+        let data = Mock {
+            advice: vec![random_column(&mut rnd), random_column(&mut rnd)],
+            fixed: vec![random_column(&mut rnd)],
+            selectors: vec![(0..N).map(|row| row % 3 == 0).collect()],
+            ..Default::default()
+        };
+
+        let query = |index, rotation| {
+            Box::new(Expression::Polynomial::<Scalar>(Query {
+                index,
+                rotation: Rotation(rotation),
+            }))
+        };
+        // s * (a0 * f - a1[next]) + a0^2
+        let expr = Expression::Sum(
+            Box::new(Expression::Product(
+                query(0, 0),
+                Box::new(Expression::Sum(
+                    Box::new(Expression::Product(query(2, 0), query(1, 0))),
+                    Box::new(Expression::Negated(query(3, 1))),
+                )),
+            )),
+            Box::new(Expression::Product(query(2, 0), query(2, 0))),
+        );
+        let evaluator = GraphEvaluator::new(&expr);
+
+        let expected = (0..N)
+            .map(|row| evaluator.evaluate(&data, row))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(evaluator.evaluate_batch(&data, 0..N), Ok(expected.clone()));
+        assert_eq!(
+            evaluator.evaluate_batch(&data, 3..11),
+            Ok(expected[3..11].to_vec())
+        );
+        assert_eq!(evaluator.evaluate_batch(&data, 5..5), Ok(vec![]));
+    }
 }
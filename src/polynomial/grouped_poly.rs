@@ -106,6 +106,10 @@ impl<F: PrimeField> GroupedPoly<F> {
                         index: ctx.shift_lookup_index(poly.index),
                         rotation: poly.rotation,
                     }))),
+                    QueryType::Instance => terms.push(Some(Expression::Polynomial(Query {
+                        index: ctx.shift_instance_index(poly.index),
+                        rotation: poly.rotation,
+                    }))),
                     _other => (),
                 }
 
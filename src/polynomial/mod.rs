@@ -5,5 +5,7 @@ pub mod lagrange;
 pub mod sparse;
 pub mod univariate;
 
-pub use expression::{ColumnIndex, Expression, Query, QueryType};
+pub use expression::{
+    ColumnIndex, ColumnIndexLayout, Expression, Halo2ExprError, NamedExpression, Query, QueryType,
+};
 pub use lagrange::iter_eval_lagrange_polynomials_for_cyclic_group;
@@ -109,6 +109,15 @@ impl<F: PrimeField + PrimeFieldBits, const T: usize, const RATE: usize> Poseidon
         assert!(inputs.len() <= RATE);
         let s_val = state[state_idx].value().copied();
 
+        // constant input is added to `rc`, so it can't be changed by prover
+        let constant = state_idx
+            .checked_sub(1)
+            .and_then(|index| inputs.get(index))
+            .and_then(|input| match input {
+                WrapValue::Constant(value) => Some(*value),
+                _ => None,
+            });
+
         // TODO: add copy constraint
         let inputs = std::iter::once(Value::known(F::ZERO))
             .chain(inputs.into_iter().map(|v| v.value()))
@@ -116,11 +125,13 @@ impl<F: PrimeField + PrimeFieldBits, const T: usize, const RATE: usize> Poseidon
             .chain(std::iter::repeat(Value::known(F::ZERO)))
             .take(T)
             .collect::<Vec<_>>();
-        let input_val = inputs[state_idx];
 
         let constants = self.spec.constants().start();
         let pre_constants = constants[0];
-        let rc_val = pre_constants[state_idx];
+        let (input_val, rc_val) = match constant {
+            Some(constant) => (Value::known(F::ZERO), pre_constants[state_idx] + constant),
+            None => (inputs[state_idx], pre_constants[state_idx]),
+        };
 
         let out_val = s_val + input_val + Value::known(rc_val);
 
@@ -131,17 +142,19 @@ impl<F: PrimeField + PrimeFieldBits, const T: usize, const RATE: usize> Poseidon
         )?;
         ctx.constrain_equal(state[state_idx].cell(), si.cell())?;
 
-        ctx.assign_advice(
-            || "pre_round: input",
-            self.main_gate.config().input,
-            input_val,
-        )?;
+        if constant.is_none() {
+            ctx.assign_advice(
+                || "pre_round: input",
+                self.main_gate.config().input,
+                input_val,
+            )?;
+            ctx.assign_fixed(|| "pre_round: q_i", self.main_gate.config().q_i, F::ONE)?;
+        }
         ctx.assign_fixed(
             || "pre_round: q_1",
             self.main_gate.config().q_1[state_idx],
             F::ONE,
         )?;
-        ctx.assign_fixed(|| "pre_round: q_i", self.main_gate.config().q_i, F::ONE)?;
         ctx.assign_fixed(|| "pre_round: q_o", self.main_gate.config().q_o, -F::ONE)?;
         ctx.assign_fixed(|| "pre_round: rc", self.main_gate.config().rc, rc_val)?;
         let out = ctx.assign_advice(|| "pre_round: out", self.main_gate.config().out, out_val)?;
@@ -535,4 +548,20 @@ mod tests {
 
         run_mock_prover_test!(K, circuit, public_inputs);
     }
+
+    #[test]
+    fn constant_inputs() {
+        const K: u32 = 10;
+        let mut circuit = TestCircuit::new(
+            (0..5).map(|i| Fp::from(i as u64)).collect(),
+            NonZeroUsize::new(128).unwrap(),
+        );
+        // same hash as in `test_mock`, when some of inputs are pinned by fixed column
+        circuit.inputs[1] = WrapValue::Constant(Fp::from(1));
+        circuit.inputs[4] = WrapValue::Constant(Fp::from(4));
+        let out_hash = Fp::from_str_vartime("277726250230731218669330566268314254439").unwrap();
+        let public_inputs = vec![vec![out_hash]];
+
+        run_mock_prover_test!(K, circuit, public_inputs);
+    }
 }
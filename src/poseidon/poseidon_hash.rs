@@ -222,7 +222,10 @@ where
 
 #[cfg(test)]
 mod tests {
-    use halo2curves::pasta::{EpAffine, Fp, Fq};
+    use halo2curves::{
+        group::prime::PrimeCurveAffine,
+        pasta::{EpAffine, Fp, Fq},
+    };
     use tracing_test::traced_test;
 
     use super::*;
@@ -246,4 +249,35 @@ mod tests {
             Fq::from_str_vartime("277726250230731218669330566268314254439").unwrap()
         );
     }
+
+    #[test]
+    fn absorb_field_slice_separation() {
+        type PH = PoseidonHash<<EpAffine as CurveAffine>::Base, 3, 2>;
+
+        let [a, b, c] = [1u64, 2, 3].map(Fp::from);
+        let new_ph = || PH::new(Spec::<Fp, 3, 2>::new(4, 3));
+        let num_bits = NonZeroUsize::new(128).unwrap();
+
+        let split = new_ph()
+            .absorb_field_slice(&[a, b])
+            .absorb_field_slice(&[c])
+            .squeeze::<EpAffine>(num_bits);
+        let joined = new_ph()
+            .absorb_field_slice(&[a, b, c])
+            .squeeze::<EpAffine>(num_bits);
+        assert_ne!(split, joined);
+
+        let points = [
+            EpAffine::generator(),
+            (EpAffine::generator() * Fq::from(2)).into(),
+        ];
+        let split = new_ph()
+            .absorb_point_slice(&points[..1])
+            .absorb_point_slice(&points[1..])
+            .squeeze::<EpAffine>(num_bits);
+        let joined = new_ph()
+            .absorb_point_slice(&points)
+            .squeeze::<EpAffine>(num_bits);
+        assert_ne!(split, joined);
+    }
 }
@@ -57,6 +57,23 @@ pub trait ROTrait<F: PrimeField> {
         self
     }
 
+    /// Adds the length of `bases` followed by its elements to the internal state
+    ///
+    /// The length prefix separates consecutive slices, so `[a, b]` followed by `[c]` is not
+    /// absorbed the same way as `[a, b, c]`
+    fn absorb_field_slice(&mut self, bases: &[F]) -> &mut Self {
+        self.absorb_field(F::from(bases.len() as u64))
+            .absorb_field_iter(bases.iter().copied())
+    }
+
+    /// Adds the length of `points` followed by its elements to the internal state
+    ///
+    /// See [`ROTrait::absorb_field_slice`] for details on the length prefix
+    fn absorb_point_slice<C: CurveAffine<Base = F>>(&mut self, points: &[C]) -> &mut Self {
+        self.absorb_field(F::from(points.len() as u64))
+            .absorb_point_iter(points.iter())
+    }
+
     fn inspect(&mut self, scan: impl FnOnce(&[F])) -> &mut Self;
 
     /// Returns a challenge by hashing the internal state
@@ -96,6 +113,15 @@ pub trait ROCircuitTrait<F: PrimeFieldBits + FromUniformBytes<64>> {
         self
     }
 
+    /// Adds length of the slice, that is absorbed next, to the internal state
+    ///
+    /// On-circuit counterpart of the length prefix of [`ROTrait::absorb_field_slice`] &
+    /// [`ROTrait::absorb_point_slice`]. Length is defined by the circuit, so it's absorbed as
+    /// [`WrapValue::Constant`] & pinned by fixed column, prover can't replace it
+    fn absorb_len(&mut self, len: usize) -> &mut Self {
+        self.absorb_base(WrapValue::Constant(F::from(len as u64)))
+    }
+
     fn inspect(&mut self, scan: impl FnOnce(&[F])) -> &mut Self;
 
     /// Returns a challenge of `num_bits` by hashing the internal state
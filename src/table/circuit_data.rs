@@ -1,4 +1,6 @@
-use ff::PrimeField;
+use std::collections::HashMap;
+
+use ff::{Field, PrimeField};
 use halo2_proofs::{
     circuit::Value,
     plonk::{
@@ -9,12 +11,34 @@ use tracing::*;
 
 use crate::plonk;
 
+/// Names of columns, collected from [`Assignment::annotate_column`] calls during synthesize
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnAnnotations(HashMap<Column<Any>, String>);
+
+impl ColumnAnnotations {
+    pub fn column_name(&self, column: impl Into<Column<Any>>) -> Option<&str> {
+        self.0.get(&column.into()).map(String::as_str)
+    }
+}
+
+/// Rejects rational fixed value with zero denominator, which would be silently evaluated as zero
+fn non_zero_denominator<F: PrimeField>(value: Assigned<F>) -> Result<Assigned<F>, Error> {
+    match value {
+        Assigned::Rational(_, denominator) if bool::from(denominator.is_zero()) => {
+            error!("fixed value with zero denominator: {value:?}");
+            Err(Error::Synthesis)
+        }
+        value => Ok(value),
+    }
+}
+
 pub struct CircuitData<F: PrimeField> {
     pub(crate) k: u32,
     pub(crate) num_io: usize,
     pub(crate) fixed: Vec<Vec<Assigned<F>>>,
     pub(crate) selector: Vec<Vec<bool>>,
     pub(crate) permutation: plonk::permutation::Assembly,
+    pub(crate) annotations: ColumnAnnotations,
 }
 
 impl<F: PrimeField> Assignment<F> for CircuitData<F> {
@@ -35,16 +59,23 @@ impl<F: PrimeField> Assignment<F> for CircuitData<F> {
         A: FnOnce() -> AR,
         AR: Into<String>,
     {
-        self.selector[selector.index()][row] = true;
+        *self
+            .selector
+            .get_mut(selector.index())
+            .and_then(|v| v.get_mut(row))
+            .ok_or_else(|| {
+                error!("Error while enable selector {selector:?} at row {row}");
+                Error::BoundsFailure
+            })? = true;
         Ok(())
     }
 
-    fn annotate_column<A, AR>(&mut self, _annotation: A, _column: Column<Any>)
+    fn annotate_column<A, AR>(&mut self, annotation: A, column: Column<Any>)
     where
         A: FnOnce() -> AR,
         AR: Into<String>,
     {
-        // Do nothing
+        self.annotations.0.insert(column, annotation().into());
     }
 
     fn query_instance(&self, column: Column<Instance>, row: usize) -> Result<Value<F>, Error> {
@@ -86,6 +117,8 @@ impl<F: PrimeField> Assignment<F> for CircuitData<F> {
         A: FnOnce() -> AR,
         AR: Into<String>,
     {
+        let value = non_zero_denominator(to().into_field().assign()?)?;
+
         *self
             .fixed
             .get_mut(column.index())
@@ -96,7 +129,7 @@ impl<F: PrimeField> Assignment<F> for CircuitData<F> {
                     annotation().into()
                 );
                 Error::BoundsFailure
-            })? = to().into_field().assign()?;
+            })? = value;
         Ok(())
     }
 
@@ -113,10 +146,21 @@ impl<F: PrimeField> Assignment<F> for CircuitData<F> {
 
     fn fill_from_row(
         &mut self,
-        _: Column<Fixed>,
-        _: usize,
-        _: Value<Assigned<F>>,
+        column: Column<Fixed>,
+        from_row: usize,
+        to: Value<Assigned<F>>,
     ) -> Result<(), Error> {
+        let value = non_zero_denominator(to.assign()?)?;
+
+        self.fixed
+            .get_mut(column.index())
+            .and_then(|v| v.get_mut(from_row..))
+            .ok_or_else(|| {
+                error!("Error while fill fixed column {column:?} from row {from_row}");
+                Error::BoundsFailure
+            })?
+            .fill(value);
+
         Ok(())
     }
 
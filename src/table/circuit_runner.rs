@@ -1,32 +1,125 @@
-use ff::PrimeField;
-use halo2_proofs::plonk::{Circuit, ConstraintSystem, Error, FloorPlanner};
+use std::{collections::BTreeSet, ops::RangeInclusive};
+
+use ff::{FromUniformBytes, PrimeField};
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    dev::{MockProver, VerifyFailure},
+    plonk::{Any, Circuit, Column, ConstraintSystem, Error, FloorPlanner},
+};
 use tracing::*;
 
 use crate::{
+    commitment::CommitmentKey,
     plonk::{self, PlonkStructure},
-    polynomial::sparse::SparseMatrix,
-    util::batch_invert_assigned,
+    polynomial::{sparse::SparseMatrix, ColumnIndex, Expression, Halo2ExprError},
+    poseidon::ROTrait,
+    util::evaluate_assigned_matrix,
 };
 
-use super::{circuit_data::CircuitData, ConstraintSystemMetainfo, WitnessCollector};
+use super::{
+    circuit_data::{CircuitData, ColumnAnnotations},
+    ConstantsConfig, ConstraintSystemMetainfo, WitnessCollector,
+};
 
 pub type Witness<F> = Vec<Vec<F>>;
 
+#[derive(Debug, thiserror::Error)]
+pub enum StructureError {
+    #[error(transparent)]
+    Plonk(#[from] Error),
+    #[error("Gate degree {degree} exceeds maximum allowed {max_gate_degree}")]
+    GateDegreeExceeded {
+        degree: usize,
+        max_gate_degree: usize,
+    },
+    #[error("Circuit `configure` isn't deterministic: count of {what} changed from {expected} to {actual}")]
+    UnstableConfigure {
+        what: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Rotation {rotation} is out of bounds of table with {num_rows} rows")]
+    RotationOutOfBounds { rotation: i32, num_rows: usize },
+    #[error("`{name}` queries {kind}, which isn't supported in gates & lookups")]
+    UnsupportedQuery { name: String, kind: &'static str },
+    #[error(transparent)]
+    Halo2Expr(#[from] Halo2ExprError),
+}
+
+/// Discrepancy between halo2 [`MockProver`] and [`PlonkStructure::is_sat`] verdicts, see
+/// [`CircuitRunner::cross_check_mock`]
+#[derive(Debug)]
+pub enum Mismatch {
+    /// Structure or witness can't be collected, so there is nothing to compare with
+    Synthesis(Error),
+    /// [`MockProver`] accepts the circuit, but [`PlonkStructure::is_sat`] rejects it
+    RejectedByIsSat(plonk::Error),
+    /// [`PlonkStructure::is_sat`] accepts the circuit, but [`MockProver`] rejects it
+    RejectedByMockProver(VerifyFailure),
+}
+
+/// Read-only copy of evaluated circuit table: fixed, selector, advice & instance cells
+///
+/// All [`halo2_proofs::plonk::Assigned`] cells are already evaluated, so it can be rendered by
+/// inspection tools as is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableSnapshot<F: PrimeField> {
+    fixed: Vec<Vec<F>>,
+    selectors: Vec<Vec<bool>>,
+    advice: Witness<F>,
+    instance: Vec<F>,
+}
+
+impl<F: PrimeField> TableSnapshot<F> {
+    pub fn fixed(&self) -> &[Vec<F>] {
+        &self.fixed
+    }
+
+    pub fn selectors(&self) -> &[Vec<bool>] {
+        &self.selectors
+    }
+
+    pub fn advice(&self) -> &[Vec<F>] {
+        &self.advice
+    }
+
+    pub fn instance(&self) -> &[F] {
+        &self.instance
+    }
+
+    /// Value of cell at `column` & `row`, `None` if it is out of table
+    ///
+    /// Only first instance column is supported, same as in [`CircuitRunner`]
+    pub fn cell(&self, column: impl Into<Column<Any>>, row: usize) -> Option<F> {
+        let column = column.into();
+        match column.column_type() {
+            Any::Advice(_) => self.advice.get(column.index())?.get(row).copied(),
+            Any::Fixed => self.fixed.get(column.index())?.get(row).copied(),
+            Any::Instance if column.index() == 0 => self.instance.get(row).copied(),
+            Any::Instance => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CircuitRunner<F: PrimeField, CT: Circuit<F>> {
     pub(crate) k: u32,
     pub(crate) circuit: CT,
     pub(crate) cs: ConstraintSystem<F>,
     pub(crate) config: CT::Config,
+    pub(crate) constants: ConstantsConfig,
     pub(crate) instance: Vec<F>,
 }
 
 impl<F: PrimeField, CT: Circuit<F>> CircuitRunner<F, CT> {
     pub fn new(k: u32, circuit: CT, instance: Vec<F>) -> Self {
         let mut cs = ConstraintSystem::default();
+        let config = CT::configure(&mut cs);
+        let constants = ConstantsConfig::configure(&mut cs);
 
         CircuitRunner {
-            config: CT::configure(&mut cs),
+            config,
+            constants,
             k,
             circuit,
             cs,
@@ -34,23 +127,222 @@ impl<F: PrimeField, CT: Circuit<F>> CircuitRunner<F, CT> {
         }
     }
 
+    /// Same as [`CircuitRunner::new`], but picks minimal `k` from `k_range`, for which the
+    /// circuit fits into table
+    ///
+    /// Synthesis retried with increasing `k` while it fails with [`Error::BoundsFailure`], the
+    /// chosen `k` available via [`CircuitRunner::k`]. If the circuit doesn't fit even into
+    /// `k_range.end()` rows, [`Error::NotEnoughRowsAvailable`] returned
+    pub fn new_with_auto_k(
+        k_range: RangeInclusive<u32>,
+        circuit: CT,
+        instance: Vec<F>,
+    ) -> Result<Self, Error> {
+        let max_k = *k_range.end();
+        let mut runner = Self::new(*k_range.start(), circuit, instance);
+
+        for k in k_range {
+            runner.k = k;
+
+            match runner
+                .try_collect_preprocessing()
+                .and_then(|_| runner.try_collect_witness())
+            {
+                Ok(_) => return Ok(runner),
+                Err(Error::BoundsFailure) => debug!("circuit doesn't fit into k = {k}"),
+                Err(err) => return Err(err),
+            }
+        }
+
+        Err(Error::NotEnoughRowsAvailable { current_k: max_k })
+    }
+
+    pub fn k(&self) -> u32 {
+        self.k
+    }
+
     #[instrument(name = "circuit_collect_plonk_struct", skip_all)]
     pub fn try_collect_plonk_structure(&self) -> Result<PlonkStructure<F>, Error> {
+        self.check_configure_stable().map_err(|err| {
+            error!("{err}");
+            Error::Synthesis
+        })?;
+
+        self.check_queries().map_err(|err| {
+            error!("{err}");
+            Error::Synthesis
+        })?;
+
         debug!("start build metainfo");
+        let metainfo =
+            ConstraintSystemMetainfo::build(self.k as usize, &self.cs).map_err(|err| {
+                error!("{err}");
+                Error::Synthesis
+            })?;
+        debug!("meta info is ready");
+
+        self.check_rotations(&metainfo).map_err(|err| {
+            error!("{err}");
+            Error::Synthesis
+        })?;
+
+        self.collect_plonk_structure(metainfo)
+    }
+
+    /// Runs `configure` of circuit once again & checks, that it allocates the same columns,
+    /// gates & lookups as the first call in [`CircuitRunner::new`]
+    ///
+    /// Structure & witness of the circuit are sized by the first call, so non-deterministic
+    /// `configure` leads to mismatch of structures of the same circuit
+    fn check_configure_stable(&self) -> Result<(), StructureError> {
+        let mut cs = ConstraintSystem::default();
+        let _ = CT::configure(&mut cs);
+        let _ = ConstantsConfig::configure(&mut cs);
+
+        [
+            (
+                "fixed columns",
+                self.cs.num_fixed_columns(),
+                cs.num_fixed_columns(),
+            ),
+            (
+                "advice columns",
+                self.cs.num_advice_columns(),
+                cs.num_advice_columns(),
+            ),
+            (
+                "instance columns",
+                self.cs.num_instance_columns(),
+                cs.num_instance_columns(),
+            ),
+            ("selectors", self.cs.num_selectors(), cs.num_selectors()),
+            ("challenges", self.cs.num_challenges(), cs.num_challenges()),
+            ("gates", self.cs.gates().len(), cs.gates().len()),
+            ("lookups", self.cs.lookups().len(), cs.lookups().len()),
+        ]
+        .into_iter()
+        .find(|(_, expected, actual)| expected != actual)
+        .map_or(Ok(()), |(what, expected, actual)| {
+            Err(StructureError::UnstableConfigure {
+                what,
+                expected,
+                actual,
+            })
+        })
+    }
+
+    /// Checks, that gates don't query halo2 challenges & lookups don't query instance column or
+    /// halo2 challenges
+    ///
+    /// They can't be converted by [`crate::polynomial::Expression::from_halo2_expr`]: lookups are
+    /// evaluated over advice only & values of halo2 challenges aren't folded
+    fn check_queries(&self) -> Result<(), StructureError> {
+        let num_instance = self.cs.num_instance_columns();
+        let gates = self.cs.gates().iter().flat_map(move |gate| {
+            gate.polynomials()
+                .iter()
+                .map(move |expr| (gate.name().to_string(), expr, num_instance))
+        });
+        let lookups = self
+            .cs
+            .lookups()
+            .iter()
+            .enumerate()
+            .flat_map(|(index, lookup)| {
+                lookup
+                    .input_expressions()
+                    .iter()
+                    .chain(lookup.table_expressions().iter())
+                    .map(move |expr| (format!("lookup {index}"), expr, 0))
+            });
+
+        gates
+            .chain(lookups)
+            .find_map(|(name, expr, num_instance)| {
+                Expression::unsupported_halo2_query(expr, num_instance)
+                    .map(|kind| StructureError::UnsupportedQuery { name, kind })
+            })
+            .map_or(Ok(()), Err)
+    }
+
+    /// Checks, that each rotation queried by gates & lookups is less than count of rows by
+    /// absolute value
+    ///
+    /// Rotations wrap around the table, i.e. `Rotation(-1)` at row `0` queries the last row, see
+    /// [`crate::polynomial::graph_evaluator::GraphEvaluator`]. Rotation by whole table or more
+    /// is most likely a bug of circuit, so it's rejected instead of being silently wrapped
+    fn check_rotations(
+        &self,
+        metainfo: &ConstraintSystemMetainfo<F>,
+    ) -> Result<(), StructureError> {
+        let num_rows = 1usize << self.k;
+
+        let mut queries = BTreeSet::new();
+        metainfo
+            .custom_gates_lookup_compressed
+            .compressed()
+            .poly_set(&mut queries);
+
+        queries
+            .into_iter()
+            .find_map(|query| match query {
+                ColumnIndex::Polynominal { rotation, .. }
+                    if rotation.unsigned_abs() as usize >= num_rows =>
+                {
+                    Some(StructureError::RotationOutOfBounds { rotation, num_rows })
+                }
+                _ => None,
+            })
+            .map_or(Ok(()), Err)
+    }
+
+    /// Same as [`CircuitRunner::try_collect_plonk_structure`], but fails before preprocessing if
+    /// degree of homogeneous compressed gates is greater than `max_gate_degree`
+    ///
+    /// This degree defines count of cross terms, see [`PlonkStructure::num_cross_terms`]
+    #[instrument(name = "circuit_collect_plonk_struct_with_max_degree", skip_all)]
+    pub fn try_collect_plonk_structure_with_max_degree(
+        &self,
+        max_gate_degree: Option<usize>,
+    ) -> Result<PlonkStructure<F>, StructureError> {
+        self.check_configure_stable()?;
+
+        self.check_queries()?;
+
+        let metainfo = ConstraintSystemMetainfo::build(self.k as usize, &self.cs)?;
+        self.check_rotations(&metainfo)?;
+
+        let degree = metainfo.folding_degree.saturating_sub(1);
+        if let Some(max_gate_degree) = max_gate_degree.filter(|max| degree > *max) {
+            return Err(StructureError::GateDegreeExceeded {
+                degree,
+                max_gate_degree,
+            });
+        }
+
+        Ok(self.collect_plonk_structure(metainfo)?)
+    }
+
+    fn collect_plonk_structure(
+        &self,
+        metainfo: ConstraintSystemMetainfo<F>,
+    ) -> Result<PlonkStructure<F>, Error> {
         let ConstraintSystemMetainfo {
             num_challenges,
             round_sizes,
             gates,
+            gate_names,
+            gate_indices,
             custom_gates_lookup_compressed,
             ..
-        } = ConstraintSystemMetainfo::build(self.k as usize, &self.cs);
-        debug!("meta info is ready");
+        } = metainfo;
 
         debug!("start preprocessing");
         let PreprocessingData {
             permutation_matrix,
             fixed_columns,
             selectors,
+            ..
         } = self.try_collect_preprocessing()?;
         debug!("preprocessing is ready");
 
@@ -60,25 +352,182 @@ impl<F: PrimeField, CT: Circuit<F>> CircuitRunner<F, CT> {
             selectors,
             fixed_columns,
             num_advice_columns: self.cs.num_advice_columns(),
+            num_instance_columns: self.cs.num_instance_columns(),
             num_challenges,
             round_sizes,
             custom_gates_lookup_compressed,
             gates,
+            gate_names,
+            gate_indices,
             permutation_matrix,
-            lookup_arguments: plonk::lookup::Arguments::compress_from(&self.cs),
+            lookup_arguments: plonk::lookup::Arguments::compress_from(&self.cs)?,
         })
     }
 
+    /// Collects names of columns, given by circuit with [`halo2_proofs::circuit::Region::name_column`]
+    pub fn try_collect_column_annotations(&self) -> Result<ColumnAnnotations, Error> {
+        Ok(self.try_collect_preprocessing()?.column_annotations)
+    }
+
+    /// Collects [`TableSnapshot`] of evaluated cells, e.g. to render the table
+    ///
+    /// ```
+    /// use halo2_proofs::{
+    ///     circuit::{Layouter, SimpleFloorPlanner, Value},
+    ///     plonk::{Advice, Circuit, Column, ConstraintSystem, Error},
+    /// };
+    /// use halo2curves::pasta::Fp;
+    /// use sirius::table::CircuitRunner;
+    ///
+    /// struct Squares;
+    ///
+    /// impl Circuit<Fp> for Squares {
+    ///     type Config = Column<Advice>;
+    ///     type FloorPlanner = SimpleFloorPlanner;
+    ///
+    ///     fn without_witnesses(&self) -> Self {
+    ///         Self
+    ///     }
+    ///
+    ///     fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+    ///         meta.advice_column()
+    ///     }
+    ///
+    ///     fn synthesize(&self, a: Self::Config, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+    ///         layouter.assign_region(
+    ///             || "squares",
+    ///             |mut region| {
+    ///                 for row in 0..4 {
+    ///                     let value = Value::known(Fp::from(row * row));
+    ///                     region.assign_advice(|| "a", a, row as usize, || value)?;
+    ///                 }
+    ///                 Ok(())
+    ///             },
+    ///         )
+    ///     }
+    /// }
+    ///
+    /// let runner = CircuitRunner::new(3, Squares, vec![]);
+    /// let snapshot = runner.try_collect_snapshot().unwrap();
+    ///
+    /// // same column, as the one allocated by circuit
+    /// let column = Squares::configure(&mut ConstraintSystem::default());
+    /// let rendered = (0..4)
+    ///     .map(|row| format!("{:?}", snapshot.cell(column, row).unwrap()))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(rendered[2], format!("{:?}", Fp::from(4)));
+    /// assert_eq!(snapshot.advice()[0][3], Fp::from(9));
+    /// ```
+    pub fn try_collect_snapshot(&self) -> Result<TableSnapshot<F>, Error> {
+        let PreprocessingData {
+            fixed_columns,
+            selectors,
+            ..
+        } = self.try_collect_preprocessing()?;
+
+        Ok(TableSnapshot {
+            fixed: fixed_columns,
+            selectors,
+            advice: self.try_collect_witness()?,
+            instance: self.instance.clone(),
+        })
+    }
+
+    /// Checks the circuit with halo2 [`MockProver`] and with [`PlonkStructure::is_sat`] & compares
+    /// verdicts
+    ///
+    /// Used to catch divergence of this crate's table collection (selectors, copy constraints,
+    /// lookups) from halo2 itself. Only satisfaction verdicts are compared: if both reject the
+    /// circuit, it's still considered as an agreement
+    ///
+    /// `ro_nark_prepare` & `ro_nark_decider` must be in the same state: the first one is used to
+    /// run special soundness protocol, the second one to verify it
+    #[instrument(name = "circuit_cross_check_mock", skip_all)]
+    pub fn cross_check_mock<C, RO>(
+        &self,
+        ck: &CommitmentKey<C>,
+        ro_nark_prepare: &mut RO,
+        ro_nark_decider: &mut RO,
+    ) -> Result<(), Vec<Mismatch>>
+    where
+        F: FromUniformBytes<64> + Ord,
+        C: CurveAffine<ScalarExt = F>,
+        RO: ROTrait<C::Base>,
+    {
+        let mock_verdict = MockProver::run(self.k, &self.circuit, vec![self.instance.clone()])
+            .map_err(|err| vec![Mismatch::Synthesis(err)])?
+            .verify();
+
+        let S = self
+            .try_collect_plonk_structure()
+            .map_err(|err| vec![Mismatch::Synthesis(err)])?;
+        let witness = self
+            .try_collect_witness()
+            .map_err(|err| vec![Mismatch::Synthesis(err)])?;
+
+        let is_sat_verdict = S
+            .run_sps_protocol(
+                ck,
+                &self.instance,
+                &witness,
+                ro_nark_prepare,
+                S.num_challenges,
+            )
+            .map_err(plonk::Error::from)
+            .and_then(|(U, W)| S.is_sat(ck, ro_nark_decider, &U, &W));
+
+        match (mock_verdict, is_sat_verdict) {
+            (Ok(()), Ok(())) | (Err(_), Err(_)) => Ok(()),
+            (Ok(()), Err(err)) => Err(vec![Mismatch::RejectedByIsSat(err)]),
+            (Err(failures), Ok(())) => Err(failures
+                .into_iter()
+                .map(Mismatch::RejectedByMockProver)
+                .collect()),
+        }
+    }
+
+    /// Checks, that public inputs passed to [`CircuitRunner::new`] can be placed into table: only
+    /// single instance column is supported & it can't be longer than table
+    fn validate_instance(&self) -> Result<(), Error> {
+        if self.cs.num_instance_columns() > 1 {
+            return Err(Error::InvalidInstances);
+        }
+        if self.instance.len() > 1 << self.k {
+            return Err(Error::InstanceTooLarge);
+        }
+        Ok(())
+    }
+
     #[instrument(name = "circuit_collect_witness", skip_all)]
     pub fn try_collect_witness(&self) -> Result<Witness<F>, Error> {
+        self.validate_instance()?;
+
         let mut witness = WitnessCollector {
             instance: self.instance.clone(),
             advice: vec![vec![F::ZERO.into(); 1 << self.k]; self.cs.num_advice_columns()],
         };
 
-        CT::FloorPlanner::synthesize(&mut witness, &self.circuit, self.config.clone(), vec![])?;
+        CT::FloorPlanner::synthesize(
+            &mut witness,
+            &self.circuit,
+            self.config.clone(),
+            self.cs.constants().clone(),
+        )?;
+
+        Ok(evaluate_assigned_matrix(&witness.advice))
+    }
+
+    /// Replaces circuit & instance, keeping [`ConstraintSystem`] & config, and collects witness
+    ///
+    /// Allows to reuse one runner for several folding steps of the same circuit: `configure` isn't
+    /// re-run, so the structure collected earlier stays valid and only advice is regenerated
+    #[instrument(name = "circuit_reassign_witness", skip_all)]
+    pub fn reassign_witness(&mut self, circuit: CT, instance: Vec<F>) -> Result<Witness<F>, Error> {
+        self.circuit = circuit;
+        self.instance = instance;
 
-        Ok(batch_invert_assigned(&witness.advice))
+        self.try_collect_witness()
     }
 
     fn try_collect_preprocessing(&self) -> Result<PreprocessingData<F>, Error> {
@@ -90,14 +539,17 @@ impl<F: PrimeField, CT: Circuit<F>> CircuitRunner<F, CT> {
             fixed: vec![vec![F::ZERO.into(); nrow]; self.cs.num_fixed_columns()],
             selector: vec![vec![false; nrow]; self.cs.num_selectors()],
             permutation: plonk::permutation::Assembly::new(nrow, &self.cs.permutation),
+            annotations: ColumnAnnotations::default(),
         };
 
         CT::FloorPlanner::synthesize(
             &mut circuit_data,
             &self.circuit,
             self.config.clone(),
-            vec![],
+            self.cs.constants().clone(),
         )?;
+        self.constants
+            .assign(&self.cs, &circuit_data.permutation, &mut circuit_data.fixed)?;
 
         Ok(PreprocessingData {
             permutation_matrix: plonk::util::construct_permutation_matrix(
@@ -106,8 +558,9 @@ impl<F: PrimeField, CT: Circuit<F>> CircuitRunner<F, CT> {
                 &self.cs,
                 &circuit_data.permutation,
             ),
-            fixed_columns: batch_invert_assigned(&circuit_data.fixed),
+            fixed_columns: evaluate_assigned_matrix(&circuit_data.fixed),
             selectors: circuit_data.selector,
+            column_annotations: circuit_data.annotations,
         })
     }
 }
@@ -116,4 +569,5 @@ struct PreprocessingData<F: PrimeField> {
     pub(crate) permutation_matrix: SparseMatrix<F>,
     pub(crate) fixed_columns: Vec<Vec<F>>,
     pub(crate) selectors: Vec<Vec<bool>>,
+    pub(crate) column_annotations: ColumnAnnotations,
 }
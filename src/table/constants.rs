@@ -0,0 +1,145 @@
+//! Support of halo2 constants, i.e. `assign_advice_from_constant` & `constrain_constant`
+//!
+//! Halo2 places constants into fixed columns enabled by `enable_constant` & copies them into
+//! advice cells. But copy of fixed cell isn't preserved by folding: advice values are folded, but
+//! fixed ones aren't. So each such copy is replaced by gate `s_a * (a - v_a) = 0` for each advice
+//! column `a`, where fixed columns `s_a` & `v_a` hold the copied constant on the row of advice
+//! cell. Homogenization turns it into `s_a * (a - u * v_a)`, which is preserved by folding
+use ff::PrimeField;
+use halo2_proofs::{
+    plonk::{Advice, Any, Assigned, Column, ConstraintSystem, Error, Fixed},
+    poly::Rotation,
+};
+use tracing::*;
+
+use crate::plonk::permutation::Assembly;
+
+/// Columns of constant gate for one advice column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ConstantColumns {
+    advice: Column<Advice>,
+    selector: Column<Fixed>,
+    value: Column<Fixed>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ConstantsConfig {
+    columns: Vec<ConstantColumns>,
+}
+
+impl ConstantsConfig {
+    /// Adds fixed columns & gates for each advice column, that can be copied from constants
+    ///
+    /// Must be called right after `configure` of circuit. For circuits without constants it does
+    /// nothing, so their structure isn't changed
+    pub fn configure<F: PrimeField>(cs: &mut ConstraintSystem<F>) -> Self {
+        if cs.constants().is_empty() {
+            return Self::default();
+        }
+
+        let advice_columns = cs
+            .permutation
+            .columns
+            .iter()
+            .filter_map(|column| Column::<Advice>::try_from(*column).ok())
+            .collect::<Vec<_>>();
+
+        let columns = advice_columns
+            .into_iter()
+            .map(|advice| {
+                let selector = cs.fixed_column();
+                let value = cs.fixed_column();
+
+                cs.create_gate("constant", |meta| {
+                    let s = meta.query_fixed(selector, Rotation::cur());
+                    let a = meta.query_advice(advice, Rotation::cur());
+                    let v = meta.query_fixed(value, Rotation::cur());
+                    vec![s * (a - v)]
+                });
+
+                ConstantColumns {
+                    advice,
+                    selector,
+                    value,
+                }
+            })
+            .collect();
+
+        Self { columns }
+    }
+
+    /// Fills fixed columns of constant gates by copies from constants, collected in `permutation`
+    ///
+    /// Each advice cell in a cycle with a constant gets this constant. Cycles with different
+    /// constants can't be satisfied, [`Error::Synthesis`] is returned for them
+    pub fn assign<F: PrimeField>(
+        &self,
+        cs: &ConstraintSystem<F>,
+        permutation: &Assembly,
+        fixed: &mut [Vec<Assigned<F>>],
+    ) -> Result<(), Error> {
+        if self.columns.is_empty() {
+            return Ok(());
+        }
+
+        let columns = &cs.permutation.columns;
+        let constants = cs.constants();
+        let num_rows = permutation.mapping.first().map_or(0, Vec::len);
+
+        let mut visited = vec![vec![false; num_rows]; columns.len()];
+        for start_column in 0..columns.len() {
+            for start_row in 0..num_rows {
+                if visited[start_column][start_row] {
+                    continue;
+                }
+
+                let mut cycle = vec![];
+                let mut cell = (start_column, start_row);
+                loop {
+                    visited[cell.0][cell.1] = true;
+                    cycle.push(cell);
+                    cell = permutation.mapping[cell.0][cell.1];
+                    if cell == (start_column, start_row) {
+                        break;
+                    }
+                }
+
+                let values = cycle
+                    .iter()
+                    .filter_map(|(column, row)| {
+                        let column = Column::<Fixed>::try_from(columns[*column]).ok()?;
+                        constants
+                            .contains(&column)
+                            .then(|| fixed[column.index()][*row])
+                    })
+                    .collect::<Vec<_>>();
+
+                let Some(value) = values.first().copied() else {
+                    continue;
+                };
+                if values
+                    .iter()
+                    .any(|other| other.evaluate() != value.evaluate())
+                {
+                    error!("different constants are copied into one cycle: {values:?}");
+                    return Err(Error::Synthesis);
+                }
+
+                for (column, row) in cycle {
+                    let Some(gate) = self
+                        .columns
+                        .iter()
+                        .find(|gate| Column::<Any>::from(gate.advice) == columns[column])
+                    else {
+                        continue;
+                    };
+
+                    fixed[gate.selector.index()][row] = F::ONE.into();
+                    fixed[gate.value.index()][row] = value;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
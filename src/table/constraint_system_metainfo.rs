@@ -4,7 +4,7 @@ use tracing::*;
 
 use crate::{
     plonk::{lookup, CompressedGates},
-    polynomial::{expression::QueryIndexContext, Expression},
+    polynomial::{expression::QueryIndexContext, Expression, Halo2ExprError},
 };
 
 pub(crate) struct ConstraintSystemMetainfo<F: PrimeField> {
@@ -12,20 +12,27 @@ pub(crate) struct ConstraintSystemMetainfo<F: PrimeField> {
     pub round_sizes: Vec<usize>,
     pub folding_degree: usize,
     pub gates: Vec<Expression<F>>,
+    /// Names of `gates` in the same order, see [`crate::plonk::PlonkStructure::unsatisfied_gates`]
+    pub gate_names: Vec<String>,
+    /// Index of each of `gates` in order of `create_gate`, lookups follow custom gates
+    pub gate_indices: Vec<usize>,
     pub custom_gates_lookup_compressed: CompressedGates<F>,
 }
 
 impl<F: PrimeField> ConstraintSystemMetainfo<F> {
     /// The separation of this function from circuit_info is to remove dependency on [`PlonkStructure`]
     /// it is used to kickstart the Folding Circuit initialization
+    ///
+    /// Fails if gates or lookups query halo2 columns or challenges, that can't be converted, see
+    /// [`Expression::from_halo2_expr`]
     pub(crate) fn build(
         k_table_size: usize,
         cs: &ConstraintSystem<F>,
-    ) -> ConstraintSystemMetainfo<F> {
+    ) -> Result<ConstraintSystemMetainfo<F>, Halo2ExprError> {
         let num_gates: usize = cs.gates().iter().map(|gate| gate.polynomials().len()).sum();
         info!("start build constraint system metainfo with {num_gates} custom gates");
 
-        let (num_lookups, has_vector_lookup, lookup_exprs) = lookup::Arguments::compress_from(cs)
+        let (num_lookups, has_vector_lookup, lookup_exprs) = lookup::Arguments::compress_from(cs)?
             .as_ref()
             .map(|arg| {
                 (
@@ -45,68 +52,120 @@ impl<F: PrimeField> ConstraintSystemMetainfo<F> {
             }
         );
 
-        let gates = cs
-            .gates()
-            .iter()
-            .flat_map(|gate| gate.polynomials().iter())
-            .map(|expr| {
-                Expression::from_halo2_expr(expr, cs.num_selectors(), cs.num_fixed_columns())
-            })
-            .chain(lookup_exprs)
-            .collect::<Vec<_>>();
-
-        // we have at most 3 prover rounds
-        let nrow = 1 << k_table_size;
-
-        let mut round_sizes = Vec::new();
-
-        if has_vector_lookup {
-            round_sizes.extend([
-                // advice columns
-                cs.num_advice_columns() * nrow,
-                // (l_i, t_i, m_i), see [`lookup.rs::Arguments::log_derivative_expr`]
-                3 * num_lookups * nrow,
-                // (h_i, g_i), see [`lookup.rs::Arguments::log_derivative_expr`]
-                2 * num_lookups * nrow,
-            ]);
-        } else if num_lookups > 0 {
-            round_sizes.extend([
-                // advice columns || (l_i, t_i, m_i)
-                (cs.num_advice_columns() + 3 * num_lookups) * nrow,
-                // (h_i, g_i)
-                2 * num_lookups * nrow,
-            ]);
-        } else {
-            // advice columns
-            round_sizes.push(cs.num_advice_columns() * nrow);
-        };
-
-        // we use r3 to combine all custom gates and lookup expressions
-        // find the challenge index of r3
         let mut ctx = QueryIndexContext {
             num_selectors: cs.num_selectors(),
             num_fixed: cs.num_fixed_columns(),
             num_advice: cs.num_advice_columns(),
             num_lookups,
-            num_challenges: if has_vector_lookup {
-                2
-            } else if num_lookups > 0 {
-                1
-            } else {
-                0
-            },
+            num_instance: cs.num_instance_columns(),
+            num_challenges: lookup_challenges(num_lookups, has_vector_lookup),
         };
 
+        let mut named_gates = cs
+            .gates()
+            .iter()
+            .flat_map(|gate| {
+                gate.polynomials()
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, expr)| (gate, index, expr))
+            })
+            .enumerate()
+            .map(|(gate_index, (gate, index, expr))| {
+                let name = match gate.constraint_name(index) {
+                    "" => gate.name().to_string(),
+                    constraint => format!("{}: {constraint}", gate.name()),
+                };
+                let expr = Expression::from_halo2_expr(expr, &ctx)?;
+
+                Ok((gate_index, name, expr))
+            })
+            .collect::<Result<Vec<_>, Halo2ExprError>>()?;
+        // Custom gates are combined with powers of a challenge, so their order is a part of
+        // the structure. Sort them by canonical encoding to not depend on the order of
+        // `create_gate`, equal gates keep their relative order
+        named_gates
+            .sort_by_cached_key(|(gate_index, _, gate)| (gate.canonical_bytes(), *gate_index));
+        named_gates.extend(
+            lookup_exprs
+                .into_iter()
+                .enumerate()
+                .map(|(index, expr)| (num_gates + index, format!("lookup {index}"), expr)),
+        );
+        let (gate_indices, (gate_names, gates)): (Vec<_>, (Vec<_>, Vec<_>)) = named_gates
+            .into_iter()
+            .map(|(gate_index, name, expr)| (gate_index, (name, expr)))
+            .unzip();
+
+        let round_sizes = round_sizes(
+            k_table_size,
+            cs.num_advice_columns(),
+            num_lookups,
+            has_vector_lookup,
+        );
+
+        // we use r3 to combine all custom gates and lookup expressions, its index is
+        // `ctx.num_challenges`
         let custom_gates_lookup_compressed = CompressedGates::new(&gates, &mut ctx);
 
         let folding_degree = custom_gates_lookup_compressed.grouped().len();
 
-        ConstraintSystemMetainfo {
+        Ok(ConstraintSystemMetainfo {
             num_challenges: custom_gates_lookup_compressed.compressed().num_challenges(),
             round_sizes,
             folding_degree,
             gates,
+            gate_names,
+            gate_indices,
             custom_gates_lookup_compressed,
-        }
+        })
     }
 }
+
+/// Count of challenges, that lookup argument needs before the challenge combining gates,
+/// see [`crate::plonk::PlonkInstance::challenges`]
+pub(crate) fn lookup_challenges(num_lookups: usize, has_vector_lookup: bool) -> usize {
+    if has_vector_lookup {
+        2
+    } else if num_lookups > 0 {
+        1
+    } else {
+        0
+    }
+}
+
+/// Witness size of each prover round, see [`crate::plonk::PlonkStructure::round_sizes`]
+pub(crate) fn round_sizes(
+    k_table_size: usize,
+    num_advice_columns: usize,
+    num_lookups: usize,
+    has_vector_lookup: bool,
+) -> Vec<usize> {
+    // we have at most 3 prover rounds
+    let nrow = 1 << k_table_size;
+
+    let mut round_sizes = Vec::new();
+
+    if has_vector_lookup {
+        round_sizes.extend([
+            // advice columns
+            num_advice_columns * nrow,
+            // (l_i, t_i, m_i), see [`lookup.rs::Arguments::log_derivative_expr`]
+            3 * num_lookups * nrow,
+            // (h_i, g_i), see [`lookup.rs::Arguments::log_derivative_expr`]
+            2 * num_lookups * nrow,
+        ]);
+    } else if num_lookups > 0 {
+        round_sizes.extend([
+            // advice columns || (l_i, t_i, m_i)
+            (num_advice_columns + 3 * num_lookups) * nrow,
+            // (h_i, g_i)
+            2 * num_lookups * nrow,
+        ]);
+    } else {
+        // advice columns
+        round_sizes.push(num_advice_columns * nrow);
+    }
+
+    round_sizes
+}
@@ -14,11 +14,16 @@
 
 mod circuit_data;
 mod circuit_runner;
+mod constants;
 mod constraint_system_metainfo;
 mod witness_data;
 
-pub use circuit_runner::CircuitRunner;
-pub(crate) use constraint_system_metainfo::ConstraintSystemMetainfo;
+pub use circuit_data::ColumnAnnotations;
+pub use circuit_runner::{CircuitRunner, Mismatch, StructureError, TableSnapshot};
+pub(crate) use constants::ConstantsConfig;
+pub(crate) use constraint_system_metainfo::{
+    lookup_challenges, round_sizes, ConstraintSystemMetainfo,
+};
 pub(crate) use witness_data::WitnessCollector;
 
 #[cfg(test)]
@@ -1,15 +1,21 @@
 use ff::{Field, PrimeField};
 use halo2_proofs::{
-    circuit::{Layouter, SimpleFloorPlanner},
-    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{
+        Advice, Assigned, Circuit, Column, ConstraintSystem, Error, Expression, Fixed, Instance,
+        Selector, TableColumn,
+    },
+    poly::Rotation,
 };
 use halo2curves::group::ff::FromUniformBytes;
-use prettytable::{row, Cell, Row, Table};
+use prettytable::{Cell, Row, Table};
 use tracing_test::traced_test;
 
 use crate::{
+    commitment::CommitmentKey,
     main_gate::{MainGate, MainGateConfig, RegionCtx},
-    util::trim_leading_zeros,
+    plonk::{PlonkInstance, PlonkStructure, PlonkWitness},
+    util::{concatenate_with_padding, create_ro, trim_leading_zeros},
 };
 
 use super::*;
@@ -56,10 +62,11 @@ impl<F: PrimeField + FromUniformBytes<64>> Circuit<F> for TestCircuit<F> {
         config: Self::Config,
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
-        let pchip = MainGate::new(config.pconfig);
+        let pchip = MainGate::new(config.pconfig.clone());
         let output = layouter.assign_region(
             || "test",
-            |region| {
+            |mut region| {
+                config.pconfig.name_columns(&mut region);
                 let ctx = &mut RegionCtx::new(region, 0);
                 pchip.random_linear_combination(ctx, self.inputs.clone(), self.r)
             },
@@ -85,14 +92,26 @@ fn test_assembly() -> Result<(), Error> {
 
     let td = CircuitRunner::<Fp, _>::new(K, circuit, public_inputs);
     let witness = td.try_collect_witness()?;
+    let annotations = td.try_collect_column_annotations()?;
+
+    let pconfig = &td.config.pconfig;
+    let columns = pconfig
+        .state
+        .iter()
+        .chain([&pconfig.input, &pconfig.out])
+        .collect::<Vec<_>>();
 
     let mut table = Table::new();
-    table.add_row(row!["s0", "s1", "s2", "in", "out"]);
-    let col = 5;
+    table.add_row(Row::new(
+        columns
+            .iter()
+            .map(|column| Cell::new(annotations.column_name(**column).unwrap_or("?")))
+            .collect(),
+    ));
     for i in 0..2usize.pow(K) {
         let mut row = vec![];
-        for j in 0..col {
-            if let Some(val) = witness.get(j).and_then(|v| v.get(i)) {
+        for column in columns.iter() {
+            if let Some(val) = witness.get(column.index()).and_then(|v| v.get(i)) {
                 row.push(trim_leading_zeros(format!("{:?}", val)));
             }
         }
@@ -101,3 +120,1496 @@ fn test_assembly() -> Result<(), Error> {
     // table.printstd();
     Ok(())
 }
+
+#[test]
+fn column_annotations() -> Result<(), Error> {
+    use halo2curves::pasta::Fp;
+
+    let circuit = TestCircuit::new((1..10).map(Fp::from).collect(), Fp::ONE);
+    let td = CircuitRunner::<Fp, _>::new(4, circuit, vec![Fp::from(45)]);
+    let annotations = td.try_collect_column_annotations()?;
+
+    let pconfig = &td.config.pconfig;
+    for i in 0..T {
+        assert_eq!(
+            annotations.column_name(pconfig.state[i]),
+            Some(format!("state[{i}]").as_str())
+        );
+    }
+    assert_eq!(annotations.column_name(pconfig.input), Some("input"));
+    assert_eq!(annotations.column_name(pconfig.out), Some("out"));
+    assert_eq!(annotations.column_name(pconfig.q_o), Some("q_o"));
+    assert_eq!(annotations.column_name(td.config.instance), None);
+
+    Ok(())
+}
+
+#[test]
+fn same_circuit() -> Result<(), Error> {
+    use halo2curves::pasta::Fp;
+
+    const K: u32 = 4;
+    // instance values are not part of structure, so any output is fine here
+    let collect = |inputs: std::ops::Range<u64>, r: u64| {
+        let circuit = TestCircuit::new(inputs.map(Fp::from).collect(), Fp::from(r));
+        CircuitRunner::<Fp, _>::new(K, circuit, vec![Fp::ZERO]).try_collect_plonk_structure()
+    };
+
+    let S1 = collect(1..10, 1)?;
+    let S2 = collect(2..11, 1)?;
+    // linear combination coefficients are fixed, so it's another circuit
+    let S3 = collect(1..10, 2)?;
+
+    assert!(S1.same_circuit(&S2));
+    assert!(!S1.same_circuit(&S3));
+
+    // names of gates are diagnostics only, so it's the same circuit, but not an equal structure
+    let mut renamed = S1.clone();
+    renamed.gate_names = vec!["renamed".to_string(); renamed.gate_names.len()];
+    assert!(renamed != S1);
+    assert!(S1.same_circuit(&renamed));
+
+    Ok(())
+}
+
+/// Single gate `s * (a^4 - b) = 0`
+struct Degree4Circuit;
+
+impl<F: PrimeField> Circuit<F> for Degree4Circuit {
+    type Config = (Selector, Column<Advice>, Column<Advice>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let s = meta.selector();
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+
+        meta.create_gate("a^4 = b", |meta| {
+            let s = meta.query_selector(s);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            vec![s * (a.clone() * a.clone() * a.clone() * a - b)]
+        });
+
+        (s, a, b)
+    }
+
+    fn synthesize(
+        &self,
+        (s, a, b): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "a^4 = b",
+            |mut region| {
+                s.enable(&mut region, 0)?;
+                region.assign_advice(|| "a", a, 0, || Value::known(F::from(2)))?;
+                region.assign_advice(|| "b", b, 0, || Value::known(F::from(16)))?;
+                Ok(())
+            },
+        )
+    }
+}
+
+#[test]
+fn max_gate_degree() {
+    use halo2curves::pasta::Fp;
+
+    let runner = CircuitRunner::<Fp, _>::new(4, Degree4Circuit, vec![]);
+
+    assert!(matches!(
+        runner.try_collect_plonk_structure_with_max_degree(Some(3)),
+        Err(StructureError::GateDegreeExceeded {
+            degree: 4,
+            max_gate_degree: 3
+        })
+    ));
+    assert!(runner
+        .try_collect_plonk_structure_with_max_degree(Some(4))
+        .is_ok());
+    assert!(runner
+        .try_collect_plonk_structure_with_max_degree(None)
+        .is_ok());
+}
+
+/// Single gate `s * (a^DEGREE - b) = 0`, satisfied at the first row
+struct PowerCircuit<const DEGREE: usize>;
+
+impl<const DEGREE: usize, F: PrimeField> Circuit<F> for PowerCircuit<DEGREE> {
+    type Config = (Selector, Column<Advice>, Column<Advice>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let s = meta.selector();
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+
+        meta.create_gate("a^DEGREE = b", |meta| {
+            let s = meta.query_selector(s);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let power = (1..DEGREE).fold(a.clone(), |acc, _| acc * a.clone());
+            vec![s * (power - b)]
+        });
+
+        (s, a, b)
+    }
+
+    fn synthesize(
+        &self,
+        (s, a, b): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "a^DEGREE = b",
+            |mut region| {
+                s.enable(&mut region, 0)?;
+                region.assign_advice(|| "a", a, 0, || Value::known(F::from(2)))?;
+                region.assign_advice(|| "b", b, 0, || Value::known(F::from(1 << DEGREE)))?;
+                Ok(())
+            },
+        )
+    }
+}
+
+#[test]
+fn num_cross_terms() -> Result<(), Error> {
+    use halo2curves::pasta::Fp;
+
+    // folding of degree `d` gate gives `d - 1` cross terms, selector doesn't add degree
+    let S =
+        CircuitRunner::<Fp, _>::new(4, PowerCircuit::<2>, vec![]).try_collect_plonk_structure()?;
+    assert_eq!(S.num_cross_terms(), 1);
+    assert_eq!(S.cross_term_len(), 1 << 4);
+
+    let S =
+        CircuitRunner::<Fp, _>::new(4, PowerCircuit::<3>, vec![]).try_collect_plonk_structure()?;
+    assert_eq!(S.num_cross_terms(), 2);
+    assert_eq!(S.cross_term_len(), 1 << 4);
+
+    Ok(())
+}
+
+/// Table `[7, 8, 9]`, which tail filled by layouter with default `7`, and gate `t - a = 0`
+struct FillCircuit<F> {
+    advice: Vec<F>,
+}
+
+impl<F: PrimeField> Circuit<F> for FillCircuit<F> {
+    type Config = (TableColumn, Column<Advice>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self { advice: vec![] }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let t = meta.lookup_table_column();
+        let a = meta.advice_column();
+
+        meta.create_gate("t = a", |meta| {
+            let t = meta.query_fixed(t.inner(), Rotation::cur());
+            let a = meta.query_advice(a, Rotation::cur());
+            vec![t - a]
+        });
+
+        (t, a)
+    }
+
+    fn synthesize(
+        &self,
+        (t, a): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_table(
+            || "table",
+            |mut table| {
+                for (row, value) in [7, 8, 9].into_iter().enumerate() {
+                    table.assign_cell(|| "t", t, row, || Value::known(F::from(value)))?;
+                }
+                Ok(())
+            },
+        )?;
+
+        layouter.assign_region(
+            || "a",
+            |mut region| {
+                for (row, value) in self.advice.iter().enumerate() {
+                    region.assign_advice(|| "a", a, row, || Value::known(*value))?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[test]
+fn fill_from_row() -> Result<(), Error> {
+    use halo2curves::pasta::{EqAffine, Fp};
+
+    const K: u32 = 4;
+
+    let table = [7, 8, 9]
+        .into_iter()
+        .chain(std::iter::repeat(7))
+        .take(1 << K)
+        .map(Fp::from)
+        .collect::<Vec<_>>();
+
+    let is_sat = |advice: Vec<Fp>| -> Result<bool, Error> {
+        let runner = CircuitRunner::new(K, FillCircuit { advice }, vec![]);
+        let S = runner.try_collect_plonk_structure()?;
+        assert_eq!(S.fixed_columns, vec![table.clone()]);
+
+        let witness = runner.try_collect_witness()?;
+        let u = PlonkInstance::<EqAffine>::new(S.num_io, S.num_challenges, 1);
+        let w = PlonkWitness {
+            W: vec![concatenate_with_padding(&witness, 1 << K)],
+            blinds: vec![Fp::ZERO],
+        };
+        Ok(S.is_sat_relation_only(&u, &w).is_ok())
+    };
+
+    assert!(is_sat(table.clone())?);
+
+    // the filled tail is constrained as well
+    let mut advice = table.clone();
+    advice[10] = Fp::from(9);
+    assert!(!is_sat(advice)?);
+
+    Ok(())
+}
+
+/// Circuit without advice columns at all: `s * (f - 1) = 0`
+struct FixedOnlyCircuit;
+
+impl<F: PrimeField> Circuit<F> for FixedOnlyCircuit {
+    type Config = (Selector, Column<Fixed>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let s = meta.selector();
+        let f = meta.fixed_column();
+
+        meta.create_gate("f = 1", |meta| {
+            let s = meta.query_selector(s);
+            let f = meta.query_fixed(f, Rotation::cur());
+            vec![s * (f - Expression::Constant(F::ONE))]
+        });
+
+        (s, f)
+    }
+
+    fn synthesize(
+        &self,
+        (s, f): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "f = 1",
+            |mut region| {
+                s.enable(&mut region, 0)?;
+                region.assign_fixed(|| "f", f, 0, || Value::known(F::ONE))?;
+                Ok(())
+            },
+        )
+    }
+}
+
+#[test]
+fn zero_advice_columns() -> Result<(), Error> {
+    use halo2curves::pasta::{EqAffine, Fp};
+
+    const K: u32 = 4;
+    let runner = CircuitRunner::<Fp, _>::new(K, FixedOnlyCircuit, vec![]);
+    let S = runner.try_collect_plonk_structure()?;
+    assert_eq!(S.num_advice_columns, 0);
+
+    let witness = runner.try_collect_witness()?;
+    let u = PlonkInstance::<EqAffine>::new(S.num_io, S.num_challenges, 1);
+    let w = PlonkWitness {
+        W: vec![concatenate_with_padding(&witness, 1 << K)],
+        blinds: vec![Fp::ZERO],
+    };
+
+    let U = u.to_relax();
+    let W = w.into_relax(S.k);
+    assert!(W.W.iter().all(Vec::is_empty));
+    assert_eq!(W.E.len(), 1 << K);
+
+    let ck = CommitmentKey::<EqAffine>::setup(K as usize, b"zero_advice_columns");
+    assert_eq!(S.is_sat_relaxed(&ck, &U, &W), Ok(()));
+
+    Ok(())
+}
+
+/// Two gates `s * (a - b) = 0` & `s * (a^2 - b^2) = 0`, combined by a squeezed challenge
+struct TwoGatesCircuit<F> {
+    a: F,
+    b: F,
+}
+
+impl<F: PrimeField> Circuit<F> for TwoGatesCircuit<F> {
+    type Config = (Selector, Column<Advice>, Column<Advice>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            a: F::ZERO,
+            b: F::ZERO,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let s = meta.selector();
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+
+        meta.create_gate("a = b", |meta| {
+            let s = meta.query_selector(s);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            vec![s * (a - b)]
+        });
+        meta.create_gate("a^2 = b^2", |meta| {
+            let s = meta.query_selector(s);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            vec![s * (a.clone() * a - b.clone() * b)]
+        });
+
+        (s, a, b)
+    }
+
+    fn synthesize(
+        &self,
+        (s, a, b): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "two gates",
+            |mut region| {
+                s.enable(&mut region, 0)?;
+                region.assign_advice(|| "a", a, 0, || Value::known(self.a))?;
+                region.assign_advice(|| "b", b, 0, || Value::known(self.b))?;
+                Ok(())
+            },
+        )
+    }
+}
+
+#[test]
+fn gates_combination_challenge() {
+    use halo2curves::{
+        bn256::{Fr, G1Affine},
+        CurveAffine,
+    };
+
+    use crate::{constants::NUM_CHALLENGE_BITS, poseidon::ROTrait};
+
+    const K: u32 = 4;
+    let ck = CommitmentKey::<G1Affine>::setup(K as usize, b"gates_combination_challenge");
+    let new_ro = create_ro::<<G1Affine as CurveAffine>::Base, T, 2, 4, 3>;
+
+    let is_sat = |a: Fr, b: Fr| {
+        let runner = CircuitRunner::new(K, TwoGatesCircuit { a, b }, vec![]);
+        let S = runner.try_collect_plonk_structure().unwrap();
+        // gates are combined with one extra challenge
+        assert_eq!(S.num_challenges, 1);
+
+        let witness = runner.try_collect_witness().unwrap();
+        let (u, w) = S
+            .run_sps_protocol(&ck, &[], &witness, &mut new_ro(), S.num_challenges)
+            .unwrap();
+
+        let expected_challenge = new_ro()
+            .absorb_point(&u.W_commitments[0])
+            .squeeze::<G1Affine>(NUM_CHALLENGE_BITS);
+        assert_eq!(u.challenges, vec![expected_challenge]);
+
+        S.is_sat(&ck, &mut new_ro(), &u, &w)
+    };
+
+    assert_eq!(is_sat(Fr::from(3), Fr::from(3)), Ok(()));
+    // only the first gate is violated, but the combined one still catches it
+    assert!(is_sat(Fr::from(3), -Fr::from(3)).is_err());
+}
+
+#[test]
+fn is_sat_fail_fast() {
+    use halo2curves::{
+        bn256::{Fr, G1Affine},
+        CurveAffine,
+    };
+
+    use crate::plonk::Error as PlonkError;
+
+    const K: u32 = 4;
+    let ck = CommitmentKey::<G1Affine>::setup(K as usize, b"is_sat_fail_fast");
+    let new_ro = create_ro::<<G1Affine as CurveAffine>::Base, T, 2, 4, 3>;
+
+    // returns verdicts of `is_sat` & `is_sat_fail_fast`
+    let check = |a: Fr, b: Fr, tamper_blind: bool| {
+        let runner = CircuitRunner::new(K, TwoGatesCircuit { a, b }, vec![]);
+        let S = runner.try_collect_plonk_structure().unwrap();
+        let witness = runner.try_collect_witness().unwrap();
+        let (u, mut w) = S
+            .run_sps_protocol(&ck, &[], &witness, &mut new_ro(), S.num_challenges)
+            .unwrap();
+        if tamper_blind {
+            w.blinds[0] += Fr::ONE;
+        }
+
+        (
+            S.is_sat(&ck, &mut new_ro(), &u, &w),
+            S.is_sat_fail_fast(&ck, &mut new_ro(), &u, &w),
+        )
+    };
+
+    let (full, fail_fast) = check(Fr::from(3), Fr::from(3), false);
+    assert_eq!(full, Ok(()));
+    assert_eq!(fail_fast, Ok(()));
+
+    let (full, fail_fast) = check(Fr::from(3), -Fr::from(3), false);
+    assert!(matches!(full, Err(PlonkError::EvaluationMismatch { .. })));
+    assert_eq!(full, fail_fast);
+
+    let (full, fail_fast) = check(Fr::from(3), Fr::from(3), true);
+    assert!(matches!(full, Err(PlonkError::CommitmentMismatch { .. })));
+    assert_eq!(full, fail_fast);
+
+    // both are broken: full check reports relation, fail-fast one reports commitments
+    let (full, fail_fast) = check(Fr::from(3), -Fr::from(3), true);
+    assert!(matches!(full, Err(PlonkError::EvaluationMismatch { .. })));
+    assert!(matches!(
+        fail_fast,
+        Err(PlonkError::CommitmentMismatch { .. })
+    ));
+}
+
+#[test]
+fn unsatisfied_gates() {
+    use halo2curves::pasta::{EqAffine, Fp};
+
+    const K: u32 = 4;
+
+    let unsatisfied = |a: Fp, b: Fp| {
+        let runner = CircuitRunner::new(K, TwoGatesCircuit { a, b }, vec![]);
+        let S = runner.try_collect_plonk_structure().unwrap();
+        let witness = runner.try_collect_witness().unwrap();
+
+        // original gates don't depend on the challenge, that combines them
+        let u = PlonkInstance::<EqAffine>::new(S.num_io, S.num_challenges, 1);
+        let w = PlonkWitness {
+            W: vec![concatenate_with_padding(&witness, 1 << K)],
+            blinds: vec![Fp::ZERO],
+        };
+
+        let mut failures = S
+            .unsatisfied_gates(&u, &w)
+            .unwrap()
+            .into_iter()
+            .map(|failure| (failure.gate_name, failure.row))
+            .collect::<Vec<_>>();
+        failures.sort();
+        failures
+    };
+
+    assert_eq!(unsatisfied(Fp::from(3), Fp::from(3)), vec![]);
+    assert_eq!(
+        unsatisfied(Fp::from(3), -Fp::from(3)),
+        vec![("a = b".to_string(), 0)]
+    );
+    assert_eq!(
+        unsatisfied(Fp::from(3), Fp::from(4)),
+        vec![("a = b".to_string(), 0), ("a^2 = b^2".to_string(), 0)]
+    );
+}
+
+/// Assigns `a` at first `rows` rows & enables `s * a = 0` at the last one
+struct RowsCircuit {
+    rows: usize,
+}
+
+impl<F: PrimeField> Circuit<F> for RowsCircuit {
+    type Config = (Selector, Column<Advice>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self { rows: self.rows }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let s = meta.selector();
+        let a = meta.advice_column();
+
+        meta.create_gate("a = 0", |meta| {
+            let s = meta.query_selector(s);
+            let a = meta.query_advice(a, Rotation::cur());
+            vec![s * a]
+        });
+
+        (s, a)
+    }
+
+    fn synthesize(
+        &self,
+        (s, a): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "rows",
+            |mut region| {
+                for row in 0..self.rows {
+                    region.assign_advice(|| "a", a, row, || Value::known(F::ZERO))?;
+                }
+                s.enable(&mut region, self.rows - 1)
+            },
+        )
+    }
+}
+
+#[test]
+fn auto_k() {
+    use halo2curves::pasta::Fp;
+
+    let runner =
+        CircuitRunner::<Fp, _>::new_with_auto_k(2..=10, RowsCircuit { rows: 10 }, vec![]).unwrap();
+    assert_eq!(runner.k(), 4);
+    assert!(runner.try_collect_plonk_structure().is_ok());
+
+    assert!(matches!(
+        CircuitRunner::<Fp, _>::new_with_auto_k(2..=3, RowsCircuit { rows: 10 }, vec![]),
+        Err(Error::NotEnoughRowsAvailable { current_k: 3 })
+    ));
+}
+
+#[test]
+fn cross_check_mock() {
+    use halo2_proofs::dev::MockProver;
+    use halo2curves::{
+        bn256::{Fr, G1Affine},
+        CurveAffine,
+    };
+
+    const K: u32 = 4;
+    let ck = CommitmentKey::<G1Affine>::setup(K as usize, b"cross_check_mock");
+
+    for (output, expected_sat) in [(4097, true), (4096, false)] {
+        let circuit = TestCircuit::new((1..10).map(Fr::from).collect(), Fr::from(2));
+        let runner = CircuitRunner::new(K, circuit, vec![Fr::from(output)]);
+
+        let mock_sat = MockProver::run(K, &runner.circuit, vec![runner.instance.clone()])
+            .unwrap()
+            .verify()
+            .is_ok();
+        assert_eq!(mock_sat, expected_sat);
+
+        let mut ro_nark_prepare = create_ro::<<G1Affine as CurveAffine>::Base, T, 2, 4, 3>();
+        let mut ro_nark_decider = create_ro::<<G1Affine as CurveAffine>::Base, T, 2, 4, 3>();
+        runner
+            .cross_check_mock(&ck, &mut ro_nark_prepare, &mut ro_nark_decider)
+            .unwrap();
+    }
+}
+
+/// Single gate `VALUE = 0`, which doesn't depend on any cell
+struct ConstantGateCircuit<const VALUE: u64>;
+
+impl<F: PrimeField, const VALUE: u64> Circuit<F> for ConstantGateCircuit<VALUE> {
+    type Config = Column<Advice>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let a = meta.advice_column();
+        meta.create_gate("constant", |_| vec![Expression::Constant(F::from(VALUE))]);
+        a
+    }
+
+    fn synthesize(&self, a: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "a",
+            |mut region| {
+                region.assign_advice(|| "a", a, 0, || Value::known(F::ONE))?;
+                Ok(())
+            },
+        )
+    }
+}
+
+#[test]
+fn constant_gate() -> Result<(), Error> {
+    use halo2curves::pasta::{EqAffine, Fp};
+
+    const K: u32 = 4;
+
+    fn is_sat<CT: Circuit<Fp>>(circuit: CT) -> Result<Result<(), crate::plonk::Error>, Error> {
+        let runner = CircuitRunner::new(K, circuit, vec![]);
+        let S = runner.try_collect_plonk_structure()?;
+        let witness = runner.try_collect_witness()?;
+
+        let u = PlonkInstance::<EqAffine>::new(S.num_io, S.num_challenges, 1);
+        let w = PlonkWitness {
+            W: vec![concatenate_with_padding(&witness, 1 << K)],
+            blinds: vec![Fp::ZERO],
+        };
+        Ok(S.is_sat_relation_only(&u, &w))
+    }
+
+    assert_eq!(is_sat(ConstantGateCircuit::<0>)?, Ok(()));
+    assert_eq!(
+        is_sat(ConstantGateCircuit::<42>)?,
+        Err(crate::plonk::Error::ConstantGate { gate_index: 0 })
+    );
+
+    Ok(())
+}
+
+/// Assigns rational `1 / DENOMINATOR` into a fixed cell
+struct FixedRationalCircuit<const DENOMINATOR: u64>;
+
+impl<F: PrimeField, const DENOMINATOR: u64> Circuit<F> for FixedRationalCircuit<DENOMINATOR> {
+    type Config = Column<Fixed>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        meta.fixed_column()
+    }
+
+    fn synthesize(&self, f: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "rational",
+            |mut region| {
+                let value = Assigned::Rational(F::ONE, F::from(DENOMINATOR));
+                region.assign_fixed(|| "f", f, 0, || Value::known(value))?;
+                Ok(())
+            },
+        )
+    }
+}
+
+#[test]
+fn fixed_rational() {
+    use halo2curves::pasta::Fp;
+
+    let S = CircuitRunner::<Fp, _>::new(4, FixedRationalCircuit::<2>, vec![])
+        .try_collect_plonk_structure()
+        .unwrap();
+    assert_eq!(S.fixed_columns[0][0], Fp::from(2).invert().unwrap());
+
+    assert!(matches!(
+        CircuitRunner::<Fp, _>::new(4, FixedRationalCircuit::<0>, vec![])
+            .try_collect_plonk_structure(),
+        Err(Error::Synthesis)
+    ));
+}
+
+#[test]
+fn collect_twice() -> Result<(), Error> {
+    use halo2curves::pasta::Fp;
+
+    let circuit = TestCircuit::new((1..10).map(Fp::from).collect(), Fp::from(2));
+    let runner = CircuitRunner::<Fp, _>::new(4, circuit, vec![Fp::from(4097)]);
+
+    // each call synthesizes into fresh buffers, so there is no state to mix
+    let witness = runner.try_collect_witness()?;
+    let S = runner.try_collect_plonk_structure()?;
+    assert_eq!(runner.try_collect_witness()?, witness);
+    assert!(runner.try_collect_plonk_structure()? == S);
+
+    Ok(())
+}
+
+#[test]
+fn homogeneous_consistent() -> Result<(), Error> {
+    use halo2curves::pasta::Fp;
+
+    use crate::{
+        plonk::CompressedGates,
+        polynomial::{expression::QueryIndexContext, Expression as PolyExpression, Query},
+    };
+
+    let circuit = TestCircuit::new((1..10).map(Fp::from).collect(), Fp::from(2));
+    let S = CircuitRunner::<Fp, _>::new(4, circuit, vec![Fp::from(4097)])
+        .try_collect_plonk_structure()?;
+    assert_eq!(S.assert_homogeneous_consistent(), Ok(()));
+
+    // a * b * c - d + 5, where all queries are advice columns
+    let mut ctx = QueryIndexContext::from(&S);
+    let advice_offset = ctx.num_selectors + ctx.num_fixed;
+    let advice = |column: usize| {
+        PolyExpression::Polynomial(Query {
+            index: advice_offset + column,
+            rotation: Rotation::cur(),
+        })
+    };
+    let gate =
+        advice(0) * advice(1) * advice(2) - advice(3) + PolyExpression::Constant(Fp::from(5));
+
+    let mut degree3 = S.clone();
+    degree3.custom_gates_lookup_compressed = CompressedGates::new(&[gate], &mut ctx);
+    degree3.num_challenges = degree3
+        .custom_gates_lookup_compressed
+        .compressed()
+        .num_challenges();
+    assert_eq!(degree3.assert_homogeneous_consistent(), Ok(()));
+
+    Ok(())
+}
+
+#[test]
+fn summary() -> Result<(), Error> {
+    use halo2curves::pasta::Fp;
+
+    use crate::plonk::CircuitSummary;
+
+    let circuit = TestCircuit::new((1..10).map(Fp::from).collect(), Fp::from(2));
+    let S = CircuitRunner::<Fp, _>::new(4, circuit, vec![Fp::from(4097)])
+        .try_collect_plonk_structure()?;
+
+    // main gate with `T = 3`: `q_m[0]*s[0]*s[1] + q_i*input + rc + q_o*out` and linear & quintic
+    // term of each state column
+    assert_eq!(
+        S.summary(),
+        CircuitSummary {
+            k: 4,
+            num_rows: 16,
+            num_fixed_columns: 3 * T + 2 + 3,
+            num_advice_columns: T + 2,
+            gate_degree: 5,
+            num_monomials: 4 + 2 * T,
+            num_cross_terms: 5,
+        }
+    );
+
+    Ok(())
+}
+
+/// Allocates one more advice column on each `configure` call
+struct UnstableConfigureCircuit;
+
+static CONFIGURE_CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(1);
+
+impl<F: PrimeField> Circuit<F> for UnstableConfigureCircuit {
+    type Config = Vec<Column<Advice>>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let calls = CONFIGURE_CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        (0..calls).map(|_| meta.advice_column()).collect()
+    }
+
+    fn synthesize(&self, _: Self::Config, _: impl Layouter<F>) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[test]
+fn unstable_configure() {
+    use halo2curves::pasta::Fp;
+
+    let runner = CircuitRunner::<Fp, _>::new(4, UnstableConfigureCircuit, vec![]);
+    let num_advice = runner.cs.num_advice_columns();
+
+    match runner.try_collect_plonk_structure_with_max_degree(None) {
+        Err(StructureError::UnstableConfigure {
+            what: "advice columns",
+            expected,
+            actual,
+        }) => {
+            assert_eq!(expected, num_advice);
+            assert!(actual > expected);
+        }
+        _ => panic!("non-deterministic configure must be rejected"),
+    }
+    assert!(matches!(
+        runner.try_collect_plonk_structure(),
+        Err(Error::Synthesis)
+    ));
+}
+
+#[test]
+fn gates_order_independent() {
+    use halo2curves::pasta::Fp;
+
+    let build = |mul_first: bool| {
+        let mut cs = ConstraintSystem::<Fp>::default();
+        let [a, b, c] = [(); 3].map(|_| cs.advice_column());
+        let q = cs.fixed_column();
+
+        let query = |cs: &mut halo2_proofs::plonk::VirtualCells<'_, Fp>| {
+            [a, b, c]
+                .map(|column| cs.query_advice(column, Rotation::cur()))
+                .into_iter()
+                .chain([cs.query_fixed(q, Rotation::cur())])
+                .collect::<Vec<_>>()
+        };
+        let mul = |cs: &mut ConstraintSystem<Fp>| {
+            cs.create_gate("mul", |meta| {
+                let [a, b, c, q] = query(meta).try_into().unwrap();
+                vec![q * (a * b - c)]
+            })
+        };
+        let add = |cs: &mut ConstraintSystem<Fp>| {
+            cs.create_gate("add", |meta| {
+                let [a, b, c, q] = query(meta).try_into().unwrap();
+                vec![q * (a + b - c)]
+            })
+        };
+
+        if mul_first {
+            mul(&mut cs);
+            add(&mut cs);
+        } else {
+            add(&mut cs);
+            mul(&mut cs);
+        }
+
+        ConstraintSystemMetainfo::build(4, &cs).unwrap()
+    };
+
+    let lhs = build(true);
+    let rhs = build(false);
+
+    assert_eq!(lhs.gates, rhs.gates);
+    assert_eq!(lhs.gate_names, rhs.gate_names);
+    // original indices follow `create_gate` order
+    assert_eq!(lhs.gate_indices.len(), 2);
+    assert_eq!(
+        lhs.gate_indices,
+        rhs.gate_indices
+            .iter()
+            .map(|index| 1 - index)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        lhs.custom_gates_lookup_compressed.compressed(),
+        rhs.custom_gates_lookup_compressed.compressed()
+    );
+}
+
+/// `a = b`, `a^2 = b^2` & `a^3 = b^3` at the first row
+struct ThreeGatesCircuit;
+
+impl<F: PrimeField> Circuit<F> for ThreeGatesCircuit {
+    type Config = (Selector, Column<Advice>, Column<Advice>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let s = meta.selector();
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+
+        for power in 1..=3 {
+            meta.create_gate("a^power = b^power", |meta| {
+                let s = meta.query_selector(s);
+                let a = meta.query_advice(a, Rotation::cur());
+                let b = meta.query_advice(b, Rotation::cur());
+                let pow = |value: Expression<F>| {
+                    (1..power).fold(value.clone(), |acc, _| acc * value.clone())
+                };
+                vec![s * (pow(a) - pow(b))]
+            });
+        }
+
+        (s, a, b)
+    }
+
+    fn synthesize(
+        &self,
+        (s, a, b): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "three gates",
+            |mut region| {
+                s.enable(&mut region, 0)?;
+                region.assign_advice(|| "a", a, 0, || Value::known(F::ONE))?;
+                region.assign_advice(|| "b", b, 0, || Value::known(F::ONE))?;
+                Ok(())
+            },
+        )
+    }
+}
+
+#[test]
+fn gate_combination_powers() -> Result<(), Error> {
+    use halo2curves::bn256::Fr;
+
+    use crate::polynomial::Expression as PolyExpression;
+
+    let S =
+        CircuitRunner::<Fr, _>::new(4, ThreeGatesCircuit, vec![]).try_collect_plonk_structure()?;
+
+    assert_eq!(S.num_gates(), 3);
+    let powers = S.gate_combination_powers();
+    assert_eq!(
+        powers.iter().copied().sorted().collect::<Vec<_>>(),
+        [0, 1, 2]
+    );
+
+    // without lookups the combination challenge is the first one
+    let y = Fr::from(7);
+    let evaluate = |expr: &PolyExpression<Fr>| {
+        expr.evaluate(
+            &|constant| constant,
+            &|query| Fr::from(query.index as u64 + 2),
+            &|_challenge| y,
+            &|value| -value,
+            &|lhs, rhs| lhs + rhs,
+            &|lhs, rhs| lhs * rhs,
+            &|value, scalar| value * scalar,
+        )
+    };
+
+    // powers are given in `create_gate` order, gates are sorted
+    let recombined = S
+        .gates
+        .iter()
+        .zip_eq(&S.gate_indices)
+        .map(|(gate, index)| evaluate(gate) * y.pow([powers[*index] as u64]))
+        .sum::<Fr>();
+
+    assert_ne!(recombined, Fr::ZERO);
+    assert_eq!(
+        recombined,
+        evaluate(S.custom_gates_lookup_compressed.compressed())
+    );
+
+    Ok(())
+}
+
+/// Copies first two public inputs `a` & `b` into advice & checks `a^2 = b`
+struct SquareInstanceCircuit;
+
+impl<F: PrimeField> Circuit<F> for SquareInstanceCircuit {
+    type Config = (Selector, Column<Advice>, Column<Advice>, Column<Instance>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let s = meta.selector();
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(a);
+        meta.enable_equality(b);
+        meta.enable_equality(instance);
+
+        meta.create_gate("a^2 = b", |meta| {
+            let s = meta.query_selector(s);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            vec![s * (a.clone() * a - b)]
+        });
+
+        (s, a, b, instance)
+    }
+
+    fn synthesize(
+        &self,
+        (s, a, b, instance): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "square",
+            |mut region| {
+                s.enable(&mut region, 0)?;
+                region.assign_advice_from_instance(|| "a", instance, 0, a, 0)?;
+                region.assign_advice_from_instance(|| "b", instance, 1, b, 0)?;
+                Ok(())
+            },
+        )
+    }
+}
+
+#[test]
+fn instance_preserved() -> Result<(), Error> {
+    use halo2curves::{
+        bn256::{Fr, G1Affine},
+        CurveAffine,
+    };
+
+    const K: u32 = 4;
+    let ck = CommitmentKey::<G1Affine>::setup(K as usize, b"instance_preserved");
+    let new_ro = create_ro::<<G1Affine as CurveAffine>::Base, T, 2, 4, 3>;
+
+    let public_inputs = vec![Fr::from(3), Fr::from(9)];
+    let runner = CircuitRunner::<Fr, _>::new(K, SquareInstanceCircuit, public_inputs.clone());
+
+    let S = runner.try_collect_plonk_structure()?;
+    let snapshot = runner.try_collect_snapshot()?;
+    assert_eq!(snapshot.instance(), public_inputs.as_slice());
+    assert_eq!(snapshot.advice()[0][0], Fr::from(3));
+    assert_eq!(snapshot.advice()[1][0], Fr::from(9));
+
+    let witness = runner.try_collect_witness()?;
+    let (u, w) = S
+        .run_sps_protocol(
+            &ck,
+            &public_inputs,
+            &witness,
+            &mut new_ro(),
+            S.num_challenges,
+        )
+        .unwrap();
+    assert_eq!(S.is_sat(&ck, &mut new_ro(), &u, &w), Ok(()));
+
+    // public inputs don't fit into table
+    let runner = CircuitRunner::<Fr, _>::new(K, SquareInstanceCircuit, vec![Fr::ONE; 17]);
+    assert!(matches!(
+        runner.try_collect_witness(),
+        Err(Error::InstanceTooLarge)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn serde_round_trip() -> Result<(), Error> {
+    use halo2curves::{
+        bn256::{Fr, G1Affine},
+        CurveAffine,
+    };
+
+    use crate::artifact;
+
+    const K: u32 = 4;
+    let output = Fr::from(45);
+    let runner = CircuitRunner::new(
+        K,
+        TestCircuit::new((1..10).map(Fr::from).collect(), Fr::ONE),
+        vec![output],
+    );
+    let S = runner.try_collect_plonk_structure()?;
+    let witness = runner.try_collect_witness()?;
+
+    let restored: PlonkStructure<Fr> = artifact::decode(&artifact::encode(&S).unwrap()).unwrap();
+    assert!(restored.same_circuit(&S));
+    assert_eq!(restored.num_challenges, S.num_challenges);
+    assert_eq!(
+        restored.custom_gates_lookup_compressed.compressed(),
+        S.custom_gates_lookup_compressed.compressed()
+    );
+
+    let ck = CommitmentKey::<G1Affine>::setup(K as usize + 3, b"serde_round_trip");
+    let new_ro = create_ro::<<G1Affine as CurveAffine>::Base, T, 2, 4, 3>;
+    let (u, w) = S
+        .run_sps_protocol(&ck, &[output], &witness, &mut new_ro(), S.num_challenges)
+        .unwrap();
+
+    let u: PlonkInstance<G1Affine> =
+        artifact::decode_for(&artifact::encode(&u).unwrap(), &restored).unwrap();
+    let w: PlonkWitness<Fr> =
+        artifact::decode_for(&artifact::encode(&w).unwrap(), &restored).unwrap();
+    assert_eq!(restored.is_sat(&ck, &mut new_ro(), &u, &w), Ok(()));
+
+    // malformed structures are rejected on decoding
+    let decode = |S: &PlonkStructure<Fr>| {
+        artifact::decode::<PlonkStructure<Fr>>(&artifact::encode(S).unwrap())
+    };
+
+    let mut short_fixed = S.clone();
+    short_fixed.fixed_columns[0].pop();
+    assert!(decode(&short_fixed).is_err());
+
+    let mut short_selector = S.clone();
+    short_selector.selectors.push(vec![false; 1]);
+    assert!(decode(&short_selector).is_err());
+
+    let mut out_of_table = S.clone();
+    let table_len = S.num_io + (1 << S.k) * S.num_advice_columns;
+    out_of_table
+        .permutation_matrix
+        .push((0, table_len, Fr::ONE));
+    assert!(decode(&out_of_table).is_err());
+
+    let mut wrong_rounds = S.clone();
+    wrong_rounds.round_sizes[0] += 1;
+    assert!(decode(&wrong_rounds).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn structure_digest() {
+    use halo2curves::bn256::{Fr, G1Affine};
+
+    let digest = |k: u32, circuit: TwoGatesCircuit<Fr>| {
+        CircuitRunner::new(k, circuit, vec![])
+            .try_collect_plonk_structure()
+            .unwrap()
+            .digest::<G1Affine>()
+            .unwrap()
+    };
+    let circuit = |a: u64, b: u64| TwoGatesCircuit {
+        a: Fr::from(a),
+        b: Fr::from(b),
+    };
+
+    // witness isn't a part of structure
+    assert_eq!(digest(4, circuit(3, 3)), digest(4, circuit(3, 4)));
+    assert_ne!(digest(4, circuit(3, 3)), digest(5, circuit(3, 3)));
+
+    let fill = CircuitRunner::new(4, FillCircuit::<Fr> { advice: vec![] }, vec![])
+        .try_collect_plonk_structure()
+        .unwrap()
+        .digest::<G1Affine>()
+        .unwrap();
+    assert_ne!(digest(4, circuit(3, 3)), fill);
+}
+
+/// Copies constant `CONSTANT` into advice cell & constrains `advice` cell to be equal it
+struct ConstantCopyCircuit<const CONSTANT: u64> {
+    advice: u64,
+}
+
+impl<F: PrimeField, const CONSTANT: u64> Circuit<F> for ConstantCopyCircuit<CONSTANT> {
+    type Config = Column<Advice>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self { advice: 0 }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+
+        let a = meta.advice_column();
+        meta.enable_equality(a);
+        a
+    }
+
+    fn synthesize(&self, a: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "a",
+            |mut region| {
+                region.assign_advice_from_constant(|| "from", a, 0, F::from(CONSTANT))?;
+
+                let cell =
+                    region.assign_advice(|| "a", a, 1, || Value::known(F::from(self.advice)))?;
+                region.constrain_constant(cell.cell(), F::from(CONSTANT))?;
+
+                Ok(())
+            },
+        )
+    }
+}
+
+#[test]
+fn constants() -> Result<(), Error> {
+    use halo2curves::{
+        bn256::{Fr, G1Affine},
+        CurveAffine,
+    };
+
+    const K: u32 = 4;
+
+    let is_sat = |advice: u64| -> Result<Result<(), crate::plonk::Error>, Error> {
+        let runner = CircuitRunner::<Fr, _>::new(K, ConstantCopyCircuit::<42> { advice }, vec![]);
+        let S = runner.try_collect_plonk_structure()?;
+        let witness = runner.try_collect_witness()?;
+
+        let ck = CommitmentKey::<G1Affine>::setup(K as usize + 3, b"constants");
+        let new_ro = create_ro::<<G1Affine as CurveAffine>::Base, T, 2, 4, 3>;
+        let (u, w) = S
+            .run_sps_protocol(&ck, &[], &witness, &mut new_ro(), S.num_challenges)
+            .unwrap();
+
+        Ok(S.is_sat(&ck, &mut new_ro(), &u, &w))
+    };
+
+    assert_eq!(is_sat(42)?, Ok(()));
+    assert!(is_sat(43)?.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn reassign_witness() -> Result<(), Error> {
+    use halo2curves::pasta::Fp;
+
+    const K: u32 = 4;
+    let circuit = |from: u64| TestCircuit::new((from..from + 9).map(Fp::from).collect(), Fp::ONE);
+    let output = |from: u64| vec![Fp::from((from..from + 9).sum::<u64>())];
+
+    let mut runner = CircuitRunner::new(K, circuit(1), output(1));
+    let S = runner.try_collect_plonk_structure()?;
+    let first = runner.try_collect_witness()?;
+
+    let second = runner.reassign_witness(circuit(2), output(2))?;
+    assert_ne!(first, second);
+    assert_eq!(
+        second,
+        CircuitRunner::new(K, circuit(2), output(2)).try_collect_witness()?
+    );
+    assert!(S.same_circuit(&runner.try_collect_plonk_structure()?));
+
+    Ok(())
+}
+
+/// Gate `s * a[ROTATION] = 0`, enabled on the first row
+struct RotationCircuit<const ROTATION: i32>;
+
+impl<F: PrimeField, const ROTATION: i32> Circuit<F> for RotationCircuit<ROTATION> {
+    type Config = (Selector, Column<Advice>);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let s = meta.selector();
+        let a = meta.advice_column();
+        meta.create_gate("rotation", |meta| {
+            let s = meta.query_selector(s);
+            let a = meta.query_advice(a, Rotation(ROTATION));
+            vec![s * a]
+        });
+        (s, a)
+    }
+
+    fn synthesize(
+        &self,
+        (s, a): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "a",
+            |mut region| {
+                s.enable(&mut region, 0)?;
+                region.assign_advice(|| "a", a, 0, || Value::known(F::ZERO))?;
+                Ok(())
+            },
+        )
+    }
+}
+
+#[test]
+fn rotation_bounds() {
+    use halo2curves::pasta::Fp;
+
+    const K: u32 = 4;
+
+    assert!(
+        CircuitRunner::<Fp, _>::new(K, RotationCircuit::<-1>, vec![])
+            .try_collect_plonk_structure()
+            .is_ok()
+    );
+    assert!(
+        CircuitRunner::<Fp, _>::new(K, RotationCircuit::<15>, vec![])
+            .try_collect_plonk_structure_with_max_degree(None)
+            .is_ok()
+    );
+
+    assert!(matches!(
+        CircuitRunner::<Fp, _>::new(K, RotationCircuit::<16>, vec![])
+            .try_collect_plonk_structure_with_max_degree(None),
+        Err(StructureError::RotationOutOfBounds {
+            rotation: 16,
+            num_rows: 16
+        })
+    ));
+    assert!(matches!(
+        CircuitRunner::<Fp, _>::new(K, RotationCircuit::<-16>, vec![])
+            .try_collect_plonk_structure(),
+        Err(Error::Synthesis)
+    ));
+}
+
+/// Gate `a - instance = 0`, which queries instance column directly
+struct InstanceGateCircuit {
+    advice: [u64; 2],
+}
+
+impl<F: PrimeField> Circuit<F> for InstanceGateCircuit {
+    type Config = Column<Advice>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self { advice: [0; 2] }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let a = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.create_gate("instance", |meta| {
+            vec![
+                meta.query_advice(a, Rotation::cur())
+                    - meta.query_instance(instance, Rotation::cur()),
+            ]
+        });
+        a
+    }
+
+    fn synthesize(&self, a: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_region(
+            || "advice",
+            |mut region| {
+                for (offset, value) in self.advice.iter().enumerate() {
+                    region.assign_advice(|| "a", a, offset, || Value::known(F::from(*value)))?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[test]
+fn instance_query() -> Result<(), Error> {
+    use halo2curves::{
+        bn256::{Fr, G1Affine},
+        CurveAffine,
+    };
+
+    use std::collections::BTreeSet;
+
+    use crate::polynomial::{expression::QueryIndexContext, ColumnIndex, Query, QueryType};
+
+    const K: u32 = 4;
+    let ck = CommitmentKey::<G1Affine>::setup(K as usize, b"instance_query");
+    let new_ro = create_ro::<<G1Affine as CurveAffine>::Base, T, 2, 4, 3>;
+
+    let is_sat = |advice: [u64; 2], instance: &[u64]| {
+        let instance = instance.iter().copied().map(Fr::from).collect::<Vec<_>>();
+        let runner = CircuitRunner::new(K, InstanceGateCircuit { advice }, instance.clone());
+        let S = runner.try_collect_plonk_structure()?;
+        let witness = runner.try_collect_witness()?;
+        let (u, w) = S
+            .run_sps_protocol(&ck, &instance, &witness, &mut new_ro(), S.num_challenges)
+            .unwrap();
+
+        let verdict = S.is_sat(&ck, &mut new_ro(), &u, &w);
+        Ok::<_, Error>((S, verdict))
+    };
+
+    let (S, verdict) = is_sat([5, 7], &[5, 7])?;
+    assert_eq!(verdict, Ok(()));
+
+    // instance is queried as its own folded variable, not as advice
+    let ctx = QueryIndexContext::from(&S);
+    let mut queries = BTreeSet::new();
+    S.gates[0].poly_set(&mut queries);
+    let subtypes = queries
+        .into_iter()
+        .map(|query| match query {
+            ColumnIndex::Polynominal { column_index, .. } => Query {
+                index: column_index,
+                rotation: Rotation(0),
+            }
+            .subtype(&ctx),
+            ColumnIndex::Challenge { .. } => unreachable!("gate has no challenges"),
+        })
+        .collect::<Vec<_>>();
+    assert!(matches!(
+        subtypes[..],
+        [QueryType::Advice, QueryType::Instance]
+    ));
+
+    // the second public input doesn't match advice
+    assert!(is_sat([5, 7], &[5, 8])?.1.is_err());
+    // rows after public inputs are read as zero
+    assert_eq!(is_sat([5, 0], &[5])?.1, Ok(()));
+    assert!(is_sat([5, 7], &[5])?.1.is_err());
+
+    Ok(())
+}
+
+/// Lookup of instance column into table, lookups can't query instance
+struct InstanceLookupCircuit;
+
+impl<F: PrimeField> Circuit<F> for InstanceLookupCircuit {
+    type Config = ();
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let table = meta.lookup_table_column();
+        let instance = meta.instance_column();
+        meta.lookup("instance", |meta| {
+            vec![(meta.query_instance(instance, Rotation::cur()), table)]
+        });
+    }
+
+    fn synthesize(&self, _: Self::Config, _: impl Layouter<F>) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[test]
+fn unsupported_query() {
+    use halo2curves::pasta::Fp;
+
+    let runner = CircuitRunner::<Fp, _>::new(4, InstanceLookupCircuit, vec![Fp::ONE]);
+
+    assert!(matches!(
+        runner.try_collect_plonk_structure_with_max_degree(None),
+        Err(StructureError::UnsupportedQuery { name, kind: "instance" }) if name == "lookup 0"
+    ));
+    assert!(matches!(
+        runner.try_collect_plonk_structure(),
+        Err(Error::Synthesis)
+    ));
+}
+
+#[test]
+fn unsupported_query_metainfo() {
+    use halo2_proofs::plonk::FirstPhase;
+    use halo2curves::pasta::Fp;
+
+    use crate::polynomial::Halo2ExprError;
+
+    let mut cs = ConstraintSystem::<Fp>::default();
+    InstanceLookupCircuit::configure(&mut cs);
+    assert!(matches!(
+        ConstraintSystemMetainfo::build(4, &cs),
+        Err(Halo2ExprError::UnsupportedQuery { kind: "instance" })
+    ));
+
+    let mut cs = ConstraintSystem::<Fp>::default();
+    let a = cs.advice_column();
+    let challenge = cs.challenge_usable_after(FirstPhase);
+    cs.create_gate("challenge", |meta| {
+        vec![meta.query_advice(a, Rotation::cur()) * meta.query_challenge(challenge)]
+    });
+    assert!(matches!(
+        ConstraintSystemMetainfo::build(4, &cs),
+        Err(Halo2ExprError::UnsupportedQuery { kind: "challenge" })
+    ));
+}
@@ -47,7 +47,10 @@ impl<F: PrimeField> Assignment<F> for WitnessCollector<F> {
     }
 
     fn query_instance(&self, column: Column<Instance>, row: usize) -> Result<Value<F>, Error> {
-        assert!(column.index() == 0); // require just single instance
+        // require just single instance column
+        if column.index() != 0 {
+            return Err(Error::InvalidInstances);
+        }
         self.instance
             .get(row)
             .map(|v| Value::known(*v))
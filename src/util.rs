@@ -73,10 +73,16 @@ pub fn fe_to_big<F: PrimeField>(fe: &F) -> BigUint {
     BigUint::from_bytes_le(fe.to_repr().as_ref())
 }
 
+#[cfg(not(feature = "ct"))]
 pub fn fe_to_fe<F1: PrimeField, F2: PrimeField>(fe: &F1) -> Option<F2> {
     fe_from_big(fe_to_big(fe) % modulus::<F2>())
 }
 
+#[cfg(feature = "ct")]
+pub fn fe_to_fe<F1: PrimeField, F2: PrimeField>(fe: &F1) -> Option<F2> {
+    Some(ct::fe_to_fe(fe))
+}
+
 pub fn fe_to_fe_safe<F1: PrimeField, F2: PrimeField>(fe: &F1) -> Option<F2> {
     let bn1 = fe_to_big(fe);
     let bn2 = modulus::<F2>();
@@ -88,39 +94,115 @@ pub fn fe_to_fe_safe<F1: PrimeField, F2: PrimeField>(fe: &F1) -> Option<F2> {
     }
 }
 
-fn invert<F: Field>(poly: &[Assigned<F>], inv_denoms: impl ExactSizeIterator<Item = F>) -> Vec<F> {
-    assert_eq!(inv_denoms.len(), poly.len());
-    poly.iter()
-        .zip(inv_denoms)
-        .map(|(a, inv_den)| a.numerator() * inv_den)
-        .collect()
+/// Turns inverted denominators into values of cells in place, so evaluated matrix is the only
+/// matrix of `F` allocated while columns are collected
+fn multiply_by_numerators<F: Field>(assigned: &[Vec<Assigned<F>>], inv_denoms: &mut [Vec<F>]) {
+    inv_denoms
+        .par_iter_mut()
+        .zip(assigned.par_iter())
+        .for_each(|(inv_denoms, poly)| {
+            assert_eq!(inv_denoms.len(), poly.len());
+            inv_denoms
+                .iter_mut()
+                .zip(poly.iter())
+                .for_each(|(inv_den, a)| *inv_den = a.numerator() * *inv_den)
+        });
+}
+
+/// Evaluates matrix of [`Assigned`] cells into field elements
+///
+/// The only place, where [`Assigned`] columns (advice & fixed) are turned into `F`, so all
+/// consumers evaluate rationals the same way: `numerator / denominator`, where cells with zero
+/// denominator are mapped to zero, same as [`Assigned::evaluate`]
+#[cfg(not(feature = "ct"))]
+pub(crate) fn evaluate_assigned_matrix<F: Field>(assigned: &[Vec<Assigned<F>>]) -> Vec<Vec<F>> {
+    batch_invert_assigned_vartime(assigned)
+}
+
+/// Evaluates matrix of [`Assigned`] cells into field elements
+///
+/// Constant-time version, see [`ct::batch_invert_assigned`]
+#[cfg(feature = "ct")]
+pub(crate) fn evaluate_assigned_matrix<F: Field>(assigned: &[Vec<Assigned<F>>]) -> Vec<Vec<F>> {
+    ct::batch_invert_assigned(assigned)
 }
 
-pub(crate) fn batch_invert_assigned<F: Field>(assigned: &[Vec<Assigned<F>>]) -> Vec<Vec<F>> {
-    let mut assigned_denominators: Vec<_> = assigned
+fn batch_invert_assigned_vartime<F: Field>(assigned: &[Vec<Assigned<F>>]) -> Vec<Vec<F>> {
+    let mut values: Vec<_> = assigned
         .par_iter()
         .map(|f| {
             f.par_iter()
-                .map(|value| value.denominator())
+                .map(|value| value.denominator().unwrap_or(F::ONE))
                 .collect::<Vec<_>>()
         })
         .collect();
 
-    assigned_denominators
+    values
         .iter_mut()
-        .flat_map(|f| {
-            f.iter_mut()
+        .zip(assigned.iter())
+        .flat_map(|(values, f)| {
+            values
+                .iter_mut()
+                .zip(f.iter())
                 // If the denominator is trivial, we can skip it, reducing the
                 // size of the batch inversion.
-                .filter_map(|d| d.as_mut())
+                .filter_map(|(d, value)| value.denominator().is_some().then_some(d))
         })
         .batch_invert();
 
-    assigned
-        .iter()
-        .zip(assigned_denominators)
-        .map(|(poly, inv_denoms)| invert(poly, inv_denoms.into_iter().map(|d| d.unwrap_or(F::ONE))))
-        .collect()
+    multiply_by_numerators(assigned, &mut values);
+    values
+}
+
+/// Constant-time versions of field conversion and batch inversion
+///
+/// Enabled by `ct` feature, produce the same values as default versions, but without
+/// data-dependent branches & early exits on zero
+#[cfg(feature = "ct")]
+pub(crate) mod ct {
+    use ff::{BatchInvert, Field, PrimeField};
+    use halo2_proofs::plonk::Assigned;
+    use rayon::prelude::*;
+    use subtle::{Choice, ConditionallySelectable};
+
+    /// Rebuilds `fe` in `F2` bit by bit, iterating over whole little-endian repr of `F1`,
+    /// so result is reduced by modulus of `F2`
+    pub fn fe_to_fe<F1: PrimeField, F2: PrimeField>(fe: &F1) -> F2 {
+        let repr = fe.to_repr();
+
+        let mut power_of_two = F2::ONE;
+        let mut result = F2::ZERO;
+        for byte in repr.as_ref() {
+            for i in 0..8 {
+                let bit = Choice::from((byte >> i) & 1);
+                result += F2::conditional_select(&F2::ZERO, &power_of_two, bit);
+                power_of_two = power_of_two.double();
+            }
+        }
+
+        result
+    }
+
+    /// Inverts all denominators in one batch, including trivial ones, zero denominators are
+    /// mapped to zero by [`BatchInvert`] without branching
+    pub fn batch_invert_assigned<F: Field>(assigned: &[Vec<Assigned<F>>]) -> Vec<Vec<F>> {
+        let mut inv_denominators = assigned
+            .par_iter()
+            .map(|f| {
+                f.par_iter()
+                    .map(|value| value.denominator().unwrap_or(F::ONE))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        inv_denominators
+            .iter_mut()
+            .flat_map(|f| f.iter_mut())
+            .batch_invert();
+
+        super::multiply_by_numerators(assigned, &mut inv_denominators);
+        inv_denominators
+    }
 }
 
 pub fn parallelize_iter<I, T, F>(iter: I, f: F)
@@ -153,6 +235,20 @@ where
     }
 }
 
+/// Runs `f` in `pool`, so all rayon parallel sections inside of `f` (satisfiability checks,
+/// folding, [`parallelize`], etc.) are bounded by its threads
+///
+/// With `None` it's just `f()` on the global rayon pool, as everywhere else in the crate
+pub fn in_thread_pool<R: Send>(
+    pool: Option<&rayon::ThreadPool>,
+    f: impl FnOnce() -> R + Send,
+) -> R {
+    match pool {
+        Some(pool) => pool.install(f),
+        None => f(),
+    }
+}
+
 pub(crate) fn trim_leading_zeros(hex: String) -> String {
     let without_prefix = hex.as_str().trim_start_matches("0x");
     let trimmed = without_prefix.trim_start_matches('0');
@@ -263,6 +359,89 @@ mod tests {
             [fp(1), fp(2), fp(3)]
         );
     }
+
+    #[test]
+    fn evaluate_assigned_matrix_zero_denominators() {
+        let assigned = vec![
+            vec![
+                Assigned::Rational(fp(6), fp(3)),
+                Assigned::Rational(fp(6), Fp::zero()),
+                Assigned::Trivial(fp(5)),
+            ],
+            vec![Assigned::Zero, Assigned::Rational(fp(1), fp(2))],
+        ];
+
+        assert_eq!(
+            evaluate_assigned_matrix(&assigned),
+            vec![
+                vec![fp(2), Fp::zero(), fp(5)],
+                vec![Fp::zero(), fp(2).invert().unwrap()],
+            ]
+        );
+    }
+
+    #[test]
+    fn evaluate_assigned_matrix_random() {
+        use ff::Field;
+        use rand::Rng;
+
+        let random_cell = |rng: &mut rand::rngs::ThreadRng| match rng.gen_range(0..5) {
+            0 => Assigned::Zero,
+            1 => Assigned::Trivial(Fp::random(&mut *rng)),
+            2 => Assigned::Rational(Fp::random(&mut *rng), Fp::ZERO),
+            3 => Assigned::Rational(Fp::ZERO, Fp::random(&mut *rng)),
+            _ => Assigned::Rational(Fp::random(&mut *rng), Fp::random(&mut *rng)),
+        };
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..10 {
+            let assigned = (0..rng.gen_range(0..5))
+                .map(|_| {
+                    (0..rng.gen_range(0..64))
+                        .map(|_| random_cell(&mut rng))
+                        .collect()
+                })
+                .collect::<Vec<Vec<_>>>();
+
+            let expected = assigned
+                .iter()
+                .map(|column| {
+                    column
+                        .iter()
+                        .map(|cell| cell.evaluate())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(evaluate_assigned_matrix(&assigned), expected);
+        }
+    }
+
+    #[cfg(feature = "ct")]
+    #[test]
+    fn ct_same_as_vartime() {
+        use halo2curves::pasta::Fq;
+
+        let assigned = vec![
+            vec![
+                Assigned::Rational(fp(6), fp(3)),
+                Assigned::Rational(fp(6), Fp::zero()),
+                Assigned::Trivial(fp(5)),
+            ],
+            vec![Assigned::Zero, Assigned::Rational(fp(1), fp(2))],
+        ];
+        assert_eq!(
+            ct::batch_invert_assigned(&assigned),
+            batch_invert_assigned_vartime(&assigned)
+        );
+
+        for fe in [Fq::zero(), Fq::one(), Fq::from(u64::MAX), -Fq::one()] {
+            assert_eq!(
+                Some(ct::fe_to_fe::<Fq, Fp>(&fe)),
+                fe_from_big::<Fp>(fe_to_big(&fe) % modulus::<Fp>())
+            );
+        }
+    }
 }
 
 pub(crate) fn create_ro<F, const T: usize, const RATE: usize, const R_F: usize, const R_P: usize>(
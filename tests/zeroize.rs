@@ -0,0 +1,76 @@
+//! Checks that witness buffers are wiped right before they're freed
+//!
+//! Lives in its own test binary, so the watching `#[global_allocator]` doesn't affect any
+//! other test
+#![cfg(feature = "zeroize")]
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    slice,
+    sync::atomic::{AtomicU8, AtomicUsize, Ordering},
+};
+
+use halo2curves::bn256::Fr;
+use sirius::plonk::PlonkWitness;
+
+const NOT_FREED: u8 = 0;
+const WIPED: u8 = 1;
+const NOT_WIPED: u8 = 2;
+
+/// Address of buffer, which contents are inspected right before it's freed
+static WATCHED: AtomicUsize = AtomicUsize::new(0);
+static WATCHED_STATE: AtomicU8 = AtomicU8::new(NOT_FREED);
+
+struct WatchingAllocator;
+
+unsafe impl GlobalAlloc for WatchingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if WATCHED
+            .compare_exchange(ptr as usize, 0, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+        {
+            let wiped = slice::from_raw_parts(ptr, layout.size())
+                .iter()
+                .all(|byte| *byte == 0);
+            WATCHED_STATE.store(if wiped { WIPED } else { NOT_WIPED }, Ordering::SeqCst);
+        }
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: WatchingAllocator = WatchingAllocator;
+
+fn freed_state<T>(value: T, buffer: *const Fr) -> u8 {
+    WATCHED_STATE.store(NOT_FREED, Ordering::SeqCst);
+    WATCHED.store(buffer as usize, Ordering::SeqCst);
+    drop(value);
+    WATCHED_STATE.load(Ordering::SeqCst)
+}
+
+fn witness() -> PlonkWitness<Fr> {
+    let mut w = PlonkWitness::new(&[16]);
+    w.W_mut()[0].fill(Fr::from(5));
+    w
+}
+
+#[test]
+fn wiped_on_drop() {
+    let mut w = witness();
+    let buffer = w.W_mut()[0].as_ptr();
+    assert_eq!(freed_state(w, buffer), WIPED);
+
+    let mut w = witness().into_relax(4);
+    *w.E_mut().get_mut(3).unwrap() = Fr::from(7);
+    let buffer = w.E_mut().chunks().next().unwrap().1.as_ptr();
+    assert_eq!(freed_state(w, buffer), WIPED);
+
+    // the allocator itself sees non-wiped memory
+    let values = vec![Fr::from(5); 16];
+    let buffer = values.as_ptr();
+    assert_eq!(freed_state(values, buffer), NOT_WIPED);
+}